@@ -14,6 +14,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         api_endpoint: "https://api.anthropic.com/v1/messages".to_string(),
         max_tokens: 4096,
         temperature: 0.1,
+        api_key_env: "ANTHROPIC_API_KEY".to_string(),
+        cache_dir: None,
     };
 
     println!("🔍 Rust2Mojo LLM Comparison Demo");
@@ -2,17 +2,18 @@
 //!
 //! This module contains intermediate representations used during compilation from Rust to Mojo.
 
+use crate::error::SourceLocation;
 use serde::{Deserialize, Serialize};
 
 /// Intermediate representation for the compiler
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CompilationUnit {
     pub items: Vec<Item>,
     pub metadata: CompilationMetadata,
 }
 
 /// Metadata about the compilation unit
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CompilationMetadata {
     pub source_file: Option<String>,
     pub rust_edition: String,
@@ -20,7 +21,7 @@ pub struct CompilationMetadata {
 }
 
 /// Top-level items in the compilation unit
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Item {
     Function(Function),
     Struct(Struct),
@@ -31,14 +32,17 @@ pub enum Item {
     Const(Const),
     Static(Static),
     Type(TypeAlias),
+    Trait(Trait),
 }
 
 /// Function representation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Function {
     pub name: String,
     pub visibility: Visibility,
     pub generics: Vec<Generic>,
+    /// Lifetime parameters, without the leading `'`; kept apart from type generics
+    pub lifetimes: Vec<String>,
     pub parameters: Vec<Parameter>,
     pub return_type: Option<Type>,
     pub body: Vec<Statement>,
@@ -46,7 +50,7 @@ pub struct Function {
 }
 
 /// Struct representation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Struct {
     pub name: String,
     pub visibility: Visibility,
@@ -56,7 +60,7 @@ pub struct Struct {
 }
 
 /// Enum representation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Enum {
     pub name: String,
     pub visibility: Visibility,
@@ -66,7 +70,7 @@ pub struct Enum {
 }
 
 /// Implementation block
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Impl {
     pub target_type: Type,
     pub trait_: Option<Type>,
@@ -74,15 +78,61 @@ pub struct Impl {
     pub items: Vec<ImplItem>,
 }
 
-/// Use statement
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Trait definition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trait {
+    pub name: String,
+    pub visibility: Visibility,
+    pub generics: Vec<Generic>,
+    pub items: Vec<TraitItem>,
+    pub attributes: Vec<Attribute>,
+}
+
+/// Trait item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TraitItem {
+    Function(TraitFunction),
+    /// Associated type such as `type Item;`, with its bounds
+    Type {
+        name: String,
+        bounds: Vec<Type>,
+    },
+}
+
+/// Trait method signature, with its default implementation if one is provided
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraitFunction {
+    pub name: String,
+    pub generics: Vec<Generic>,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<Type>,
+    pub default_body: Option<Vec<Statement>>,
+    pub attributes: Vec<Attribute>,
+}
+
+/// Use statement, flattened to one entry per imported name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Use {
-    pub path: String,
+    pub imports: Vec<UseImport>,
     pub visibility: Visibility,
 }
 
+/// One name brought into scope by a use statement
+///
+/// `use a::b::{c, d as e}` has two: `c` and `d` (as `e`) from `["a", "b"]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UseImport {
+    /// Path of the module the name is imported from; may start with `crate`,
+    /// `self` or `super`
+    pub module: Vec<String>,
+    /// Imported name, or `*` for a glob import
+    pub name: String,
+    /// Local name given with `as`
+    pub alias: Option<String>,
+}
+
 /// Module
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Module {
     pub name: String,
     pub visibility: Visibility,
@@ -90,7 +140,7 @@ pub struct Module {
 }
 
 /// Constant
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Const {
     pub name: String,
     pub visibility: Visibility,
@@ -99,7 +149,7 @@ pub struct Const {
 }
 
 /// Static variable
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Static {
     pub name: String,
     pub visibility: Visibility,
@@ -109,7 +159,7 @@ pub struct Static {
 }
 
 /// Type alias
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TypeAlias {
     pub name: String,
     pub visibility: Visibility,
@@ -118,7 +168,7 @@ pub struct TypeAlias {
 }
 
 /// Visibility modifiers
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Visibility {
     Public,
     Private,
@@ -128,14 +178,14 @@ pub enum Visibility {
 }
 
 /// Generic parameter
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Generic {
     pub name: String,
     pub bounds: Vec<Type>,
 }
 
 /// Function parameter
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Parameter {
     pub name: String,
     pub type_: Type,
@@ -143,7 +193,7 @@ pub struct Parameter {
 }
 
 /// Struct field
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Field {
     pub name: String,
     pub visibility: Visibility,
@@ -151,14 +201,14 @@ pub struct Field {
 }
 
 /// Enum variant
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Variant {
     pub name: String,
     pub data: VariantData,
 }
 
 /// Variant data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum VariantData {
     Unit,
     Tuple(Vec<Type>),
@@ -166,7 +216,7 @@ pub enum VariantData {
 }
 
 /// Implementation item
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ImplItem {
     Function(Function),
     Const(Const),
@@ -174,11 +224,13 @@ pub enum ImplItem {
 }
 
 /// Type representation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Type {
     Path(String),
     Reference {
         mutable: bool,
+        /// Named lifetime, without the leading `'`; `None` when elided
+        lifetime: Option<String>,
         inner: Box<Type>,
     },
     Pointer {
@@ -195,12 +247,18 @@ pub enum Type {
         params: Vec<Type>,
         return_: Box<Type>,
     },
-    Generic(String),
+    /// A path type with generic arguments, e.g. `Vec<i32>`
+    Generic {
+        name: String,
+        args: Vec<Type>,
+    },
     Unit,
+    /// The never type `!` of functions that do not return
+    Never,
 }
 
 /// Statement representation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Statement {
     Expression(Expression),
     Let {
@@ -209,6 +267,12 @@ pub enum Statement {
         type_: Option<Type>,
         value: Option<Expression>,
     },
+    /// `let` with a destructuring pattern, e.g. `let (a, b) = pair;`
+    LetPattern {
+        pattern: Pattern,
+        type_: Option<Type>,
+        value: Expression,
+    },
     Return(Option<Expression>),
     If {
         condition: Expression,
@@ -218,21 +282,34 @@ pub enum Statement {
     While {
         condition: Expression,
         body: Vec<Statement>,
+        label: Option<String>,
+    },
+    Loop {
+        body: Vec<Statement>,
+        label: Option<String>,
     },
     For {
-        pattern: String,
+        pattern: Pattern,
         iterator: Expression,
         body: Vec<Statement>,
+        label: Option<String>,
     },
     Match {
         expr: Expression,
         arms: Vec<MatchArm>,
     },
     Block(Vec<Statement>),
+    Raise(Expression),
+    /// Unrecoverable failure from `panic!` and friends, carrying the message
+    Abort(Expression),
+    /// `break`, with an optional loop label
+    Break(Option<String>),
+    /// `continue`, with an optional loop label
+    Continue(Option<String>),
 }
 
 /// Expression representation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Expression {
     Literal(Literal),
     Identifier(String),
@@ -244,6 +321,7 @@ pub enum Expression {
     MethodCall {
         receiver: Box<Expression>,
         method: String,
+        turbofish: Vec<Type>,
         args: Vec<Expression>,
     },
     FieldAccess {
@@ -274,25 +352,54 @@ pub enum Expression {
     Dereference(Box<Expression>),
     Block(Vec<Statement>),
     Array(Vec<Expression>),
+    Repeat {
+        value: Box<Expression>,
+        count: Box<Expression>,
+    },
     Tuple(Vec<Expression>),
+    Range {
+        start: Option<Box<Expression>>,
+        end: Option<Box<Expression>>,
+        inclusive: bool,
+    },
     Struct {
         name: String,
         fields: Vec<(String, Expression)>,
     },
+    /// The `?` operator applied to a `Result` or `Option`
+    Try(Box<Expression>),
+    /// A closure such as `|x: i32| x * 2`; captures are not modelled
+    Closure {
+        params: Vec<Parameter>,
+        body: Box<Expression>,
+    },
+    /// Placeholder for an expression that could not be converted
+    Unsupported {
+        /// Description of the construct, e.g. ``macro `assert!` ``
+        construct: String,
+        /// Where the construct appears in the Rust source, when known
+        location: Option<SourceLocation>,
+    },
 }
 
 /// Literal values
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Literal {
     String(String),
     Integer(i64),
+    /// An integer with a type suffix like `255u8`, or too large for `Integer`,
+    /// kept as base-10 digits so no precision is lost
+    TypedInteger {
+        digits: String,
+        suffix: Option<String>,
+    },
     Float(f64),
     Boolean(bool),
     Char(char),
 }
 
 /// Binary operators
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BinaryOp {
     Add,
     Sub,
@@ -313,10 +420,21 @@ pub enum BinaryOp {
     Shl,
     Shr,
     Assign,
+    /// Compound assignments such as `+=`
+    AddAssign,
+    SubAssign,
+    MulAssign,
+    DivAssign,
+    ModAssign,
+    BitAndAssign,
+    BitOrAssign,
+    BitXorAssign,
+    ShlAssign,
+    ShrAssign,
 }
 
 /// Unary operators
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum UnaryOp {
     Not,
     Neg,
@@ -324,7 +442,7 @@ pub enum UnaryOp {
 }
 
 /// Match arm
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MatchArm {
     pub pattern: Pattern,
     pub guard: Option<Expression>,
@@ -332,7 +450,7 @@ pub struct MatchArm {
 }
 
 /// Pattern matching
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Pattern {
     Wildcard,
     Identifier(String),
@@ -347,10 +465,16 @@ pub enum Pattern {
         variant: String,
         fields: Vec<Pattern>,
     },
+    Or(Vec<Pattern>),
+    Range {
+        start: Option<Literal>,
+        end: Option<Literal>,
+        inclusive: bool,
+    },
 }
 
 /// Attribute
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Attribute {
     pub path: String,
     pub tokens: String,
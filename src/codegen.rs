@@ -3,48 +3,634 @@
 //! This module handles generating Mojo source code from our intermediate AST representation.
 
 use crate::ast::*;
-use crate::error::Result;
+use crate::error::{Error, Result, SourceLocation, Warning};
+use crate::pretty::use_path;
+use crate::{CompilerConfig, CompilerOptions};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::warn;
 
 /// Generate Mojo code from the intermediate AST
 pub fn generate_mojo(compilation_unit: &CompilationUnit) -> Result<String> {
-    let mut generator = MojoGenerator::new();
-    generator.generate_compilation_unit(compilation_unit)
+    generate_mojo_with_config(compilation_unit, &CompilerConfig::default())
+}
+
+/// Generate Mojo code from the intermediate AST using the given configuration
+pub fn generate_mojo_with_config(
+    compilation_unit: &CompilationUnit,
+    config: &CompilerConfig,
+) -> Result<String> {
+    generate_mojo_with_options(compilation_unit, config, &CompilerOptions::default())
+}
+
+/// Generate Mojo code from the intermediate AST using the given configuration and options
+pub fn generate_mojo_with_options(
+    compilation_unit: &CompilationUnit,
+    config: &CompilerConfig,
+    options: &CompilerOptions,
+) -> Result<String> {
+    generate_mojo_with_warnings(compilation_unit, config, options).map(|(mojo_code, _)| mojo_code)
+}
+
+/// Generate Mojo code, also returning warnings for constructs translated lossily
+pub fn generate_mojo_with_warnings(
+    compilation_unit: &CompilationUnit,
+    config: &CompilerConfig,
+    options: &CompilerOptions,
+) -> Result<(String, Vec<Warning>)> {
+    let generated = generate(compilation_unit, config, options)?;
+    Ok((generated.mojo_code, generated.warnings))
+}
+
+/// Generated code together with what it took to produce it
+pub(crate) struct Generated {
+    pub mojo_code: String,
+    pub warnings: Vec<Warning>,
+    /// Items, statements and expressions that were generated
+    pub nodes: usize,
+    /// Constructs that were emitted as placeholders, in the order they were hit
+    pub unsupported: Vec<String>,
+}
+
+pub(crate) fn generate(
+    compilation_unit: &CompilationUnit,
+    config: &CompilerConfig,
+    options: &CompilerOptions,
+) -> Result<Generated> {
+    let mut generator = MojoGenerator::new(config.clone(), options.clone());
+    let mojo_code = generator.generate_compilation_unit(compilation_unit)?;
+    let unsupported = generator.unsupported.into_inner();
+    if options.strict {
+        if let Some((construct, location)) = unsupported.first() {
+            return Err(Error::UnsupportedFeature {
+                feature: construct.clone(),
+                location: *location,
+            });
+        }
+    }
+    Ok(Generated {
+        mojo_code,
+        warnings: generator.warnings.into_inner(),
+        nodes: generator.nodes.get(),
+        unsupported: unsupported
+            .into_iter()
+            .map(|(construct, _)| construct)
+            .collect(),
+    })
+}
+
+/// Mojo argument convention for a method receiver
+fn mojo_receiver(type_: &Type) -> &'static str {
+    match type_ {
+        Type::Reference { mutable: true, .. } => "inout self",
+        Type::Reference { mutable: false, .. } => "self",
+        _ => "owned self",
+    }
+}
+
+/// Mojo argument convention for a non-`self` parameter, including a trailing space
+///
+/// Scalars are trivially copied, so taking them by value needs no convention.
+/// `&str` and `&[T]` become `StringRef` and `Span`, which are themselves
+/// non-owning views, so they are passed by value as well.
+fn mojo_convention(type_: &Type) -> &'static str {
+    match type_ {
+        Type::Reference {
+            mutable: false,
+            inner,
+            ..
+        } if **inner == Type::Path("str".to_string()) => "",
+        Type::Reference { inner, .. } if matches!(**inner, Type::Slice(_)) => "",
+        Type::Reference { mutable: true, .. } => "inout ",
+        Type::Reference { mutable: false, .. } => "borrowed ",
+        Type::Path(path) if is_scalar(path) => "",
+        // Pointers and function values are trivially copyable handles
+        Type::Pointer { .. } | Type::Function { .. } => "",
+        _ => "owned ",
+    }
+}
+
+/// The parameter a returned reference borrows from, when its lifetime says so
+///
+/// `fn f<'a>(x: &'a T) -> &'a T` ties the result to `x`, which Mojo spells as
+/// `-> ref [__lifetime_of(x)] T`. Only a lifetime shared with exactly one
+/// parameter names an origin Mojo can express; `&str` is returned as a
+/// `StringRef` value and needs none.
+fn return_origin<'p>(parameters: &'p [Parameter], return_type: &Option<Type>) -> Option<&'p str> {
+    let Some(Type::Reference {
+        lifetime: Some(lifetime),
+        inner,
+        ..
+    }) = return_type
+    else {
+        return None;
+    };
+    if **inner == Type::Path("str".to_string()) {
+        return None;
+    }
+
+    let mut borrowed = parameters.iter().filter(|param| {
+        matches!(&param.type_, Type::Reference { lifetime: Some(other), .. } if other == lifetime)
+    });
+    match (borrowed.next(), borrowed.next()) {
+        (Some(param), None) => Some(&param.name),
+        _ => None,
+    }
+}
+
+const COMPARISON_PRECEDENCE: u8 = 4;
+
+/// How tightly a binary operator binds in Mojo, which follows Python's table
+fn binary_precedence(op: &BinaryOp) -> u8 {
+    match op {
+        BinaryOp::Assign
+        | BinaryOp::AddAssign
+        | BinaryOp::SubAssign
+        | BinaryOp::MulAssign
+        | BinaryOp::DivAssign
+        | BinaryOp::ModAssign
+        | BinaryOp::BitAndAssign
+        | BinaryOp::BitOrAssign
+        | BinaryOp::BitXorAssign
+        | BinaryOp::ShlAssign
+        | BinaryOp::ShrAssign => 0,
+        BinaryOp::Or => 1,
+        BinaryOp::And => 2,
+        // `not` is 3
+        BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+            COMPARISON_PRECEDENCE
+        }
+        BinaryOp::BitOr => 5,
+        BinaryOp::BitXor => 6,
+        BinaryOp::BitAnd => 7,
+        BinaryOp::Shl | BinaryOp::Shr => 8,
+        BinaryOp::Add | BinaryOp::Sub => 9,
+        BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => 10,
+    }
+}
+
+/// How tightly an expression's generated Mojo binds; atoms bind tightest
+fn precedence(expr: &Expression) -> u8 {
+    match expr {
+        Expression::Binary { op, .. } => binary_precedence(op),
+        Expression::Unary {
+            op: UnaryOp::Not, ..
+        } => 3,
+        Expression::Unary {
+            op: UnaryOp::Neg, ..
+        } => 11,
+        // These render as their operand, so they bind as loosely as it does
+        Expression::Unary {
+            op: UnaryOp::Deref,
+            operand,
+        }
+        | Expression::Reference { expr: operand, .. }
+        | Expression::Dereference(operand)
+        | Expression::Try(operand) => precedence(operand),
+        Expression::Block(stmts) => match stmts.as_slice() {
+            [Statement::Expression(value)] => precedence(value),
+            // A conditional expression binds loosest of all
+            [Statement::If { .. }] => 0,
+            _ => u8::MAX,
+        },
+        Expression::MethodCall {
+            receiver,
+            method,
+            args,
+            ..
+        } if args.is_empty() && matches!(method.as_str(), "clone" | "into") => precedence(receiver),
+        // `HashMap::contains_key` becomes a membership test
+        Expression::MethodCall { method, args, .. }
+            if args.len() == 1 && method == "contains_key" =>
+        {
+            COMPARISON_PRECEDENCE
+        }
+        _ => u8::MAX,
+    }
+}
+
+/// Mojo spelling of a Rust module path: `a::b` is `a.b`, and paths through
+/// `self` and `super` become relative imports (`.a`, `..a`)
+fn mojo_module_path(module: &[String]) -> String {
+    let mut dots = String::new();
+    let mut segments = module;
+    if let Some((first, rest)) = segments.split_first() {
+        match first.as_str() {
+            "crate" => segments = rest,
+            "self" => {
+                dots.push('.');
+                segments = rest;
+            }
+            _ => {}
+        }
+    }
+    while let Some(("super", rest)) = segments.split_first().map(|(s, r)| (s.as_str(), r)) {
+        dots.push_str(if dots.is_empty() { ".." } else { "." });
+        segments = rest;
+    }
+    format!("{}{}", dots, segments.join("."))
+}
+
+/// Whether a return type means the function produces no value
+fn is_unit_like(ty: &Type) -> bool {
+    matches!(ty, Type::Unit | Type::Never)
+}
+
+/// Whether a Rust type name is a primitive that Mojo passes as a trivial scalar
+fn is_scalar(rust: &str) -> bool {
+    map_primitive(rust).is_some() && !matches!(rust, "char" | "str" | "String")
+}
+
+/// Heuristic for methods whose correctness relies on the borrow checker, e.g.
+/// `fn value_mut(&mut self) -> &mut T`
+fn returns_mut_ref_into_self(func: &Function) -> bool {
+    let mut_self = func.parameters.first().is_some_and(|param| {
+        param.name == "self" && matches!(param.type_, Type::Reference { mutable: true, .. })
+    });
+    mut_self
+        && matches!(
+            func.return_type,
+            Some(Type::Reference { mutable: true, .. })
+        )
+}
+
+fn is_test_function(func: &Function) -> bool {
+    func.attributes.iter().any(|attr| attr.path == "test")
+}
+
+/// Map a Rust primitive type name onto its Mojo counterpart
+///
+/// Mojo has no character type, so `char` becomes a one-character `String`.
+fn map_primitive(rust: &str) -> Option<&'static str> {
+    let mojo = match rust {
+        "i8" => "Int8",
+        "i16" => "Int16",
+        "i32" => "Int32",
+        "i64" => "Int64",
+        "i128" => "Int128",
+        "isize" => "Int",
+        "u8" => "UInt8",
+        "u16" => "UInt16",
+        "u32" => "UInt32",
+        "u64" => "UInt64",
+        "u128" => "UInt128",
+        "usize" => "UInt",
+        "f32" => "Float32",
+        "f64" => "Float64",
+        "bool" => "Bool",
+        "char" => "String",
+        "str" => "StringRef",
+        "String" => "String",
+        _ => return None,
+    };
+    Some(mojo)
+}
+
+/// Map a generic standard library container onto its Mojo counterpart
+fn mojo_generic_container(name: &str) -> &str {
+    match name.rsplit("::").next().unwrap_or(name) {
+        "Vec" => "List",
+        "Option" => "Optional",
+        "HashMap" => "Dict",
+        _ => name,
+    }
+}
+
+/// One level of Mojo indentation
+const INDENT: &str = "    ";
+
+/// The `collections` type a mapped container name refers to, if any
+fn collections_symbol(container: &str) -> Option<&'static str> {
+    match container {
+        "List" => Some("List"),
+        "Optional" => Some("Optional"),
+        "Dict" => Some("Dict"),
+        _ => None,
+    }
+}
+
+/// Attributes that are translated, or that carry no meaning worth translating
+const HANDLED_ATTRIBUTES: &[&str] = &[
+    "allow",
+    "deny",
+    "deprecated",
+    "derive",
+    "doc",
+    "forbid",
+    "test",
+    "warn",
+];
+
+/// `Vec` mutation methods with no direct Mojo `List` counterpart
+const UNMAPPED_LIST_MUTATIONS: &[&str] = &[
+    "swap_remove",
+    "retain",
+    "retain_mut",
+    "drain",
+    "dedup",
+    "dedup_by",
+    "dedup_by_key",
+    "split_off",
+    "splice",
+    "extend_from_slice",
+];
+
+/// Standard library collections whose methods translate differently in Mojo
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Collection {
+    /// Arrays, slices and `Vec`, which all become a Mojo `List`
+    List,
+    /// `HashMap`, which becomes a Mojo `Dict`
+    Dict,
+}
+
+/// The collection a type refers to, looking through references
+fn collection_of(ty: &Type) -> Option<Collection> {
+    match ty {
+        Type::Reference { inner, .. } => collection_of(inner),
+        Type::Array { .. } | Type::Slice(_) => Some(Collection::List),
+        Type::Path(name) | Type::Generic { name, .. } => match mojo_generic_container(name) {
+            "List" => Some(Collection::List),
+            "Dict" => Some(Collection::Dict),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Mojo for an expression used twice, as its first use and every later one;
+/// anything beyond a plain name or literal is bound with `:=` on first use so
+/// it is evaluated once
+fn bind_once(rendered: String, expr: &Expression, binding: &str) -> (String, String) {
+    if is_plain_operand(expr) {
+        (rendered.clone(), rendered)
+    } else {
+        (
+            format!("({} := {})", binding, rendered),
+            binding.to_string(),
+        )
+    }
+}
+
+/// Whether an expression is a plain name or literal, which is free to evaluate
+/// more than once
+fn is_plain_operand(expr: &Expression) -> bool {
+    match expr {
+        Expression::Reference { expr, .. } => is_plain_operand(expr),
+        Expression::Identifier(_) | Expression::Path(_) | Expression::Literal(_) => true,
+        _ => false,
+    }
+}
+
+/// A parameter or local variable of the function being generated
+struct Local {
+    name: String,
+    /// The collection it is known to hold
+    collection: Option<Collection>,
+    /// Its Rust type, when declared or evident from its value
+    type_: Option<Type>,
+}
+
+/// What to do with the value of a block's trailing expression
+enum Tail<'a> {
+    /// Evaluate it as a statement
+    Discard,
+    /// Assign it to the named variable
+    Assign(&'a str),
+    /// Return it from the enclosing function
+    Return,
 }
 
 struct MojoGenerator {
+    config: CompilerConfig,
+    options: CompilerOptions,
     output: String,
     indent_level: usize,
+    /// Impl blocks whose methods are emitted inside the matching struct body
+    impls: Vec<Impl>,
+    /// Names of the traits defined in this unit, which become Mojo traits
+    traits: Vec<String>,
+    /// Enums with data-carrying variants, which become a `Variant` of payload structs
+    data_enums: Vec<Enum>,
+    /// Return types of the free functions in this unit
+    fn_returns: Vec<(String, Type)>,
+    /// Pattern bindings to substitute while rendering a match guard, which is
+    /// evaluated before the arm's bindings are declared
+    guard_bindings: Vec<(String, String)>,
+    /// Return type of the function whose body is being generated
+    return_type: Option<Type>,
+    /// Whether the function being generated is the program's `main`
+    entry_point: bool,
+    /// Parameters and locals of the function being generated; later bindings
+    /// shadow earlier ones
+    locals: Vec<Local>,
+    /// Every name the function being generated has declared so far
+    declared: Vec<String>,
+    /// Bindings renamed so they don't redeclare a name of the enclosing
+    /// function: those inside blocks emitted in place and those that shadow
+    /// an earlier name through a pattern, innermost last
+    block_renames: Vec<(String, String)>,
+    /// Lengths of `locals` and `block_renames` where each enclosing block
+    /// emitted in place began
+    block_scopes: Vec<(usize, usize)>,
+    /// Labels of the loops enclosing the statement being generated, innermost last
+    loop_labels: Vec<Option<String>>,
+    /// Match scrutinees bound to a temporary so far in the function being generated
+    match_temps: usize,
+    /// Operands of `?` bound to a temporary so far in the function being generated
+    try_temps: usize,
+    /// Mojo symbols the output references, by the module they are imported from
+    imports: RefCell<BTreeMap<&'static str, BTreeSet<&'static str>>>,
+    /// Constructs that were emitted as placeholders, in the order they were
+    /// hit, with their source location when known
+    unsupported: RefCell<Vec<(String, Option<SourceLocation>)>>,
+    warnings: RefCell<Vec<Warning>>,
+    /// Items, statements and expressions generated so far
+    nodes: Cell<usize>,
+}
+
+/// Generates Mojo one top-level item at a time, so large inputs can be streamed
+///
+/// Items are generated as they would be by [`generate_mojo_with_options`], except
+/// that no file header or imports are emitted.
+pub struct ItemGenerator {
+    generator: MojoGenerator,
+}
+
+impl ItemGenerator {
+    /// Create a generator for items of a unit
+    ///
+    /// `context` holds the unit's items that affect how other items are generated:
+    /// impl blocks (which are emitted inside the struct they extend), traits and
+    /// enums. Passing other items is harmless.
+    pub fn new(context: &[Item], config: &CompilerConfig, options: &CompilerOptions) -> Self {
+        let mut generator = MojoGenerator::new(config.clone(), options.clone());
+        generator.prepare(context);
+        Self { generator }
+    }
+
+    /// Mojo for one top-level item, or an empty string for an impl block that
+    /// is emitted with its struct or a skipped test
+    pub fn generate(&mut self, item: &Item) -> Result<String> {
+        let generator = &mut self.generator;
+        generator.output.clear();
+        generator.unsupported.get_mut().clear();
+        generator.warnings.get_mut().clear();
+        generator.generate_top_level_item(item)?;
+        if generator.options.strict {
+            if let Some((construct, location)) = generator.unsupported.get_mut().drain(..).next() {
+                return Err(Error::UnsupportedFeature {
+                    feature: construct,
+                    location,
+                });
+            }
+        }
+        Ok(std::mem::take(&mut generator.output))
+    }
 }
 
 impl MojoGenerator {
-    fn new() -> Self {
+    fn new(config: CompilerConfig, options: CompilerOptions) -> Self {
         Self {
+            config,
+            options,
             output: String::new(),
             indent_level: 0,
+            impls: Vec::new(),
+            traits: Vec::new(),
+            data_enums: Vec::new(),
+            fn_returns: Vec::new(),
+            guard_bindings: Vec::new(),
+            return_type: None,
+            entry_point: false,
+            locals: Vec::new(),
+            declared: Vec::new(),
+            block_renames: Vec::new(),
+            block_scopes: Vec::new(),
+            loop_labels: Vec::new(),
+            match_temps: 0,
+            try_temps: 0,
+            imports: RefCell::new(BTreeMap::new()),
+            unsupported: RefCell::new(Vec::new()),
+            warnings: RefCell::new(Vec::new()),
+            nodes: Cell::new(0),
         }
     }
 
+    /// Record that the output references `symbol`, which must be imported from `module`
+    fn use_symbol(&self, module: &'static str, symbol: &'static str) {
+        self.imports
+            .borrow_mut()
+            .entry(module)
+            .or_default()
+            .insert(symbol);
+    }
+
+    fn count_node(&self) {
+        self.nodes.set(self.nodes.get() + 1);
+    }
+
     fn generate_compilation_unit(&mut self, unit: &CompilationUnit) -> Result<String> {
         // Add header comment
-        self.writeln("# Generated Mojo code from Rust source");
-        self.writeln("# This file was automatically generated by rust2mojo");
-        self.writeln("");
+        if self.options.emit_header {
+            self.writeln("# Generated Mojo code from Rust source");
+            self.writeln("# This file was automatically generated by rust2mojo");
+            self.writeln(&format!(
+                "# Target: Mojo {}",
+                unit.metadata.target_mojo_version
+            ));
+            self.writeln("");
+        }
 
-        // Generate imports for common Mojo modules
-        self.writeln("from memory import UnsafePointer");
-        self.writeln("from collections import List");
-        self.writeln("");
+        self.prepare(&unit.items);
 
-        // Generate each item
+        // Generate each item, then put the imports they used between the header and them
+        let header = std::mem::take(&mut self.output);
         for item in &unit.items {
-            self.generate_item(item)?;
+            self.generate_top_level_item(item)?;
+        }
+        let body = std::mem::replace(&mut self.output, header);
+
+        let imports = self.imports.take();
+        for (module, symbols) in &imports {
+            let symbols: Vec<&str> = symbols.iter().copied().collect();
+            self.writeln(&format!("from {} import {}", module, symbols.join(", ")));
+        }
+        if !imports.is_empty() {
             self.writeln("");
         }
+        self.output.push_str(&body);
 
         Ok(self.output.clone())
     }
 
+    /// Record what the unit's items need to know about each other
+    ///
+    /// Modules are emitted in place, so the items inside them are considered too.
+    fn prepare(&mut self, items: &[Item]) {
+        let items = flatten_modules(items);
+        self.traits = items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Trait(trait_item) => Some(trait_item.name.clone()),
+                _ => None,
+            })
+            .collect();
+        self.fn_returns = items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Function(func) => func
+                    .return_type
+                    .clone()
+                    .map(|return_type| (func.name.clone(), return_type)),
+                _ => None,
+            })
+            .collect();
+        self.data_enums = items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Enum(enum_item) if !is_c_like(enum_item) => Some(enum_item.clone()),
+                _ => None,
+            })
+            .collect();
+
+        // Mojo methods live inside the struct definition, so impl blocks for
+        // structs (and enums, which become structs) in this unit are attached
+        // to them instead of emitted separately
+        let struct_names: Vec<&str> = items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Struct(struct_item) => Some(struct_item.name.as_str()),
+                Item::Enum(enum_item) => Some(enum_item.name.as_str()),
+                _ => None,
+            })
+            .collect();
+        self.impls = items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Impl(impl_item)
+                    if impl_target_name(impl_item).is_some_and(|n| struct_names.contains(&n)) =>
+                {
+                    Some(impl_item.clone())
+                }
+                _ => None,
+            })
+            .collect();
+    }
+
+    /// Emit a top-level item followed by a blank line, unless it is attached to
+    /// another item or skipped
+    fn generate_top_level_item(&mut self, item: &Item) -> Result<()> {
+        if self.is_attached_impl(item) || self.is_skipped_test(item) {
+            return Ok(());
+        }
+        self.generate_item(item)?;
+        self.writeln("");
+        Ok(())
+    }
+
     fn generate_item(&mut self, item: &Item) -> Result<()> {
+        self.count_node();
         match item {
             Item::Function(func) => self.generate_function(func),
             Item::Struct(struct_item) => self.generate_struct(struct_item),
@@ -55,59 +641,195 @@ impl MojoGenerator {
             Item::Const(const_item) => self.generate_const(const_item),
             Item::Static(static_item) => self.generate_static(static_item),
             Item::Type(type_item) => self.generate_type_alias(type_item),
+            Item::Trait(trait_item) => self.generate_trait(trait_item),
         }
     }
 
     fn generate_function(&mut self, func: &Function) -> Result<()> {
-        // Generate function signature
-        let visibility = self.mojo_visibility(&func.visibility);
-        let name = &func.name;
-
-        // Convert main function to special Mojo main
-        if name == "main" {
-            self.writeln("fn main():");
-        } else {
-            self.write(&format!("{}fn {}(", visibility, name));
-
-            // Generate parameters
-            for (i, param) in func.parameters.iter().enumerate() {
-                if i > 0 {
-                    self.write(", ");
-                }
-                self.write(&format!("{}: {}", param.name, self.mojo_type(&param.type_)));
-            }
-
-            self.write(")");
-
-            // Generate return type
-            if let Some(return_type) = &func.return_type {
-                self.write(&format!(" -> {}", self.mojo_type(return_type)));
-            }
-
-            self.writeln(":");
+        self.warn_dropped_attributes(&func.name, &func.attributes);
+        self.generate_deprecation_note(&func.attributes);
+        self.generate_visibility_note(&func.visibility);
+        if is_test_function(func) {
+            self.writeln("# test");
         }
+        if returns_mut_ref_into_self(func) {
+            self.warn(format!(
+                "`{}` returns a mutable reference into `self`; review the generated Mojo manually",
+                func.name
+            ));
+            self.writeln(
+                "# WARNING: returns a mutable reference into self; borrow semantics may not carry over, review manually",
+            );
+        }
+
+        self.entry_point = self.indent_level == 0 && is_entry_point(func);
+        // An `Err` returned from `main` is raised instead
+        let raises = statements_raise(&func.body)
+            || (self.entry_point
+                && func.return_type.as_ref().and_then(try_carrier) == Some("Result"));
+        self.generate_signature(
+            &func.visibility,
+            &func.name,
+            &func.parameters,
+            &func.return_type,
+            raises,
+        );
 
         // Generate function body
+        let enclosing_return = std::mem::replace(&mut self.return_type, func.return_type.clone());
+        let params = func
+            .parameters
+            .iter()
+            .map(|param| Local {
+                name: param.name.clone(),
+                collection: collection_of(&param.type_),
+                type_: Some(param.type_.clone()),
+            })
+            .collect();
+        let enclosing_locals = std::mem::replace(&mut self.locals, params);
+        let param_names = func.parameters.iter().map(|param| param.name.clone());
+        let enclosing_declared = std::mem::replace(&mut self.declared, param_names.collect());
+        let enclosing_renames = std::mem::take(&mut self.block_renames);
+        let enclosing_match_temps = std::mem::replace(&mut self.match_temps, 0);
+        let enclosing_try_temps = std::mem::replace(&mut self.try_temps, 0);
         self.indent();
-        if func.body.is_empty() {
+        self.generate_docstring(&func.attributes);
+        if self.config.signatures_only {
+            self.writeln("...");
+        } else if func.body.is_empty() {
             self.writeln("pass");
+        } else if self.entry_point && matches!(func.return_type, Some(ref ty) if !is_unit_like(ty))
+        {
+            // Mojo's `main` returns nothing, so a final `Ok(())` can just fall off the end
+            match func.body.split_last() {
+                Some((Statement::Expression(tail), rest)) if is_variant_call(tail, "Ok") => {
+                    if rest.is_empty() {
+                        self.writeln("pass");
+                    }
+                    for stmt in rest {
+                        self.generate_statement(stmt)?;
+                    }
+                }
+                _ => self.generate_block(&func.body, &Tail::Return)?,
+            }
+        } else if matches!(func.return_type, Some(ref ty) if !is_unit_like(ty)) {
+            // The trailing expression of a value-returning function is its result
+            self.generate_block(&func.body, &Tail::Return)?;
         } else {
             for stmt in &func.body {
                 self.generate_statement(stmt)?;
             }
         }
         self.dedent();
+        self.return_type = enclosing_return;
+        self.locals = enclosing_locals;
+        self.declared = enclosing_declared;
+        self.block_renames = enclosing_renames;
+        self.match_temps = enclosing_match_temps;
+        self.try_temps = enclosing_try_temps;
+        self.entry_point = false;
 
         Ok(())
     }
 
+    /// Emit a `fn` signature line, including the trailing `:`
+    fn generate_signature(
+        &mut self,
+        visibility: &Visibility,
+        name: &str,
+        parameters: &[Parameter],
+        return_type: &Option<Type>,
+        raises: bool,
+    ) {
+        let visibility = self.mojo_visibility(visibility);
+        let raises = if raises { " raises" } else { "" };
+
+        let none_return = if self.config.explicit_none_return {
+            " -> None"
+        } else {
+            ""
+        };
+
+        // Mojo's `main` takes no arguments and returns nothing
+        if self.entry_point {
+            self.writeln(&format!("fn main(){}{}:", raises, none_return));
+            return;
+        }
+
+        self.write(&format!("{}fn {}(", visibility, name));
+
+        // Generate parameters
+        for (i, param) in parameters.iter().enumerate() {
+            if i > 0 {
+                self.write(", ");
+            }
+            if param.name == "self" {
+                self.write(mojo_receiver(&param.type_));
+            } else {
+                self.write(&format!(
+                    "{}{}: {}",
+                    mojo_convention(&param.type_),
+                    param.name,
+                    self.mojo_type(&param.type_)
+                ));
+            }
+        }
+
+        self.write(")");
+        self.write(raises);
+
+        // Generate return type
+        match (return_type, return_origin(parameters, return_type)) {
+            // Mojo has no never type; a diverging function just returns nothing
+            (Some(Type::Unit | Type::Never) | None, _) => self.write(none_return),
+            (Some(Type::Reference { inner, .. }), Some(origin)) => self.write(&format!(
+                " -> ref [__lifetime_of({})] {}",
+                origin,
+                self.mojo_type(inner)
+            )),
+            (Some(return_type), _) => self.write(&format!(" -> {}", self.mojo_type(return_type))),
+        }
+
+        self.writeln(":");
+    }
+
     fn generate_struct(&mut self, struct_item: &Struct) -> Result<()> {
+        self.warn_dropped_attributes(&struct_item.name, &struct_item.attributes);
+        self.generate_deprecation_note(&struct_item.attributes);
+        self.generate_visibility_note(&struct_item.visibility);
+
         let visibility = self.mojo_visibility(&struct_item.visibility);
-        self.writeln(&format!("{}struct {}:", visibility, struct_item.name));
+        let impls = self.impls_for(&struct_item.name);
+
+        let mut conformances: Vec<String> = self
+            .derive_conformances(&struct_item.name, &struct_item.attributes)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        for conformance in self.impl_conformances(&impls) {
+            if !conformances.contains(&conformance) {
+                conformances.push(conformance);
+            }
+        }
+        // `@value` synthesizes the `__copyinit__` that `Copyable` requires
+        let derives_clone = derives(&struct_item.attributes)
+            .any(|derive| matches!(trait_name(derive), "Clone" | "Copy"));
+        if derives_clone {
+            self.writeln("@value");
+        }
+        self.writeln(&struct_header(visibility, &struct_item.name, &conformances));
+
+        let derives_debug =
+            derives(&struct_item.attributes).any(|derive| trait_name(derive) == "Debug");
+        let has_methods =
+            derives_debug || impls.iter().any(|impl_item| !impl_item.items.is_empty());
 
         self.indent();
+        self.generate_docstring(&struct_item.attributes);
         if struct_item.fields.is_empty() {
-            self.writeln("pass");
+            if !has_methods {
+                self.writeln("pass");
+            }
         } else {
             for field in &struct_item.fields {
                 self.writeln(&format!(
@@ -130,195 +852,1896 @@ impl MojoGenerator {
             self.writeln("):");
 
             self.indent();
-            for field in &struct_item.fields {
-                self.writeln(&format!("self.{} = {}", field.name, field.name));
+            if self.config.signatures_only {
+                self.writeln("...");
+            } else {
+                for field in &struct_item.fields {
+                    self.writeln(&format!("self.{} = {}", field.name, field.name));
+                }
             }
             self.dedent();
+
+            if self.config.emit_move_init && needs_move_init(struct_item) {
+                self.writeln("");
+                self.writeln("fn __moveinit__(inout self, owned existing: Self):");
+                self.indent();
+                if self.config.signatures_only {
+                    self.writeln("...");
+                } else {
+                    for field in &struct_item.fields {
+                        self.writeln(&format!("self.{0} = existing.{0}^", field.name));
+                    }
+                }
+                self.dedent();
+            }
+        }
+
+        if derives_debug {
+            self.generate_debug_methods(struct_item, &impls);
+        }
+        for impl_item in &impls {
+            self.generate_impl_methods(impl_item)?;
         }
         self.dedent();
 
         Ok(())
     }
 
-    fn generate_enum(&mut self, _enum_item: &Enum) -> Result<()> {
-        // Mojo doesn't have direct enum support, generate as struct with static methods
-        self.writeln("# TODO: Enum conversion not yet implemented");
-        Ok(())
+    /// `#[derive(Debug)]` becomes a `__repr__` in Rust's `Name { field: value }`
+    /// form, with `__str__` falling back to it unless `Display` provides one
+    fn generate_debug_methods(&mut self, struct_item: &Struct, impls: &[Impl]) {
+        let fields: Vec<String> = struct_item
+            .fields
+            .iter()
+            .map(|field| format!("{0}: \" + repr(self.{0}) + \"", field.name))
+            .collect();
+        let text = if fields.is_empty() {
+            struct_item.name.clone()
+        } else {
+            format!("{} {{ {} }}", struct_item.name, fields.join(", "))
+        };
+        let body = |generator: &Self, body: String| {
+            if generator.config.signatures_only {
+                "...".to_string()
+            } else {
+                body
+            }
+        };
+
+        if !self.output.ends_with(":\n") {
+            self.writeln("");
+        }
+        self.writeln("fn __repr__(self) -> String:");
+        self.writeln_indented(&body(self, format!("return \"{}\"", text)));
+
+        let has_display = impls.iter().any(
+            |impl_item| matches!(&impl_item.trait_, Some(Type::Path(path)) if trait_name(path) == "Display"),
+        );
+        if !has_display {
+            self.writeln("");
+            self.writeln("fn __str__(self) -> String:");
+            self.writeln_indented(&body(self, "return self.__repr__()".to_string()));
+        }
     }
 
-    fn generate_impl(&mut self, _impl_item: &Impl) -> Result<()> {
-        // Mojo impl blocks are part of struct definitions
-        self.writeln("# TODO: Impl block conversion not yet implemented");
+    fn generate_impl_methods(&mut self, impl_item: &Impl) -> Result<()> {
+        let is_display =
+            matches!(&impl_item.trait_, Some(Type::Path(path)) if trait_name(path) == "Display");
+
+        for item in &impl_item.items {
+            match item {
+                ImplItem::Function(func) => {
+                    if !self.output.ends_with(":\n") {
+                        self.writeln("");
+                    }
+                    if is_display && func.name == "fmt" {
+                        self.generate_display_method(func)?;
+                    } else {
+                        // Associated functions without a receiver are called as `Type.name(...)`
+                        if func.parameters.first().map(|p| p.name.as_str()) != Some("self") {
+                            self.writeln("@staticmethod");
+                        }
+                        self.generate_function(func)?;
+                    }
+                }
+                // Associated consts and types become struct-level aliases
+                ImplItem::Const(const_item) => {
+                    self.separate_alias();
+                    self.generate_const(const_item)?;
+                }
+                ImplItem::Type(type_item) => {
+                    self.separate_alias();
+                    self.generate_type_alias(type_item)?;
+                }
+            }
+        }
         Ok(())
     }
 
-    fn generate_use(&mut self, use_item: &Use) -> Result<()> {
-        // Convert Rust use statements to Mojo imports
-        self.writeln(&format!("# Rust use: {}", use_item.path));
-        Ok(())
+    /// Start a run of aliases in a struct body on a fresh paragraph
+    fn separate_alias(&mut self) {
+        let last_line = self.output.trim_end_matches('\n').rsplit('\n').next();
+        let follows_alias = last_line.is_some_and(|line| line.trim_start().starts_with("alias "));
+        if !self.output.ends_with(":\n") && !follows_alias {
+            self.writeln("");
+        }
     }
 
-    fn generate_module(&mut self, _mod_item: &Module) -> Result<()> {
-        self.writeln("# TODO: Module conversion not yet implemented");
+    /// `impl Display` maps onto Mojo's `Stringable`, with `fmt` becoming `__str__`
+    fn generate_display_method(&mut self, func: &Function) -> Result<()> {
+        self.writeln("fn __str__(self) -> String:");
+        self.indent();
+        if self.config.signatures_only {
+            self.writeln("...");
+        } else if func.body.is_empty() {
+            self.writeln("return String()");
+        } else {
+            self.generate_block(&func.body, &Tail::Return)?;
+        }
+        self.dedent();
         Ok(())
     }
 
-    fn generate_const(&mut self, const_item: &Const) -> Result<()> {
-        let visibility = self.mojo_visibility(&const_item.visibility);
-        self.writeln(&format!(
-            "{}alias {} = {}",
-            visibility,
-            const_item.name,
-            self.mojo_expression(&const_item.value)
-        ));
+    fn generate_enum(&mut self, enum_item: &Enum) -> Result<()> {
+        self.warn_dropped_attributes(&enum_item.name, &enum_item.attributes);
+        self.generate_deprecation_note(&enum_item.attributes);
+        self.generate_visibility_note(&enum_item.visibility);
+
+        if !is_c_like(enum_item) {
+            return self.generate_data_enum(enum_item);
+        }
+
+        // Mojo has no enums; a C-like enum becomes a struct wrapping its
+        // discriminant, with one alias per variant
+        let visibility = self.mojo_visibility(&enum_item.visibility);
+        let impls = self.impls_for(&enum_item.name);
+        let mut conformances = vec!["EqualityComparable".to_string()];
+        conformances.extend(self.impl_conformances(&impls));
+        self.writeln("@value");
+        self.writeln(&struct_header(visibility, &enum_item.name, &conformances));
+        self.indent();
+        self.generate_docstring(&enum_item.attributes);
+        self.writeln("var _tag: Int");
+        self.writeln("");
+        for (tag, variant) in enum_item.variants.iter().enumerate() {
+            self.writeln(&format!("alias {} = Self({})", variant.name, tag));
+        }
+        self.writeln("");
+        self.writeln("fn __eq__(self, other: Self) -> Bool:");
+        self.writeln_indented("return self._tag == other._tag");
+        self.writeln("");
+        self.writeln("fn __ne__(self, other: Self) -> Bool:");
+        self.writeln_indented("return self._tag != other._tag");
+
+        for impl_item in &impls {
+            self.generate_impl_methods(impl_item)?;
+        }
+        self.dedent();
         Ok(())
     }
 
-    fn generate_static(&mut self, static_item: &Static) -> Result<()> {
-        let visibility = self.mojo_visibility(&static_item.visibility);
-        let mutability = if static_item.mutable { "var" } else { "alias" };
+    /// A data-carrying enum becomes one payload struct per variant, wrapped in
+    /// a struct holding a `Variant` of them, with one constructor per variant
+    fn generate_data_enum(&mut self, enum_item: &Enum) -> Result<()> {
+        let visibility = self.mojo_visibility(&enum_item.visibility);
+        let payload_types: Vec<String> = enum_item
+            .variants
+            .iter()
+            .map(|variant| payload_type_name(&enum_item.name, &variant.name))
+            .collect();
+
+        for (variant, payload_type) in enum_item.variants.iter().zip(&payload_types) {
+            self.writeln("@value");
+            self.writeln(&format!("{}struct {}:", visibility, payload_type));
+            self.indent();
+            let fields = variant_fields(&variant.data);
+            if fields.is_empty() {
+                self.writeln("pass");
+            }
+            for field in &fields {
+                self.writeln(&format!(
+                    "var {}: {}",
+                    field.name,
+                    self.mojo_type(&field.type_)
+                ));
+            }
+            self.dedent();
+            self.writeln("");
+        }
+
+        let impls = self.impls_for(&enum_item.name);
+        let conformances = self.impl_conformances(&impls);
+        self.writeln("@value");
+        self.writeln(&struct_header(visibility, &enum_item.name, &conformances));
+        self.indent();
+        self.generate_docstring(&enum_item.attributes);
+        self.use_symbol("utils", "Variant");
         self.writeln(&format!(
-            "{}{} {} = {}",
-            visibility,
-            mutability,
-            static_item.name,
-            self.mojo_expression(&static_item.value)
+            "var _value: Variant[{}]",
+            payload_types.join(", ")
         ));
+
+        for (variant, payload_type) in enum_item.variants.iter().zip(&payload_types) {
+            self.writeln("");
+            let fields = variant_fields(&variant.data);
+            // Unit variants are values, like the aliases of a C-like enum
+            if fields.is_empty() {
+                self.writeln(&format!(
+                    "alias {} = Self({}())",
+                    variant.name, payload_type
+                ));
+                continue;
+            }
+            let parameters: Vec<Parameter> = fields
+                .iter()
+                .map(|field| Parameter {
+                    name: field.name.clone(),
+                    type_: field.type_.clone(),
+                    mutable: false,
+                })
+                .collect();
+            self.writeln("@staticmethod");
+            self.generate_signature(
+                &Visibility::Public,
+                &variant.name,
+                &parameters,
+                &Some(Type::Path("Self".to_string())),
+                false,
+            );
+            self.indent();
+            if self.config.signatures_only {
+                self.writeln("...");
+            } else {
+                let args: Vec<&str> = fields.iter().map(|field| field.name.as_str()).collect();
+                self.writeln(&format!(
+                    "return Self({}({}))",
+                    payload_type,
+                    args.join(", ")
+                ));
+            }
+            self.dedent();
+        }
+
+        self.writeln("");
+        self.writeln("fn isa[T: CollectionElement](self) -> Bool:");
+        self.writeln_indented("return self._value.isa[T]()");
+        self.writeln("");
+        self.writeln(
+            "fn __getitem__[T: CollectionElement](ref [_]self: Self) -> ref [__lifetime_of(self)] T:",
+        );
+        self.writeln_indented("return self._value[T]");
+
+        for impl_item in &impls {
+            self.generate_impl_methods(impl_item)?;
+        }
+        self.dedent();
         Ok(())
     }
 
-    fn generate_type_alias(&mut self, type_item: &TypeAlias) -> Result<()> {
-        let visibility = self.mojo_visibility(&type_item.visibility);
-        self.writeln(&format!(
-            "{}alias {} = {}",
-            visibility,
-            type_item.name,
-            self.mojo_type(&type_item.type_)
-        ));
+    /// The collection an expression is known to evaluate to, if any
+    fn collection(&self, expr: &Expression) -> Option<Collection> {
+        match expr {
+            Expression::Identifier(name) => self.local(name).and_then(|local| local.collection),
+            Expression::Reference { expr, .. } | Expression::Dereference(expr) => {
+                self.collection(expr)
+            }
+            Expression::Array(_) | Expression::Repeat { .. } => Some(Collection::List),
+            Expression::MethodCall {
+                receiver, method, ..
+            } if method == "clone" => self.collection(receiver),
+            // Constructors such as `Vec::new()` or `HashMap::with_capacity(n)`,
+            // and functions of this unit that return a collection
+            Expression::Call { function, .. } => match &**function {
+                Expression::Path(path) => path
+                    .rsplit_once("::")
+                    .and_then(|(ty, _)| collection_of(&Type::Path(ty.to_string()))),
+                Expression::Identifier(name) => self
+                    .fn_returns
+                    .iter()
+                    .find(|(func, _)| func == name)
+                    .and_then(|(_, return_type)| collection_of(return_type)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Whether a `for` loop over `iterator` walks a `HashMap`'s entries: `None`
+    /// if not, otherwise whether `iterator` already renders as `.items()`
+    fn dict_iteration(&self, iterator: &Expression) -> Option<bool> {
+        match iterator {
+            Expression::MethodCall {
+                receiver,
+                method,
+                args,
+                ..
+            } if matches!(method.as_str(), "iter" | "iter_mut")
+                && args.is_empty()
+                && self.collection(receiver) == Some(Collection::Dict) =>
+            {
+                Some(true)
+            }
+            _ if self.collection(iterator) == Some(Collection::Dict) => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Whether `?` in the current function short-circuits on `None`
+    fn returns_option(&self) -> bool {
+        self.return_type.as_ref().and_then(try_carrier) == Some("Option")
+    }
+
+    /// Whether `?` in the current function short-circuits on `Err`
+    fn returns_result(&self) -> bool {
+        self.return_type.as_ref().and_then(try_carrier) == Some("Result")
+    }
+
+    /// Payload struct of a variant of a data-carrying enum in this unit, if any
+    fn data_variant_type(&self, path: &str, variant: &str) -> Option<String> {
+        let enum_name = trait_name(path);
+        self.data_enums
+            .iter()
+            .find(|enum_item| {
+                (path.is_empty() || enum_name == "Self" || enum_item.name == enum_name)
+                    && enum_item.variants.iter().any(|v| v.name == variant)
+            })
+            .map(|enum_item| payload_type_name(&enum_item.name, variant))
+    }
+
+    fn generate_trait(&mut self, trait_item: &Trait) -> Result<()> {
+        self.warn_dropped_attributes(&trait_item.name, &trait_item.attributes);
+        self.generate_deprecation_note(&trait_item.attributes);
+        self.generate_visibility_note(&trait_item.visibility);
+
+        let visibility = self.mojo_visibility(&trait_item.visibility);
+        self.writeln(&format!("{}trait {}:", visibility, trait_item.name));
+        self.indent();
+        self.generate_docstring(&trait_item.attributes);
+        if trait_item.items.is_empty() {
+            self.writeln("pass");
+        }
+        for (i, item) in trait_item.items.iter().enumerate() {
+            if i > 0 {
+                self.writeln("");
+            }
+            match item {
+                // Mojo traits only declare requirements, so defaults are dropped
+                TraitItem::Function(func) => {
+                    if func.default_body.is_some() {
+                        self.writeln(
+                            "# NOTE: default implementation omitted; Mojo traits cannot define method bodies",
+                        );
+                    }
+                    self.generate_signature(
+                        &Visibility::Public,
+                        &func.name,
+                        &func.parameters,
+                        &func.return_type,
+                        false,
+                    );
+                    self.indent();
+                    self.generate_docstring(&func.attributes);
+                    self.writeln("...");
+                    self.dedent();
+                }
+                TraitItem::Type { name, .. } => {
+                    self.writeln(&format!(
+                        "# TODO: associated type `{}` has no Mojo equivalent",
+                        name
+                    ));
+                }
+            }
+        }
+        self.dedent();
+        Ok(())
+    }
+
+    fn generate_impl(&mut self, _impl_item: &Impl) -> Result<()> {
+        // Mojo impl blocks are part of struct definitions
+        self.writeln("# TODO: Impl block conversion not yet implemented");
+        Ok(())
+    }
+
+    fn generate_use(&mut self, use_item: &Use) -> Result<()> {
+        // One `from` line per module, in the order the modules first appear
+        let mut modules: Vec<(String, Vec<String>)> = Vec::new();
+        for import in &use_item.imports {
+            // Standard library types are mapped to Mojo builtins where they are used
+            if matches!(
+                import.module.first().map(String::as_str),
+                Some("std" | "core" | "alloc")
+            ) {
+                self.writeln(&format!("# Rust use: {}", use_path(import)));
+                continue;
+            }
+            let name = match &import.alias {
+                Some(alias) => format!("{} as {}", import.name, alias),
+                None => import.name.clone(),
+            };
+            let module = mojo_module_path(&import.module);
+            if module.is_empty() {
+                self.writeln(&format!("import {}", name));
+            } else if let Some((_, names)) = modules.iter_mut().find(|(m, _)| *m == module) {
+                names.push(name);
+            } else {
+                modules.push((module, vec![name]));
+            }
+        }
+        for (module, names) in modules {
+            self.writeln(&format!("from {} import {}", module, names.join(", ")));
+        }
+        Ok(())
+    }
+
+    fn generate_module(&mut self, mod_item: &Module) -> Result<()> {
+        // Mojo has no inline modules, so the contents are emitted in place
+        self.writeln(&format!("# mod {}", mod_item.name));
+        let items: Vec<&Item> = mod_item
+            .items
+            .iter()
+            .filter(|item| !self.is_attached_impl(item) && !self.is_skipped_test(item))
+            .collect();
+        for (i, item) in items.into_iter().enumerate() {
+            if i > 0 {
+                self.writeln("");
+            }
+            self.generate_item(item)?;
+        }
+        Ok(())
+    }
+
+    /// Whether an item is an impl block emitted inside the struct it extends
+    fn is_attached_impl(&self, item: &Item) -> bool {
+        matches!(item, Item::Impl(impl_item) if self.impls.contains(impl_item))
+    }
+
+    fn is_skipped_test(&self, item: &Item) -> bool {
+        self.config.skip_tests && matches!(item, Item::Function(func) if is_test_function(func))
+    }
+
+    fn generate_const(&mut self, const_item: &Const) -> Result<()> {
+        self.generate_visibility_note(&const_item.visibility);
+        let visibility = self.mojo_visibility(&const_item.visibility);
+        self.writeln(&format!(
+            "{}alias {} = {}",
+            visibility,
+            const_item.name,
+            self.mojo_expression(&const_item.value)
+        ));
+        Ok(())
+    }
+
+    fn generate_static(&mut self, static_item: &Static) -> Result<()> {
+        self.generate_visibility_note(&static_item.visibility);
+        let visibility = self.mojo_visibility(&static_item.visibility);
+        let mutability = if static_item.mutable { "var" } else { "alias" };
+        self.writeln(&format!(
+            "{}{} {} = {}",
+            visibility,
+            mutability,
+            static_item.name,
+            self.mojo_expression(&static_item.value)
+        ));
+        Ok(())
+    }
+
+    fn generate_type_alias(&mut self, type_item: &TypeAlias) -> Result<()> {
+        self.generate_visibility_note(&type_item.visibility);
+        let visibility = self.mojo_visibility(&type_item.visibility);
+        self.writeln(&format!(
+            "{}alias {} = {}",
+            visibility,
+            type_item.name,
+            self.mojo_type(&type_item.type_)
+        ));
+        Ok(())
+    }
+
+    /// Log and record a warning about lossy output
+    fn warn(&self, message: impl Into<String>) {
+        self.warn_at(message, None);
+    }
+
+    fn warn_at(&self, message: impl Into<String>, location: Option<SourceLocation>) {
+        let message = message.into();
+        warn!("{}", message);
+        self.warnings
+            .borrow_mut()
+            .push(Warning { message, location });
+    }
+
+    /// Warn about attributes that have no effect on the generated Mojo
+    fn warn_dropped_attributes(&self, item_name: &str, attributes: &[Attribute]) {
+        for attr in attributes {
+            if !HANDLED_ATTRIBUTES.contains(&attr.path.as_str()) {
+                self.warn(format!(
+                    "dropped attribute `#[{}]` on `{}`",
+                    attr.path, item_name
+                ));
+            }
+        }
+    }
+
+    /// Impl blocks in this unit whose target is the named type
+    fn impls_for(&self, type_name: &str) -> Vec<Impl> {
+        self.impls
+            .iter()
+            .filter(|impl_item| impl_target_name(impl_item) == Some(type_name))
+            .cloned()
+            .collect()
+    }
+
+    /// Mojo traits a type conforms to through its trait impls
+    ///
+    /// Traits defined in this unit become Mojo traits of the same name; std
+    /// traits only count when they have a Mojo counterpart.
+    fn impl_conformances(&self, impls: &[Impl]) -> Vec<String> {
+        let mut conformances: Vec<String> = Vec::new();
+        for impl_item in impls {
+            let Some(Type::Path(path)) = &impl_item.trait_ else {
+                continue;
+            };
+            let conformance = match mojo_trait_conformance(path) {
+                Some(mojo_trait) => mojo_trait.to_string(),
+                None if self.traits.iter().any(|name| name == trait_name(path)) => {
+                    trait_name(path).to_string()
+                }
+                None => continue,
+            };
+            if !conformances.contains(&conformance) {
+                conformances.push(conformance);
+            }
+        }
+        conformances
+    }
+
+    /// Mojo traits implied by `#[derive(...)]`, warning about derives with no equivalent
+    fn derive_conformances(&self, item_name: &str, attributes: &[Attribute]) -> Vec<&'static str> {
+        let mut conformances = Vec::new();
+        for derive in derives(attributes) {
+            let mapped: &[&'static str] = match trait_name(derive) {
+                "Debug" => &["Stringable", "Representable"],
+                "Clone" | "Copy" => &["Copyable"],
+                _ => {
+                    self.warn(format!(
+                        "derive `{}` on `{}` has no Mojo equivalent",
+                        derive, item_name
+                    ));
+                    &[]
+                }
+            };
+            for conformance in mapped {
+                if !conformances.contains(conformance) {
+                    conformances.push(*conformance);
+                }
+            }
+        }
+        conformances
+    }
+
+    /// Emit `///` doc comments as a docstring at the top of the current body
+    fn generate_docstring(&mut self, attributes: &[Attribute]) {
+        let lines: Vec<String> = attributes
+            .iter()
+            .filter(|attr| attr.path == "doc")
+            .filter_map(|attr| syn::parse_str::<syn::LitStr>(&attr.tokens).ok())
+            .map(|lit| {
+                let line = lit.value();
+                // `/// text` carries the space after the slashes
+                line.strip_prefix(' ')
+                    .unwrap_or(&line)
+                    .replace("\"\"\"", "\\\"\"\"")
+            })
+            .collect();
+        let text = lines.join("\n");
+        let text = text.trim_end();
+        if text.is_empty() {
+            return;
+        }
+
+        if !text.contains('\n') {
+            self.writeln(&format!("\"\"\"{}\"\"\"", text));
+        } else {
+            self.writeln("\"\"\"");
+            for line in text.lines() {
+                self.writeln(line);
+            }
+            self.writeln("\"\"\"");
+        }
+    }
+
+    /// Keep restricted Rust visibility as a comment, since Mojo cannot express it
+    ///
+    /// Private items need no note: they are the Rust default.
+    fn generate_visibility_note(&mut self, visibility: &Visibility) {
+        match visibility {
+            Visibility::Public | Visibility::Private => {}
+            Visibility::Crate => self.writeln("# pub(crate)"),
+            Visibility::Super => self.writeln("# pub(super)"),
+            Visibility::InPath(path) => self.writeln(&format!("# pub(in {})", path)),
+        }
+    }
+
+    /// Mojo has no `#[deprecated]`, so keep the notice as a comment above the item
+    fn generate_deprecation_note(&mut self, attributes: &[Attribute]) {
+        if let Some(attr) = attributes.iter().find(|attr| attr.path == "deprecated") {
+            match deprecation_note(&attr.tokens) {
+                Some(note) => self.writeln(&format!("# Deprecated: {}", note)),
+                None => self.writeln("# Deprecated"),
+            }
+        }
+    }
+
+    fn generate_statement(&mut self, stmt: &Statement) -> Result<()> {
+        // A binding inside a block emitted in place must not redeclare a name
+        // of the enclosing function, so it is renamed for the rest of the block.
+        // Shadowing a name that is already renamed renames it again.
+        if let Statement::Let { name, .. } = stmt {
+            let renamed_before = self.resolve_local(name) != name;
+            if (!self.block_scopes.is_empty() || renamed_before) && self.declared.contains(name) {
+                let renamed = self.unused_name(name);
+                let mut stmt = stmt.clone();
+                if let Statement::Let { name, .. } = &mut stmt {
+                    name.clone_from(&renamed);
+                }
+                self.generate_statement(&stmt)?;
+                self.block_renames.push((name.clone(), renamed));
+                return Ok(());
+            }
+        }
+
+        self.count_node();
+        if let Statement::Let {
+            name, type_, value, ..
+        } = stmt
+        {
+            let collection = match type_ {
+                Some(ty) => collection_of(ty),
+                None => value.as_ref().and_then(|value| self.collection(value)),
+            };
+            let type_ = type_
+                .clone()
+                .or_else(|| value.as_ref().and_then(|value| self.expression_type(value)));
+            self.locals.push(Local {
+                name: name.clone(),
+                collection,
+                type_,
+            });
+            self.declared.push(name.clone());
+        }
+        match stmt {
+            // `x?;` only needs the early return, not the unwrapped value
+            Statement::Expression(Expression::Try(inner))
+                if self.returns_option() || self.returns_result() =>
+            {
+                let inner = self.hoist_tries(inner);
+                self.bind_try(&inner);
+            }
+            // A block's statements run in place, with its bindings renamed
+            // where they would clash with the enclosing function's
+            Statement::Expression(Expression::Block(stmts)) => {
+                self.generate_inline_block(stmts, &Tail::Discard)?;
+            }
+            // `Vec::append` moves the elements of `other`, leaving it empty
+            Statement::Expression(Expression::MethodCall {
+                receiver,
+                method,
+                args,
+                ..
+            }) if method == "append"
+                && args.len() == 1
+                && self.collection(receiver) == Some(Collection::List) =>
+            {
+                let other = self.mojo_expression(&args[0]);
+                self.writeln(&format!(
+                    "{}.extend({})",
+                    self.mojo_expression(receiver),
+                    other
+                ));
+                self.writeln(&format!("{}.clear()", other));
+            }
+            Statement::Expression(expr) => {
+                // Assigning a block with statements of its own assigns its trailing value
+                if let Expression::Binary {
+                    left,
+                    op: BinaryOp::Assign,
+                    right,
+                } = expr
+                {
+                    if let Expression::Block(stmts) = &**right {
+                        if self.mojo_ternary(stmts).is_none() {
+                            let target = self.mojo_expression(left);
+                            return self.generate_inline_block(stmts, &Tail::Assign(&target));
+                        }
+                    }
+                }
+                let expr = self.hoist_tries(expr);
+                self.writeln(&self.mojo_expression(&expr));
+            }
+            Statement::Let {
+                name,
+                mutable,
+                type_,
+                value,
+            } => {
+                let type_annotation = if let Some(ty) = type_ {
+                    format!(": {}", self.mojo_type(ty))
+                } else {
+                    String::new()
+                };
+                if let Some(Expression::Block(stmts)) = value {
+                    // A plain value, or an if/else over plain values as a Mojo ternary
+                    if let Some(ternary) = self.mojo_ternary(stmts) {
+                        let keyword = if *mutable { "var" } else { "let" };
+                        self.writeln(&format!(
+                            "{} {}{} = {}",
+                            keyword, name, type_annotation, ternary
+                        ));
+                        return Ok(());
+                    }
+                    let keyword = if *mutable { "var" } else { "let" };
+                    // Run the statements of a straight-line block, then bind its final value
+                    if let Some((Statement::Expression(tail), rest)) = stmts.split_last() {
+                        if !matches!(tail, Expression::Block(_)) {
+                            self.enter_block();
+                            for stmt in rest {
+                                self.generate_statement(stmt)?;
+                            }
+                            let tail = self.mojo_expression(tail);
+                            self.exit_block();
+                            self.writeln(&format!(
+                                "{} {}{} = {}",
+                                keyword, name, type_annotation, tail
+                            ));
+                            return Ok(());
+                        }
+                    }
+                    // Otherwise declare first, then assign from each branch's
+                    // trailing value; Mojo needs the type of a late-initialized var
+                    let type_annotation = match (type_, self.block_type(stmts)) {
+                        (Some(_), _) => type_annotation,
+                        (None, Some(ty)) => format!(": {}", self.mojo_type(&ty)),
+                        (None, None) => {
+                            self.warn(format!(
+                                "could not infer the type of `{}`; annotate its declaration",
+                                name
+                            ));
+                            String::new()
+                        }
+                    };
+                    self.writeln(&format!("var {}{}", name, type_annotation));
+                    return self.generate_inline_block(stmts, &Tail::Assign(name));
+                }
+                let keyword = if *mutable { "var" } else { "let" };
+                let assignment = if let Some(val) = value {
+                    let val = self.hoist_tries(val);
+                    format!(" = {}", self.mojo_expression(&val))
+                } else {
+                    String::new()
+                };
+                self.writeln(&format!(
+                    "{} {}{}{}",
+                    keyword, name, type_annotation, assignment
+                ));
+            }
+            Statement::LetPattern {
+                pattern,
+                type_,
+                value,
+            } => {
+                let value = self.hoist_tries(value);
+                // Take the pieces from a variable so the value is evaluated once
+                let source = match &value {
+                    Expression::Identifier(_) => self.mojo_expression(&value),
+                    _ => {
+                        let names: Vec<String> = self
+                            .pattern_bindings("", pattern)
+                            .into_iter()
+                            .map(|(name, _)| name)
+                            .collect();
+                        let mut source = format!("__{}", names.join("_"));
+                        if self.declared.contains(&source) {
+                            source = self.unused_name(&source);
+                        }
+                        self.declared.push(source.clone());
+                        let type_annotation = match type_ {
+                            Some(ty) => format!(": {}", self.mojo_type(ty)),
+                            None => String::new(),
+                        };
+                        self.writeln(&format!(
+                            "var {}{} = {}",
+                            source,
+                            type_annotation,
+                            self.mojo_expression(&value)
+                        ));
+                        source
+                    }
+                };
+                // Rust marks mutability per binding, which patterns don't record.
+                // A binding that shadows an earlier name is renamed from here on.
+                for (name, value) in self.pattern_bindings(&source, pattern) {
+                    let declared_name = if self.declared.contains(&name) {
+                        let renamed = self.unused_name(&name);
+                        self.block_renames.push((name, renamed.clone()));
+                        renamed
+                    } else {
+                        name
+                    };
+                    self.writeln(&format!("var {} = {}", declared_name, value));
+                    self.declared.push(declared_name.clone());
+                    self.locals.push(Local {
+                        name: declared_name,
+                        collection: None,
+                        type_: None,
+                    });
+                }
+            }
+            Statement::Return(expr) => self.generate_return(expr.as_ref()),
+            Statement::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => self.generate_if(condition, then_branch, else_branch, &Tail::Discard)?,
+            Statement::While {
+                condition,
+                body,
+                label,
+            } => {
+                let header = format!("while {}:", self.mojo_expression(condition));
+                self.generate_loop(&header, &[], body, label)?;
+            }
+            Statement::Loop { body, label } => {
+                self.generate_loop("while True:", &[], body, label)?;
+            }
+            Statement::For {
+                pattern,
+                iterator,
+                body,
+                label,
+            } => {
+                let iterator = self.hoist_tries(iterator);
+                // Destructuring patterns bind from a single loop variable
+                let var = match pattern {
+                    Pattern::Identifier(name) => name.as_str(),
+                    Pattern::Wildcard => "_",
+                    _ => "item",
+                };
+                let mut iterable = self.mojo_expression(&iterator);
+                // Iterating a `Dict` yields its keys, so `HashMap` iteration
+                // goes through its entries, which pair a key with its value
+                let bindings: Vec<(String, String)> =
+                    match self.dict_iteration(&iterator) {
+                        Some(entries) => {
+                            if !entries {
+                                iterable = format!("{}.items()", iterable);
+                            }
+                            match pattern {
+                                Pattern::Tuple(patterns) if patterns.len() == 2 => {
+                                    let mut bindings = self
+                                        .pattern_bindings(&format!("{}[].key", var), &patterns[0]);
+                                    bindings.extend(self.pattern_bindings(
+                                        &format!("{}[].value", var),
+                                        &patterns[1],
+                                    ));
+                                    bindings
+                                }
+                                _ => Vec::new(),
+                            }
+                        }
+                        None => self
+                            .pattern_bindings(var, pattern)
+                            .into_iter()
+                            .filter(|(name, value)| name != value)
+                            .collect(),
+                    };
+                let header = format!("for {} in {}:", var, iterable);
+                self.generate_loop(&header, &bindings, body, label)?;
+            }
+            Statement::Block(stmts) => {
+                for stmt in stmts {
+                    self.generate_statement(stmt)?;
+                }
+            }
+            Statement::Break(label) => self.generate_loop_exit("break", label),
+            Statement::Continue(label) => self.generate_loop_exit("continue", label),
+            Statement::Raise(expr) => {
+                self.writeln(&format!("raise {}", self.mojo_expression(expr)));
+            }
+            Statement::Abort(message) => {
+                self.use_symbol("os", "abort");
+                let message = self.mojo_expression(message);
+                // `abort` is generic over its result so value-returning
+                // functions can hand it back in place of a value
+                match self.return_type.clone() {
+                    Some(ty) if !is_unit_like(&ty) => self.writeln(&format!(
+                        "return abort[{}]({})",
+                        self.mojo_type(&ty),
+                        message
+                    )),
+                    _ => self.writeln(&format!("abort({})", message)),
+                }
+            }
+            Statement::Match { expr, arms } => {
+                self.generate_match(expr, arms, &Tail::Discard)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Generate a block of statements, routing the trailing expression's value
+    fn generate_block(&mut self, stmts: &[Statement], tail: &Tail) -> Result<()> {
+        let Some((last, rest)) = stmts.split_last() else {
+            self.writeln("pass");
+            return Ok(());
+        };
+        for stmt in rest {
+            self.generate_statement(stmt)?;
+        }
+
+        match (last, tail) {
+            (_, Tail::Discard) => self.generate_statement(last),
+            (Statement::Expression(Expression::Block(stmts)), tail) => {
+                self.generate_inline_block(stmts, tail)
+            }
+            (Statement::Expression(expr), Tail::Assign(name)) => {
+                let expr = self.hoist_tries(expr);
+                self.writeln(&format!("{} = {}", name, self.mojo_expression(&expr)));
+                Ok(())
+            }
+            (Statement::Expression(expr), Tail::Return) => {
+                self.generate_return(Some(expr));
+                Ok(())
+            }
+            (Statement::Match { expr, arms }, tail) => self.generate_match(expr, arms, tail),
+            (
+                Statement::If {
+                    condition,
+                    then_branch,
+                    else_branch,
+                },
+                tail,
+            ) => self.generate_if(condition, then_branch, else_branch, tail),
+            (Statement::Block(stmts), tail) => self.generate_block(stmts, tail),
+            (stmt, _) => self.generate_statement(stmt),
+        }
+    }
+
+    /// Emit a block expression's statements in place
+    ///
+    /// Mojo has no block expressions, so the statements join the enclosing
+    /// block; bindings that would redeclare one of its names are renamed.
+    fn generate_inline_block(&mut self, stmts: &[Statement], tail: &Tail) -> Result<()> {
+        self.enter_block();
+        let result = self.generate_block(stmts, tail);
+        self.exit_block();
+        result
+    }
+
+    fn enter_block(&mut self) {
+        self.block_scopes
+            .push((self.locals.len(), self.block_renames.len()));
+    }
+
+    /// Forget the bindings of the block being left, so later code sees the
+    /// enclosing function's variables of the same names again
+    fn exit_block(&mut self) {
+        if let Some((locals, renames)) = self.block_scopes.pop() {
+            self.locals.truncate(locals);
+            self.block_renames.truncate(renames);
+        }
+    }
+
+    /// The innermost parameter or local visible under `name`
+    fn local(&self, name: &str) -> Option<&Local> {
+        let name = self.resolve_local(name);
+        self.locals.iter().rev().find(|local| local.name == name)
+    }
+
+    /// `name` with the lowest numeric suffix the function has not declared yet
+    fn unused_name(&self, name: &str) -> String {
+        (1..)
+            .map(|n| format!("{}_{}", name, n))
+            .find(|candidate| !self.declared.contains(candidate))
+            .expect("an unused name")
+    }
+
+    /// The name a variable was declared under, following block renames
+    fn resolve_local<'n>(&'n self, name: &'n str) -> &'n str {
+        self.block_renames
+            .iter()
+            .rev()
+            .find(|(original, _)| original == name)
+            .map_or(name, |(_, renamed)| renamed)
+    }
+
+    /// Rust type of an expression, when it is evident without type checking
+    fn expression_type(&self, expr: &Expression) -> Option<Type> {
+        let path = |name: &str| Some(Type::Path(name.to_string()));
+        match expr {
+            Expression::Literal(lit) => match lit {
+                Literal::String(_) => Some(Type::Reference {
+                    mutable: false,
+                    lifetime: None,
+                    inner: Box::new(Type::Path("str".to_string())),
+                }),
+                // Unsuffixed literals default the way they do in Rust
+                Literal::Integer(_) => path("i32"),
+                Literal::TypedInteger { suffix, .. } => path(suffix.as_deref().unwrap_or("i32")),
+                Literal::Float(_) => path("f64"),
+                Literal::Boolean(_) => path("bool"),
+                Literal::Char(_) => path("char"),
+            },
+            Expression::Identifier(name) => self.local(name).and_then(|local| local.type_.clone()),
+            Expression::Binary { left, op, right } => match binary_precedence(op) {
+                0 => None,
+                1..=COMPARISON_PRECEDENCE => path("bool"),
+                _ => self
+                    .expression_type(left)
+                    .or_else(|| self.expression_type(right)),
+            },
+            Expression::Unary {
+                op: UnaryOp::Not, ..
+            } => path("bool"),
+            Expression::Unary { operand, .. } => self.expression_type(operand),
+            Expression::Cast { type_, .. } => Some(type_.clone()),
+            Expression::Struct { name, .. } if !name.contains("::") => path(name),
+            Expression::Call { function, .. } => match &**function {
+                Expression::Identifier(name) => self
+                    .fn_returns
+                    .iter()
+                    .find(|(func, _)| func == name)
+                    .map(|(_, return_type)| return_type.clone()),
+                _ => None,
+            },
+            Expression::Block(stmts) => self.block_type(stmts),
+            _ => None,
+        }
+    }
+
+    /// Rust type of the value a block yields, from the first branch that tells
+    fn block_type(&self, stmts: &[Statement]) -> Option<Type> {
+        match stmts.last()? {
+            Statement::Expression(expr) => self.expression_type(expr),
+            Statement::If {
+                then_branch,
+                else_branch,
+                ..
+            } => self.block_type(then_branch).or_else(|| {
+                else_branch
+                    .as_deref()
+                    .and_then(|stmts| self.block_type(stmts))
+            }),
+            Statement::Match { arms, .. } => arms.iter().find_map(|arm| self.block_type(&arm.body)),
+            Statement::Block(stmts) => self.block_type(stmts),
+            _ => None,
+        }
+    }
+
+    /// Emit a `return`
+    ///
+    /// In `main`, which returns nothing in Mojo, `Ok(..)` becomes a bare
+    /// `return`, `Err(e)` is raised, and any other value is evaluated for its
+    /// side effects before returning.
+    fn generate_return(&mut self, expr: Option<&Expression>) {
+        let Some(expr) = expr else {
+            self.writeln("return");
+            return;
+        };
+        let expr = &self.hoist_tries(expr);
+        if !self.entry_point {
+            self.writeln(&format!("return {}", self.mojo_expression(expr)));
+            return;
+        }
+        match expr {
+            Expression::Call { args, .. } if is_variant_call(expr, "Err") => {
+                let error = args
+                    .iter()
+                    .map(|arg| self.mojo_expression(arg))
+                    .collect::<Vec<_>>();
+                self.writeln(&format!("raise Error({})", error.join(", ")));
+            }
+            _ if is_variant_call(expr, "Ok") => self.writeln("return"),
+            _ => {
+                self.writeln(&self.mojo_expression(expr));
+                self.writeln("return");
+            }
+        }
+    }
+
+    /// Emit the early exit of `?` on the `Result` bound to `carrier`
+    ///
+    /// The error is returned as is, except from `main`, where it is raised.
+    fn generate_try_return(&mut self, carrier: &str) {
+        self.writeln(&format!("if {}.is_err():", carrier));
+        if self.entry_point {
+            self.writeln_indented(&format!("raise Error({}.err())", carrier));
+        } else {
+            self.writeln_indented(&format!("return {}", carrier));
+        }
+    }
+
+    /// Bind the operand of each `?` that `expr` always evaluates to a temporary
+    /// with its early exit, returning `expr` with the unwrapped temporaries in
+    /// place of the `?`s
+    ///
+    /// Blocks, closures and the right operand of `&&` and `||` may not run,
+    /// so a `?` inside them is left for [`Self::mojo_expression`].
+    fn hoist_tries(&mut self, expr: &Expression) -> Expression {
+        let mut expr = expr.clone();
+        if self.returns_option() || self.returns_result() {
+            self.hoist_tries_in(&mut expr);
+        }
+        expr
+    }
+
+    fn hoist_tries_in(&mut self, expr: &mut Expression) {
+        match expr {
+            Expression::Try(inner) => {
+                self.hoist_tries_in(inner);
+                let temp = self.bind_try(inner);
+                let method = if self.returns_option() {
+                    "value"
+                } else {
+                    "unwrap"
+                };
+                *expr = Expression::MethodCall {
+                    receiver: Box::new(Expression::Identifier(temp)),
+                    method: method.to_string(),
+                    turbofish: Vec::new(),
+                    args: Vec::new(),
+                };
+            }
+            Expression::Binary {
+                left,
+                op: BinaryOp::And | BinaryOp::Or,
+                ..
+            } => self.hoist_tries_in(left),
+            Expression::Binary { left, right, .. }
+            | Expression::Index {
+                object: left,
+                index: right,
+            } => {
+                self.hoist_tries_in(left);
+                self.hoist_tries_in(right);
+            }
+            Expression::Call { function, args } => {
+                self.hoist_tries_in(function);
+                args.iter_mut().for_each(|arg| self.hoist_tries_in(arg));
+            }
+            Expression::MethodCall { receiver, args, .. } => {
+                self.hoist_tries_in(receiver);
+                args.iter_mut().for_each(|arg| self.hoist_tries_in(arg));
+            }
+            Expression::FieldAccess {
+                object: operand, ..
+            }
+            | Expression::Unary { operand, .. }
+            | Expression::Cast { expr: operand, .. }
+            | Expression::Reference { expr: operand, .. }
+            | Expression::Dereference(operand) => self.hoist_tries_in(operand),
+            Expression::Array(exprs) | Expression::Tuple(exprs) => {
+                exprs.iter_mut().for_each(|expr| self.hoist_tries_in(expr));
+            }
+            Expression::Repeat { value, count } => {
+                self.hoist_tries_in(value);
+                self.hoist_tries_in(count);
+            }
+            Expression::Range { start, end, .. } => {
+                for bound in [start, end].into_iter().flatten() {
+                    self.hoist_tries_in(bound);
+                }
+            }
+            Expression::Struct { fields, .. } => {
+                for (_, value) in fields {
+                    self.hoist_tries_in(value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Bind the operand of a `?` to a fresh temporary and emit its early exit
+    fn bind_try(&mut self, operand: &Expression) -> String {
+        let temp = next_temp("_try", &mut self.try_temps);
+        self.writeln(&format!("var {} = {}", temp, self.mojo_expression(operand)));
+        if self.returns_option() {
+            self.writeln(&format!("if not {}:", temp));
+            self.writeln_indented("return None");
+        } else {
+            self.generate_try_return(&temp);
+        }
+        temp
+    }
+
+    /// Emit an `if`, folding `else if` chains into `elif`; empty branches become `pass`
+    fn generate_if(
+        &mut self,
+        condition: &Expression,
+        then_branch: &[Statement],
+        else_branch: &Option<Vec<Statement>>,
+        tail: &Tail,
+    ) -> Result<()> {
+        let condition = self.hoist_tries(condition);
+        self.writeln(&format!("if {}:", self.mojo_expression(&condition)));
+        self.indent();
+        self.generate_block(then_branch, tail)?;
+        self.dedent();
+
+        let mut else_branch = else_branch;
+        while let Some(
+            [Statement::If {
+                condition,
+                then_branch,
+                else_branch: next,
+            }],
+        ) = else_branch.as_deref()
+        {
+            self.writeln(&format!("elif {}:", self.mojo_expression(condition)));
+            self.indent();
+            self.generate_block(then_branch, tail)?;
+            self.dedent();
+            else_branch = next;
+        }
+
+        if let Some(else_stmts) = else_branch {
+            self.writeln("else:");
+            self.indent();
+            self.generate_block(else_stmts, tail)?;
+            self.dedent();
+        } else if let Tail::Assign(name) = tail {
+            // Without an `else` the Rust value is `()`
+            self.writeln("else:");
+            self.indent();
+            self.writeln(&format!("{} = None", name));
+            self.dedent();
+        }
+        Ok(())
+    }
+
+    /// Emit a loop, lowering labeled `break`/`continue` from nested loops into flags
+    ///
+    /// Mojo has no loop labels, so `break 'outer` inside an inner loop sets
+    /// `_break_outer`, breaks the inner loop, and each enclosing loop re-checks the
+    /// flag until the labeled loop is reached.
+    fn generate_loop(
+        &mut self,
+        header: &str,
+        bindings: &[(String, String)],
+        body: &[Statement],
+        label: &Option<String>,
+    ) -> Result<()> {
+        let nested = nested_loop_exits(body);
+        let targets = |kind: &str, label: &str| {
+            nested
+                .iter()
+                .any(|(exit_kind, target)| *exit_kind == kind && target == label)
+        };
+        match label {
+            Some(label) => {
+                if targets("break", label) {
+                    self.writeln(&format!("var _break_{} = False", label));
+                }
+                if targets("continue", label) {
+                    self.writeln(&format!("var _continue_{} = False", label));
+                }
+                self.writeln(&format!("{}  # '{}", header, label));
+            }
+            None => self.writeln(header),
+        }
+
+        self.indent();
+        if let Some(label) = label {
+            if targets("continue", label) {
+                self.writeln(&format!("_continue_{} = False", label));
+            }
+        }
+        for (name, value) in bindings {
+            self.writeln(&format!("var {} = {}", name, value));
+        }
+        self.loop_labels.push(label.clone());
+        let result = self.generate_block(body, &Tail::Discard);
+        self.loop_labels.pop();
+        result?;
+        self.dedent();
+
+        // Propagate exits aimed at enclosing labeled loops
+        let enclosing = self.loop_labels.last().cloned().flatten();
+        for (kind, target) in loop_exits(body) {
+            let labeled = Some(target.clone());
+            if labeled == *label || !self.loop_labels.contains(&labeled) {
+                continue;
+            }
+            let action = if kind == "continue" && labeled == enclosing {
+                "continue"
+            } else {
+                "break"
+            };
+            self.writeln(&format!("if _{}_{}:", kind, target));
+            self.writeln_indented(action);
+        }
+        Ok(())
+    }
+
+    /// Emit `break`/`continue`, routing labeled exits past inner loops through a flag
+    fn generate_loop_exit(&mut self, kind: &str, label: &Option<String>) {
+        match label {
+            Some(target) if self.loop_labels.last() != Some(label) => {
+                self.writeln(&format!("_{}_{} = True", kind, target));
+                self.writeln("break");
+            }
+            _ => self.writeln(kind),
+        }
+    }
+
+    /// Render a block as a (possibly nested) ternary, if every branch is a single value
+    fn mojo_ternary(&self, stmts: &[Statement]) -> Option<String> {
+        match stmts {
+            [Statement::Expression(expr)] => Some(self.mojo_expression(expr)),
+            [Statement::If {
+                condition,
+                then_branch,
+                else_branch: Some(else_branch),
+            }] => Some(format!(
+                "{} if {} else {}",
+                self.mojo_ternary(then_branch)?,
+                self.mojo_expression(condition),
+                self.mojo_ternary(else_branch)?
+            )),
+            _ => None,
+        }
+    }
+
+    /// Mojo has no `match`, so arms lower to an `if`/`elif`/`else` chain
+    fn generate_match(&mut self, expr: &Expression, arms: &[MatchArm], tail: &Tail) -> Result<()> {
+        let expr = &self.hoist_tries(expr);
+        let mut scrutinee = self.mojo_expression(expr);
+        // Every arm tests the scrutinee, so anything beyond a plain name or
+        // literal is evaluated once into a temporary first
+        if !is_plain_operand(expr) {
+            let temp = next_temp("_match", &mut self.match_temps);
+            self.writeln(&format!("var {} = {}", temp, scrutinee));
+            scrutinee = temp;
+        }
+
+        for (i, arm) in arms.iter().enumerate() {
+            let mut condition = self.pattern_condition(&scrutinee, &arm.pattern);
+            // A guard joins the arm's condition, so a failed guard falls through
+            // to the next arm just as it does in Rust
+            if let Some(guard) = &arm.guard {
+                self.guard_bindings = self.pattern_bindings(&scrutinee, &arm.pattern);
+                condition = Some(match condition {
+                    Some(condition) => format!(
+                        "{} and {}",
+                        condition,
+                        self.mojo_operand(guard, binary_precedence(&BinaryOp::And), false)
+                    ),
+                    None => self.mojo_expression(guard),
+                });
+                self.guard_bindings.clear();
+            }
+
+            // Rust matches are exhaustive, so an unguarded final arm always matches
+            if i > 0 && i == arms.len() - 1 && arm.guard.is_none() {
+                condition = None;
+            }
+
+            match (&condition, i) {
+                (Some(condition), 0) => self.writeln(&format!("if {}:", condition)),
+                (Some(condition), _) => self.writeln(&format!("elif {}:", condition)),
+                (None, 0) => self.writeln("if True:"),
+                (None, _) => self.writeln("else:"),
+            }
+
+            self.indent();
+            for (name, value) in self.pattern_bindings(&scrutinee, &arm.pattern) {
+                if name != value {
+                    self.writeln(&format!("var {} = {}", name, value));
+                }
+            }
+            self.generate_block(&arm.body, tail)?;
+            self.dedent();
+
+            // Arms after an irrefutable one can never run
+            if condition.is_none() {
+                break;
+            }
+        }
         Ok(())
     }
 
-    fn generate_statement(&mut self, stmt: &Statement) -> Result<()> {
-        match stmt {
-            Statement::Expression(expr) => {
-                self.writeln(&self.mojo_expression(expr));
+    /// Variables a pattern binds, paired with the Mojo expression they are bound to
+    fn pattern_bindings(&self, scrutinee: &str, pattern: &Pattern) -> Vec<(String, String)> {
+        match pattern {
+            Pattern::Identifier(name) => vec![(name.clone(), scrutinee.to_string())],
+            Pattern::Tuple(patterns) => patterns
+                .iter()
+                .enumerate()
+                .flat_map(|(i, p)| self.pattern_bindings(&format!("{}[{}]", scrutinee, i), p))
+                .collect(),
+            Pattern::Struct { name, fields } => {
+                let (path, variant) = split_path(name);
+                let object = match self.data_variant_type(path, variant) {
+                    Some(payload) => format!("{}[{}]", scrutinee, payload),
+                    None => scrutinee.to_string(),
+                };
+                fields
+                    .iter()
+                    .flat_map(|(name, p)| self.pattern_bindings(&format!("{}.{}", object, name), p))
+                    .collect()
             }
-            Statement::Let {
-                name,
-                mutable,
-                type_,
-                value,
+            Pattern::Enum {
+                path,
+                variant,
+                fields,
             } => {
-                let keyword = if *mutable { "var" } else { "let" };
-                let type_annotation = if let Some(ty) = type_ {
-                    format!(": {}", self.mojo_type(ty))
-                } else {
-                    String::new()
-                };
-                let assignment = if let Some(val) = value {
-                    format!(" = {}", self.mojo_expression(val))
-                } else {
-                    String::new()
-                };
-                self.writeln(&format!(
-                    "{} {}{}{}",
-                    keyword, name, type_annotation, assignment
-                ));
+                let payload = self.data_variant_type(path, variant);
+                fields
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, p)| {
+                        let value = match &payload {
+                            Some(payload) => format!("{}[{}].field_{}", scrutinee, payload, i),
+                            None => variant_payload(scrutinee, path, variant, i),
+                        };
+                        self.pattern_bindings(&value, p)
+                    })
+                    .collect()
             }
-            Statement::Return(expr) => {
-                if let Some(e) = expr {
-                    self.writeln(&format!("return {}", self.mojo_expression(e)));
-                } else {
-                    self.writeln("return");
-                }
+            Pattern::Wildcard | Pattern::Literal(_) | Pattern::Or(_) | Pattern::Range { .. } => {
+                Vec::new()
             }
-            Statement::If {
-                condition,
-                then_branch,
-                else_branch,
+        }
+    }
+
+    /// Condition under which `pattern` matches `scrutinee`, or `None` if it always matches
+    fn pattern_condition(&self, scrutinee: &str, pattern: &Pattern) -> Option<String> {
+        match pattern {
+            Pattern::Wildcard | Pattern::Identifier(_) => None,
+            Pattern::Literal(lit) => Some(format!("{} == {}", scrutinee, self.mojo_literal(lit))),
+            Pattern::Tuple(patterns) => join_conditions(
+                patterns
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, p)| {
+                        self.pattern_condition(&format!("{}[{}]", scrutinee, i), p)
+                    })
+                    .collect(),
+            ),
+            Pattern::Struct { name, fields } => {
+                let (path, variant) = split_path(name);
+                let (tag, object) = match self.data_variant_type(path, variant) {
+                    Some(payload) => (
+                        Some(format!("{}.isa[{}]()", scrutinee, payload)),
+                        format!("{}[{}]", scrutinee, payload),
+                    ),
+                    None => (None, scrutinee.to_string()),
+                };
+                let fields = fields.iter().filter_map(|(name, p)| {
+                    self.pattern_condition(&format!("{}.{}", object, name), p)
+                });
+                join_conditions(tag.into_iter().chain(fields).collect())
+            }
+            Pattern::Enum {
+                path,
+                variant,
+                fields,
             } => {
-                self.writeln(&format!("if {}:", self.mojo_expression(condition)));
-                self.indent();
-                for stmt in then_branch {
-                    self.generate_statement(stmt)?;
+                if let Some(payload) = self.data_variant_type(path, variant) {
+                    let tag = format!("{}.isa[{}]()", scrutinee, payload);
+                    let fields = fields.iter().enumerate().filter_map(|(i, p)| {
+                        self.pattern_condition(
+                            &format!("{}[{}].field_{}", scrutinee, payload, i),
+                            p,
+                        )
+                    });
+                    return join_conditions(std::iter::once(tag).chain(fields).collect());
                 }
-                self.dedent();
-
-                if let Some(else_stmts) = else_branch {
-                    self.writeln("else:");
-                    self.indent();
-                    for stmt in else_stmts {
-                        self.generate_statement(stmt)?;
+                let tag = match variant.as_str() {
+                    "Some" => scrutinee.to_string(),
+                    "None" => format!("not {}", scrutinee),
+                    _ if fields.is_empty() => {
+                        format!("{} == {}", scrutinee, mojo_variant_path(path, variant))
                     }
-                    self.dedent();
-                }
+                    _ => format!("{}.isa[{}]()", scrutinee, mojo_variant_path(path, variant)),
+                };
+                let payload = fields.iter().enumerate().filter_map(|(i, p)| {
+                    self.pattern_condition(&variant_payload(scrutinee, path, variant, i), p)
+                });
+                join_conditions(std::iter::once(tag).chain(payload).collect())
             }
-            Statement::While { condition, body } => {
-                self.writeln(&format!("while {}:", self.mojo_expression(condition)));
-                self.indent();
-                for stmt in body {
-                    self.generate_statement(stmt)?;
-                }
-                self.dedent();
+            Pattern::Or(patterns) => {
+                let conditions: Option<Vec<String>> = patterns
+                    .iter()
+                    .map(|p| self.pattern_condition(scrutinee, p))
+                    .collect();
+                conditions.map(|conditions| format!("({})", conditions.join(" or ")))
             }
-            Statement::For {
-                pattern,
-                iterator,
-                body,
+            Pattern::Range {
+                start,
+                end,
+                inclusive,
             } => {
-                self.writeln(&format!(
-                    "for {} in {}:",
-                    pattern,
-                    self.mojo_expression(iterator)
-                ));
-                self.indent();
-                for stmt in body {
-                    self.generate_statement(stmt)?;
+                let mut conditions = Vec::new();
+                if let Some(start) = start {
+                    conditions.push(format!("{} >= {}", scrutinee, self.mojo_literal(start)));
                 }
-                self.dedent();
-            }
-            Statement::Block(stmts) => {
-                for stmt in stmts {
-                    self.generate_statement(stmt)?;
+                if let Some(end) = end {
+                    let op = if *inclusive { "<=" } else { "<" };
+                    conditions.push(format!("{} {} {}", scrutinee, op, self.mojo_literal(end)));
                 }
-            }
-            _ => {
-                self.writeln("# TODO: Statement conversion not yet implemented");
+                join_conditions(conditions)
             }
         }
-        Ok(())
     }
 
     fn mojo_expression(&self, expr: &Expression) -> String {
+        self.count_node();
         match expr {
             Expression::Literal(lit) => self.mojo_literal(lit),
-            Expression::Identifier(name) => name.clone(),
-            Expression::Path(path) => path.clone(),
+            Expression::Identifier(name) => self
+                .guard_bindings
+                .iter()
+                .find(|(binding, _)| binding == name)
+                .map_or_else(
+                    || self.resolve_local(name).to_string(),
+                    |(_, value)| value.clone(),
+                ),
+            Expression::Path(path) => path.replace("::", "."),
             Expression::Call { function, args } => {
                 let func_str = self.mojo_expression(function);
                 let args_str = args
                     .iter()
-                    .map(|arg| self.mojo_expression(arg))
+                    .map(|arg| match arg {
+                        // Keyword argument, e.g. `print(x, end="")`
+                        Expression::Binary {
+                            left,
+                            op: BinaryOp::Assign,
+                            right,
+                        } if matches!(**left, Expression::Identifier(_)) => format!(
+                            "{}={}",
+                            self.mojo_expression(left),
+                            self.mojo_expression(right)
+                        ),
+                        arg => self.mojo_expression(arg),
+                    })
                     .collect::<Vec<_>>()
                     .join(", ");
                 format!("{}({})", func_str, args_str)
             }
+            Expression::MethodCall {
+                receiver,
+                method,
+                turbofish,
+                args,
+            } => self.mojo_method_call(receiver, method, turbofish, args),
+            Expression::Array(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|element| self.mojo_expression(element))
+                    .collect::<Vec<_>>();
+                self.use_symbol("collections", "List");
+                format!("List({})", elements.join(", "))
+            }
+            Expression::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let end = match end {
+                    Some(end) if *inclusive => format!("{} + 1", self.mojo_expression(end)),
+                    Some(end) => self.mojo_expression(end),
+                    None => {
+                        self.warn("unbounded range end has no Mojo `range` equivalent");
+                        "Int.MAX".to_string()
+                    }
+                };
+                match start {
+                    Some(start) => format!("range({}, {})", self.mojo_expression(start), end),
+                    None => format!("range({})", end),
+                }
+            }
+            Expression::Tuple(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|element| self.mojo_expression(element))
+                    .collect::<Vec<_>>();
+                match elements.as_slice() {
+                    // `()` is Rust's unit value
+                    [] => "None".to_string(),
+                    [single] => format!("({},)", single),
+                    _ => format!("({})", elements.join(", ")),
+                }
+            }
+            Expression::Repeat { value, count } => {
+                self.use_symbol("collections", "List");
+                format!(
+                    "List(length={}, fill={})",
+                    self.mojo_expression(count),
+                    self.mojo_expression(value)
+                )
+            }
+            // Mojo closures are nested `fn`s, which can't be written inline
+            Expression::Closure { .. } => {
+                self.warn("unsupported closure emitted as a placeholder");
+                self.unsupported
+                    .borrow_mut()
+                    .push(("closure".to_string(), None));
+                "\"unsupported_closure\"".to_string()
+            }
+            // Blocks that need statements of their own are handled where they are bound
+            Expression::Block(stmts) => match self.mojo_ternary(stmts) {
+                Some(value) => value,
+                None => {
+                    self.warn("block expression emitted as a placeholder");
+                    self.unsupported
+                        .borrow_mut()
+                        .push(("block expression".to_string(), None));
+                    "\"unsupported_block\"".to_string()
+                }
+            },
+            Expression::Unsupported {
+                construct,
+                location,
+            } => {
+                self.warn_at(
+                    format!("unsupported {} emitted as a placeholder", construct),
+                    *location,
+                );
+                self.unsupported
+                    .borrow_mut()
+                    .push((construct.clone(), *location));
+                "\"unsupported_expr\"".to_string()
+            }
+            // The generated `__init__` takes fields by name, so construct with keywords
+            Expression::Struct { name, fields } => {
+                let fields = fields
+                    .iter()
+                    .map(|(field, value)| format!("{}={}", field, self.mojo_expression(value)))
+                    .collect::<Vec<_>>();
+                format!("{}({})", name.replace("::", "."), fields.join(", "))
+            }
+            Expression::FieldAccess { object, field } => {
+                let object = self.mojo_expression(object);
+                // Tuple fields are positional, e.g. `pair.0` -> `pair[0]`
+                if field.parse::<usize>().is_ok() {
+                    format!("{}[{}]", object, field)
+                } else {
+                    format!("{}.{}", object, field)
+                }
+            }
+            Expression::Index { object, index } => {
+                let index_str = match &**index {
+                    // Indexing with a range is slicing, e.g. `v[1..3]` -> `v[1:3]`
+                    Expression::Range {
+                        start,
+                        end,
+                        inclusive,
+                    } => {
+                        let start = start
+                            .as_ref()
+                            .map(|start| self.mojo_expression(start))
+                            .unwrap_or_default();
+                        let end = match end {
+                            Some(end) if *inclusive => format!("{} + 1", self.mojo_expression(end)),
+                            Some(end) => self.mojo_expression(end),
+                            None => String::new(),
+                        };
+                        format!("{}:{}", start, end)
+                    }
+                    index => self.mojo_expression(index),
+                };
+                format!("{}[{}]", self.mojo_expression(object), index_str)
+            }
+            Expression::Unary { op, operand } => {
+                match op {
+                    UnaryOp::Neg => {
+                        format!("-{}", self.mojo_operand(operand, precedence(expr), false))
+                    }
+                    UnaryOp::Not => format!(
+                        "not {}",
+                        self.mojo_operand(operand, precedence(expr), false)
+                    ),
+                    // References are implicit in Mojo, so dereferencing one is a no-op
+                    UnaryOp::Deref => self.mojo_expression(operand),
+                }
+            }
+            Expression::Try(operand) => {
+                let inner = self.mojo_expression(operand);
+                match self.return_type.as_ref().and_then(try_carrier) {
+                    Some("Result") => {
+                        self.warn(
+                            "`?` on a Result that is only conditionally evaluated is lowered to `.unwrap()` without an early return",
+                        );
+                        format!("{}.unwrap()", inner)
+                    }
+                    Some(_) => {
+                        self.warn(
+                            "`?` on an Option that is only conditionally evaluated is lowered to `.value()` without an early return",
+                        );
+                        format!("{}.value()", inner)
+                    }
+                    // There is no early exit to lower to, but the payload is still unwrapped
+                    None => {
+                        let is_option = matches!(
+                            self.expression_type(operand).as_ref().and_then(try_carrier),
+                            Some("Option")
+                        );
+                        let method = if is_option { "value" } else { "unwrap" };
+                        self.warn(format!(
+                            "`?` used in a function that returns neither Result nor Option is lowered to `.{}()` without an early return",
+                            method
+                        ));
+                        format!("{}.{}()", inner, method)
+                    }
+                }
+            }
+            // Mojo passes by reference implicitly, so `&x` is just `x`
+            Expression::Reference { expr, .. } => self.mojo_expression(expr),
             Expression::Binary { left, op, right } => {
+                let parent = binary_precedence(op);
                 format!(
                     "{} {} {}",
-                    self.mojo_expression(left),
+                    self.mojo_operand(left, parent, false),
                     self.mojo_binary_op(op),
-                    self.mojo_expression(right)
+                    self.mojo_operand(right, parent, true)
                 )
             }
             _ => "# TODO: Expression conversion".to_string(),
         }
     }
 
+    /// Render an operand of an operator with precedence `parent`, parenthesized
+    /// when Mojo would otherwise group it differently
+    ///
+    /// Mojo's binary operators associate to the left, so a right operand of the
+    /// same precedence also needs parentheses. Comparisons chain in Mojo
+    /// (`a < b == c` means `a < b and b == c`), so they never nest bare.
+    fn mojo_operand(&self, operand: &Expression, parent: u8, right: bool) -> String {
+        let child = precedence(operand);
+        let rendered = self.mojo_expression(operand);
+        // Assignments are statements in Mojo, so their operands never need grouping
+        let needs_parens = parent > 0
+            && (child < parent || (child == parent && (right || parent == COMPARISON_PRECEDENCE)));
+        if needs_parens {
+            format!("({})", rendered)
+        } else {
+            rendered
+        }
+    }
+
+    fn mojo_method_call(
+        &self,
+        receiver: &Expression,
+        method: &str,
+        turbofish: &[Type],
+        args: &[Expression],
+    ) -> String {
+        // `s.parse::<T>()` already raises on failure in Mojo, so unwrapping it is a no-op
+        if matches!(method, "unwrap" | "expect") && is_parse_call(receiver) {
+            return self.mojo_expression(receiver);
+        }
+
+        let recv = self.mojo_expression(receiver);
+        let args_str = args
+            .iter()
+            .map(|arg| self.mojo_expression(arg))
+            .collect::<Vec<_>>();
+
+        let collection = self.collection(receiver);
+        if collection == Some(Collection::List)
+            && matches!(
+                (method, args_str.len()),
+                ("first" | "last" | "pop", 0) | ("get", 1)
+            )
+        {
+            self.use_symbol("collections", "Optional");
+        }
+        match (method, args_str.as_slice()) {
+            // Slice accessors return `Option` in Rust; Mojo's `List` has no
+            // checked accessors, so guard the index and wrap in `Optional`.
+            // The guard is evaluated first, so that is where operands are bound.
+            ("first", []) if collection == Some(Collection::List) => {
+                let (list, list_again) = bind_once(recv, receiver, "__list");
+                format!(
+                    "(Optional({1}[0]) if len({0}) > 0 else None)",
+                    list, list_again
+                )
+            }
+            ("last", []) if collection == Some(Collection::List) => {
+                let (list, list_again) = bind_once(recv, receiver, "__list");
+                format!(
+                    "(Optional({1}[len({1}) - 1]) if len({0}) > 0 else None)",
+                    list, list_again
+                )
+            }
+            ("get", [index]) if collection == Some(Collection::List) => {
+                let (index, index_again) = bind_once(index.clone(), &args[0], "__index");
+                let (list, list_again) = bind_once(recv, receiver, "__list");
+                format!(
+                    "(Optional({2}[{3}]) if {0} < len({1}) else None)",
+                    index, list, list_again, index_again
+                )
+            }
+            // Standard conversion traits: Mojo converts through constructors and
+            // copies on assignment, so these need no method call of their own
+            ("to_string" | "to_owned", []) => format!("String({})", recv),
+            ("clone" | "into", []) => recv,
+            // `Vec` mutation methods onto their Mojo `List` equivalents
+            ("push", [value]) if collection == Some(Collection::List) => {
+                format!("{}.append({})", recv, value)
+            }
+            ("pop", []) if collection == Some(Collection::List) => {
+                let (list, list_again) = bind_once(recv, receiver, "__list");
+                format!(
+                    "(Optional({1}.pop()) if len({0}) > 0 else None)",
+                    list, list_again
+                )
+            }
+            ("insert", [index, value]) if collection == Some(Collection::List) => {
+                format!("{}.insert({}, {})", recv, index, value)
+            }
+            ("remove", [index]) if collection == Some(Collection::List) => {
+                format!("{}.pop({})", recv, index)
+            }
+            // Moving `other`'s elements also empties it; see `generate_statement`
+            ("append", [other]) if collection == Some(Collection::List) => {
+                self.warn(format!(
+                    "`append` inside an expression becomes `extend`, which does not empty `{}`",
+                    other
+                ));
+                format!("{}.extend({})", recv, other)
+            }
+            // `HashMap` methods whose `Dict` counterparts differ
+            ("iter" | "iter_mut", []) if collection == Some(Collection::Dict) => {
+                format!("{}.items()", recv)
+            }
+            ("insert", [key, value]) if collection == Some(Collection::Dict) => {
+                format!("{}[{}] = {}", recv, key, value)
+            }
+            ("contains_key", [key]) if collection == Some(Collection::Dict) => {
+                format!("{} in {}", key, recv)
+            }
+            ("remove", [key]) if collection == Some(Collection::Dict) => {
+                self.use_symbol("collections", "Optional");
+                let (key, key_again) = bind_once(key.clone(), &args[0], "__key");
+                format!(
+                    "(Optional({0}.pop({2})) if {1} in {0} else None)",
+                    recv, key, key_again
+                )
+            }
+            ("truncate", [len]) if collection == Some(Collection::List) => {
+                format!("{}.resize({})", recv, len)
+            }
+            ("parse", []) => match turbofish.first() {
+                Some(Type::Path(path)) if matches!(path.as_str(), "f32" | "f64") => {
+                    format!("{}(atof({}))", self.mojo_type(&turbofish[0]), recv)
+                }
+                Some(ty) => format!("{}(atol({}))", self.mojo_type(ty), recv),
+                None => format!("atol({})", recv),
+            },
+            _ => {
+                if UNMAPPED_LIST_MUTATIONS.contains(&method) {
+                    self.warn(format!(
+                        "`{}` has no Mojo `List` equivalent; emitting the call unchanged",
+                        method
+                    ));
+                }
+                format!("{}.{}({})", recv, method, args_str.join(", "))
+            }
+        }
+    }
+
     fn mojo_literal(&self, lit: &Literal) -> String {
         match lit {
-            Literal::String(s) => format!("\"{}\"", s),
+            Literal::String(s) => format!("\"{}\"", escape_mojo_string(s)),
             Literal::Integer(i) => i.to_string(),
+            // Mojo integer literals are arbitrary precision, so only the suffix needs a type
+            Literal::TypedInteger { digits, suffix } => {
+                match suffix.as_deref().and_then(map_primitive) {
+                    Some(mojo_type) => format!("{}({})", mojo_type, digits),
+                    None => digits.clone(),
+                }
+            }
             Literal::Float(f) => f.to_string(),
             Literal::Boolean(b) => {
                 if *b {
@@ -327,7 +2750,8 @@ impl MojoGenerator {
                     "False".to_string()
                 }
             }
-            Literal::Char(c) => format!("\"{}\"", c),
+            // `char` maps to `String`, so a char literal is a one-character string
+            Literal::Char(c) => format!("\"{}\"", escape_mojo_string(&c.to_string())),
         }
     }
 
@@ -352,64 +2776,112 @@ impl MojoGenerator {
             BinaryOp::Shl => "<<",
             BinaryOp::Shr => ">>",
             BinaryOp::Assign => "=",
+            BinaryOp::AddAssign => "+=",
+            BinaryOp::SubAssign => "-=",
+            BinaryOp::MulAssign => "*=",
+            BinaryOp::DivAssign => "/=",
+            BinaryOp::ModAssign => "%=",
+            BinaryOp::BitAndAssign => "&=",
+            BinaryOp::BitOrAssign => "|=",
+            BinaryOp::BitXorAssign => "^=",
+            BinaryOp::ShlAssign => "<<=",
+            BinaryOp::ShrAssign => ">>=",
         }
     }
 
     #[allow(clippy::only_used_in_recursion)]
     fn mojo_type(&self, ty: &Type) -> String {
         match ty {
-            Type::Path(path) => {
-                // Map common Rust types to Mojo types
-                match path.as_str() {
-                    "i32" => "Int32".to_string(),
-                    "i64" => "Int64".to_string(),
-                    "u32" => "UInt32".to_string(),
-                    "u64" => "UInt64".to_string(),
-                    "f32" => "Float32".to_string(),
-                    "f64" => "Float64".to_string(),
-                    "bool" => "Bool".to_string(),
-                    "String" => "String".to_string(),
-                    "str" => "StringRef".to_string(),
-                    _ => path.clone(),
-                }
-            }
-            Type::Reference { inner, .. } => {
+            Type::Path(path) => map_primitive(path).unwrap_or(path).to_string(),
+            Type::Reference { inner, .. } => match &**inner {
+                // A borrowed slice is a view into someone else's buffer
+                Type::Slice(element) => {
+                    self.use_symbol("utils", "Span");
+                    format!("Span[{}]", self.mojo_type(element))
+                }
                 // Mojo doesn't have explicit references, use the inner type
-                self.mojo_type(inner)
-            }
+                inner => self.mojo_type(inner),
+            },
             Type::Array { inner, size } => {
                 if let Some(s) = size {
-                    format!("StaticTuple[{}, {}]", self.mojo_type(inner), s)
+                    self.use_symbol("collections", "InlineArray");
+                    format!("InlineArray[{}, {}]", self.mojo_type(inner), s)
                 } else {
-                    format!("List[{}]", self.mojo_type(inner))
+                    let list = format!("List[{}]", self.mojo_type(inner));
+                    self.warn(format!(
+                        "array length is not an integer literal; `[{}; _]` emitted as `{}`",
+                        self.mojo_type(inner),
+                        list
+                    ));
+                    self.use_symbol("collections", "List");
+                    list
                 }
             }
             Type::Slice(inner) => {
+                self.use_symbol("collections", "List");
                 format!("List[{}]", self.mojo_type(inner))
             }
             Type::Tuple(types) => {
                 let type_strs = types.iter().map(|t| self.mojo_type(t)).collect::<Vec<_>>();
                 format!("Tuple[{}]", type_strs.join(", "))
             }
-            Type::Unit => "NoneType".to_string(),
-            _ => "# TODO: Type conversion".to_string(),
+            Type::Generic { name, args } => {
+                let args = args.iter().map(|t| self.mojo_type(t)).collect::<Vec<_>>();
+                let container = mojo_generic_container(name);
+                if let Some(symbol) = collections_symbol(container) {
+                    self.use_symbol("collections", symbol);
+                }
+                format!("{}[{}]", container, args.join(", "))
+            }
+            Type::Pointer { inner, .. } => {
+                // Mojo 24.5's UnsafePointer has no mutability parameter, so
+                // `*const T` and `*mut T` map to the same type
+                self.use_symbol("memory", "UnsafePointer");
+                format!("UnsafePointer[{}]", self.mojo_type(inner))
+            }
+            Type::Function { params, return_ } => {
+                let params = params.iter().map(|t| self.mojo_type(t)).collect::<Vec<_>>();
+                let return_ = match **return_ {
+                    Type::Unit | Type::Never => "None".to_string(),
+                    ref ty => self.mojo_type(ty),
+                };
+                format!("fn({}) -> {}", params.join(", "), return_)
+            }
+            Type::Unit | Type::Never => "NoneType".to_string(),
         }
     }
 
-    fn mojo_visibility(&self, vis: &Visibility) -> &'static str {
-        match vis {
-            Visibility::Public => "", // Mojo defaults to public
-            _ => "",                  // Mojo doesn't have explicit private visibility
-        }
+    /// Mojo has no access modifiers: everything is public, so no prefix is emitted
+    fn mojo_visibility(&self, _vis: &Visibility) -> &'static str {
+        ""
     }
 
+    /// Append `text`, prefixing each line that starts here with the current indentation
     fn write(&mut self, text: &str) {
-        self.output.push_str(text);
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.output.push('\n');
+            }
+            // Only indent at the start of a line, so signatures built from several
+            // `write` calls stay on one correctly indented line
+            let at_line_start = self.output.is_empty() || self.output.ends_with('\n');
+            if at_line_start && !line.is_empty() {
+                self.output.push_str(&INDENT.repeat(self.indent_level));
+            }
+            self.output.push_str(line);
+        }
     }
 
     fn writeln(&mut self, text: &str) {
-        let indent = "    ".repeat(self.indent_level);
-        self.output.push_str(&format!("{}{}\n", indent, text));
+        self.write(text);
+        self.output.push('\n');
+    }
+
+    /// Write a line one level deeper than the current indentation
+    fn writeln_indented(&mut self, text: &str) {
+        self.indent();
+        self.writeln(text);
+        self.dedent();
     }
 
     fn indent(&mut self) {
@@ -422,3 +2894,375 @@ impl MojoGenerator {
         }
     }
 }
+
+/// Whether a struct is move-only in Rust and owns fields that need to be moved
+fn needs_move_init(struct_item: &Struct) -> bool {
+    let is_copy = struct_item
+        .attributes
+        .iter()
+        .any(|attr| attr.path == "derive" && attr.tokens.split(',').any(|t| t.trim() == "Copy"));
+    !is_copy
+        && struct_item
+            .fields
+            .iter()
+            .any(|field| !is_trivially_movable(&field.type_))
+}
+
+/// Primitive types are bit-copied, so moving them needs no special handling
+fn is_trivially_movable(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => matches!(
+            path.as_str(),
+            "i8" | "i16"
+                | "i32"
+                | "i64"
+                | "i128"
+                | "isize"
+                | "u8"
+                | "u16"
+                | "u32"
+                | "u64"
+                | "u128"
+                | "usize"
+                | "f32"
+                | "f64"
+                | "bool"
+                | "char"
+        ),
+        Type::Unit => true,
+        _ => false,
+    }
+}
+
+/// Extract the message from `#[deprecated = "..."]` or `#[deprecated(note = "...")]`
+fn deprecation_note(tokens: &str) -> Option<String> {
+    use syn::parse::Parser;
+
+    if let Ok(lit) = syn::parse_str::<syn::LitStr>(tokens) {
+        return Some(lit.value());
+    }
+    let args = syn::punctuated::Punctuated::<syn::MetaNameValue, syn::Token![,]>::parse_terminated
+        .parse_str(tokens)
+        .ok()?;
+    args.iter()
+        .find(|arg| arg.path.is_ident("note"))
+        .and_then(|arg| match &arg.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit),
+                ..
+            }) => Some(lit.value()),
+            _ => None,
+        })
+}
+
+/// Items of a unit, with the contents of its modules in place of the modules
+fn flatten_modules(items: &[Item]) -> Vec<&Item> {
+    items
+        .iter()
+        .flat_map(|item| match item {
+            Item::Mod(module) => flatten_modules(&module.items),
+            _ => vec![item],
+        })
+        .collect()
+}
+
+/// Name of the type an impl block targets, if it is a plain path
+fn impl_target_name(impl_item: &Impl) -> Option<&str> {
+    match &impl_item.target_type {
+        Type::Path(path) => Some(path.as_str()),
+        _ => None,
+    }
+}
+
+/// `struct Name:` line, listing the traits it conforms to if there are any
+fn struct_header(visibility: &str, name: &str, conformances: &[String]) -> String {
+    if conformances.is_empty() {
+        format!("{}struct {}:", visibility, name)
+    } else {
+        format!(
+            "{}struct {}({}):",
+            visibility,
+            name,
+            conformances.join(", ")
+        )
+    }
+}
+
+/// Last segment of a (possibly qualified) trait path, e.g. `fmt::Display` -> `Display`
+fn trait_name(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+/// Mojo trait a struct conforms to when it implements the given Rust trait
+fn mojo_trait_conformance(path: &str) -> Option<&'static str> {
+    match trait_name(path) {
+        "Display" => Some("Stringable"),
+        _ => None,
+    }
+}
+
+/// Labeled `break`/`continue` exits anywhere in `stmts`, as `(kind, label)` pairs
+fn loop_exits(stmts: &[Statement]) -> Vec<(&'static str, String)> {
+    let mut exits = Vec::new();
+    for stmt in stmts {
+        match stmt {
+            Statement::Break(Some(label)) => exits.push(("break", label.clone())),
+            Statement::Continue(Some(label)) => exits.push(("continue", label.clone())),
+            _ => {}
+        }
+        for body in child_blocks(stmt) {
+            exits.extend(loop_exits(body));
+        }
+    }
+    exits.sort();
+    exits.dedup();
+    exits
+}
+
+/// Labeled exits that occur inside loops nested within `stmts`
+fn nested_loop_exits(stmts: &[Statement]) -> Vec<(&'static str, String)> {
+    let mut exits = Vec::new();
+    for stmt in stmts {
+        match stmt {
+            Statement::While { body, .. }
+            | Statement::Loop { body, .. }
+            | Statement::For { body, .. } => exits.extend(loop_exits(body)),
+            _ => {
+                for body in child_blocks(stmt) {
+                    exits.extend(nested_loop_exits(body));
+                }
+            }
+        }
+    }
+    exits
+}
+
+/// Statement lists directly nested in a statement
+fn child_blocks(stmt: &Statement) -> Vec<&[Statement]> {
+    match stmt {
+        Statement::If {
+            then_branch,
+            else_branch,
+            ..
+        } => std::iter::once(then_branch.as_slice())
+            .chain(else_branch.as_deref())
+            .collect(),
+        Statement::While { body, .. }
+        | Statement::Loop { body, .. }
+        | Statement::For { body, .. }
+        | Statement::Block(body) => vec![body.as_slice()],
+        Statement::Match { arms, .. } => arms.iter().map(|arm| arm.body.as_slice()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Combine pattern sub-conditions with `and`, or `None` if there are none
+fn join_conditions(conditions: Vec<String>) -> Option<String> {
+    if conditions.is_empty() {
+        None
+    } else {
+        Some(conditions.join(" and "))
+    }
+}
+
+/// Mojo spelling of an enum variant path, e.g. `State::Idle` -> `State.Idle`
+fn mojo_variant_path(path: &str, variant: &str) -> String {
+    if path.is_empty() {
+        variant.to_string()
+    } else {
+        format!("{}.{}", path.replace("::", "."), variant)
+    }
+}
+
+/// Escape text for the inside of a double-quoted Mojo string literal
+///
+/// Mojo source is UTF-8, so non-ASCII characters are kept as-is; only quotes,
+/// backslashes and control characters need escapes.
+fn escape_mojo_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\x{:02x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Whether `func` is the program's entry point, `fn main()`
+fn is_entry_point(func: &Function) -> bool {
+    func.name == "main" && func.parameters.is_empty()
+}
+
+/// Whether `expr` wraps a value in the enum variant `variant`, e.g. `Ok(())`
+fn is_variant_call(expr: &Expression, variant: &str) -> bool {
+    matches!(
+        expr,
+        Expression::Call { function, .. } if matches!(&**function, Expression::Identifier(name) if name == variant)
+    )
+}
+
+/// Trait names listed in the `#[derive(...)]` attributes of an item
+fn derives(attributes: &[Attribute]) -> impl Iterator<Item = &str> {
+    attributes
+        .iter()
+        .filter(|attr| attr.path == "derive")
+        .flat_map(|attr| attr.tokens.split(','))
+        .map(str::trim)
+        .filter(|derive| !derive.is_empty())
+}
+
+/// Next name for a temporary: `base`, then `base_1`, `base_2` and so on
+fn next_temp(base: &str, count: &mut usize) -> String {
+    let temp = match *count {
+        0 => base.to_string(),
+        n => format!("{}_{}", base, n),
+    };
+    *count += 1;
+    temp
+}
+
+/// `Result` or `Option`, when `ty` is a type that `?` can be used to return from
+fn try_carrier(ty: &Type) -> Option<&'static str> {
+    let name = match ty {
+        Type::Generic { name, .. } | Type::Path(name) => trait_name(name),
+        _ => return None,
+    };
+    match name {
+        "Result" => Some("Result"),
+        "Option" => Some("Option"),
+        _ => None,
+    }
+}
+
+/// Whether every variant of an enum is a unit variant
+fn is_c_like(enum_item: &Enum) -> bool {
+    enum_item
+        .variants
+        .iter()
+        .all(|variant| variant.data == VariantData::Unit)
+}
+
+/// Name of the struct holding a data-carrying enum variant's payload
+fn payload_type_name(enum_name: &str, variant: &str) -> String {
+    format!("{}_{}", enum_name, variant)
+}
+
+/// A variant's payload as named fields, with tuple fields named like tuple structs
+fn variant_fields(data: &VariantData) -> Vec<Field> {
+    match data {
+        VariantData::Unit => Vec::new(),
+        VariantData::Tuple(types) => types
+            .iter()
+            .enumerate()
+            .map(|(i, type_)| Field {
+                name: format!("field_{}", i),
+                visibility: Visibility::Public,
+                type_: type_.clone(),
+            })
+            .collect(),
+        VariantData::Struct(fields) => fields.clone(),
+    }
+}
+
+/// Split `Enum::Variant` into the enum path and the final segment
+fn split_path(path: &str) -> (&str, &str) {
+    path.rsplit_once("::").unwrap_or(("", path))
+}
+
+/// Expression for the `index`th payload value of an enum variant
+fn variant_payload(scrutinee: &str, path: &str, variant: &str, index: usize) -> String {
+    match variant {
+        "Some" => format!("{}.value()", scrutinee),
+        _ => format!(
+            "{}[{}][{}]",
+            scrutinee,
+            mojo_variant_path(path, variant),
+            index
+        ),
+    }
+}
+
+/// Whether any statement (including nested blocks) raises, in which case the
+/// enclosing Mojo function must be declared `raises`.
+fn statements_raise(stmts: &[Statement]) -> bool {
+    stmts.iter().any(|stmt| match stmt {
+        Statement::Raise(_) => true,
+        Statement::Abort(message) => expression_raises(message),
+        Statement::Break(_) | Statement::Continue(_) => false,
+        Statement::Expression(expr) => expression_raises(expr),
+        Statement::Let { value, .. } => value.as_ref().is_some_and(expression_raises),
+        Statement::LetPattern { value, .. } => expression_raises(value),
+        Statement::Return(expr) => expr.as_ref().is_some_and(expression_raises),
+        Statement::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            expression_raises(condition)
+                || statements_raise(then_branch)
+                || else_branch.as_deref().is_some_and(statements_raise)
+        }
+        Statement::While {
+            condition, body, ..
+        } => expression_raises(condition) || statements_raise(body),
+        Statement::Loop { body, .. } => statements_raise(body),
+        Statement::For { iterator, body, .. } => {
+            expression_raises(iterator) || statements_raise(body)
+        }
+        Statement::Match { expr, arms } => {
+            expression_raises(expr) || arms.iter().any(|arm| statements_raise(&arm.body))
+        }
+        Statement::Block(stmts) => statements_raise(stmts),
+    })
+}
+
+/// Whether evaluating an expression can raise in the generated Mojo
+fn expression_raises(expr: &Expression) -> bool {
+    match expr {
+        Expression::Literal(_) | Expression::Identifier(_) | Expression::Path(_) => false,
+        Expression::Call { function, args } => {
+            expression_raises(function) || args.iter().any(expression_raises)
+        }
+        Expression::MethodCall { receiver, args, .. } => {
+            is_parse_call(expr) || expression_raises(receiver) || args.iter().any(expression_raises)
+        }
+        Expression::FieldAccess { object, .. } => expression_raises(object),
+        Expression::Index { object, index } => {
+            expression_raises(object) || expression_raises(index)
+        }
+        Expression::Binary { left, right, .. } => {
+            expression_raises(left) || expression_raises(right)
+        }
+        Expression::Unary { operand, .. } => expression_raises(operand),
+        Expression::Cast { expr, .. } | Expression::Reference { expr, .. } => {
+            expression_raises(expr)
+        }
+        Expression::Dereference(expr) => expression_raises(expr),
+        // In a `Result` function, `?` propagates the error as a Mojo exception
+        Expression::Try(inner) => expression_raises(inner),
+        Expression::Block(stmts) => statements_raise(stmts),
+        Expression::Repeat { value, count } => expression_raises(value) || expression_raises(count),
+        Expression::Range { start, end, .. } => [start, end]
+            .into_iter()
+            .flatten()
+            .any(|expr| expression_raises(expr)),
+        Expression::Array(exprs) | Expression::Tuple(exprs) => exprs.iter().any(expression_raises),
+        Expression::Struct { fields, .. } => {
+            fields.iter().any(|(_, value)| expression_raises(value))
+        }
+        // A closure body only runs when the closure is called
+        Expression::Closure { .. } => false,
+        Expression::Unsupported { .. } => false,
+    }
+}
+
+/// Whether an expression is a `str::parse` call, which lowers to a raising `atol`/`atof`
+fn is_parse_call(expr: &Expression) -> bool {
+    matches!(expr, Expression::MethodCall { method, args, .. } if method == "parse" && args.is_empty())
+}
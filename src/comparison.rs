@@ -2,9 +2,19 @@
 //!
 //! This module provides functionality to compare the output of rust2mojo with
 //! LLM-generated Mojo code for validation and improvement purposes.
+//! Similarity metrics and [`ComparisonEngine::compare_against_reference`] are
+//! always available; querying the LLM needs the `comparison` feature.
 
+#[cfg(feature = "comparison")]
+use crate::Error;
 use crate::{Compiler, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// `anthropic-version` header sent with every Messages API request
+#[cfg(feature = "comparison")]
+const ANTHROPIC_API_VERSION: &str = "2023-06-01";
 
 /// Configuration for LLM comparison
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +29,10 @@ pub struct ComparisonConfig {
     pub max_tokens: usize,
     /// Temperature for LLM generation (0.0 = deterministic, 1.0 = creative)
     pub temperature: f32,
+    /// Environment variable holding the API key for the LLM service
+    pub api_key_env: String,
+    /// Directory for caching LLM responses across runs; `None` disables caching
+    pub cache_dir: Option<PathBuf>,
 }
 
 impl Default for ComparisonConfig {
@@ -29,12 +43,14 @@ impl Default for ComparisonConfig {
             api_endpoint: "https://api.anthropic.com/v1/messages".to_string(),
             max_tokens: 4096,
             temperature: 0.1, // Low temperature for more consistent code generation
+            api_key_env: "ANTHROPIC_API_KEY".to_string(),
+            cache_dir: None,
         }
     }
 }
 
 /// Comparison result between rust2mojo output and LLM-generated code
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ComparisonResult {
     /// Original Rust code
     pub rust_code: String,
@@ -48,8 +64,21 @@ pub struct ComparisonResult {
     pub analysis: QualitativeAnalysis,
 }
 
-/// Quantitative similarity metrics
+/// Comparison result between rust2mojo output and a handwritten reference
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferenceComparisonResult {
+    /// Original Rust code
+    pub rust_code: String,
+    /// rust2mojo generated Mojo code
+    pub rust2mojo_output: String,
+    /// Known-good reference Mojo code
+    pub reference_output: String,
+    /// Similarity metrics
+    pub metrics: SimilarityMetrics,
+}
+
+/// Quantitative similarity metrics
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SimilarityMetrics {
     /// Structural similarity (0.0 to 1.0)
     pub structural_similarity: f64,
@@ -62,7 +91,7 @@ pub struct SimilarityMetrics {
 }
 
 /// Qualitative analysis of the comparison
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QualitativeAnalysis {
     /// Areas where rust2mojo performs better
     pub rust2mojo_advantages: Vec<String>,
@@ -74,10 +103,44 @@ pub struct QualitativeAnalysis {
     pub correctness_issues: Vec<String>,
 }
 
+/// Request body for the Messages API
+#[cfg(feature = "comparison")]
+#[derive(Debug, Serialize)]
+struct MessagesRequest<'a> {
+    model: &'a str,
+    max_tokens: usize,
+    temperature: f32,
+    messages: [Message<'a>; 1],
+}
+
+#[cfg(feature = "comparison")]
+#[derive(Debug, Serialize)]
+struct Message<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+/// Response body from the Messages API; only text blocks are kept
+#[cfg(feature = "comparison")]
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    content: Vec<ContentBlock>,
+}
+
+#[cfg(feature = "comparison")]
+#[derive(Debug, Deserialize)]
+struct ContentBlock {
+    #[serde(default)]
+    text: Option<String>,
+}
+
 /// Main comparison engine
 pub struct ComparisonEngine {
+    #[cfg_attr(not(feature = "comparison"), allow(dead_code))]
     config: ComparisonConfig,
     compiler: Compiler,
+    #[cfg(feature = "comparison")]
+    client: reqwest::Client,
 }
 
 impl ComparisonEngine {
@@ -86,103 +149,31 @@ impl ComparisonEngine {
         Self {
             config,
             compiler: Compiler::new(),
+            #[cfg(feature = "comparison")]
+            client: reqwest::Client::new(),
         }
     }
 
-    /// Compare rust2mojo output with LLM-generated code
-    pub async fn compare(&self, rust_code: &str) -> Result<ComparisonResult> {
-        if !self.config.enabled {
-            return Err(crate::Error::InternalError(
-                "LLM comparison is disabled".to_string(),
-            ));
-        }
-
-        // Generate code using rust2mojo
+    /// Compare rust2mojo output with a known-good reference Mojo file
+    ///
+    /// Unlike `compare` this needs no LLM, so it works even when
+    /// LLM comparison is disabled and is suitable for regression tracking.
+    pub fn compare_against_reference(
+        &self,
+        rust_code: &str,
+        reference_mojo: &str,
+    ) -> Result<ReferenceComparisonResult> {
         let rust2mojo_output = self.compiler.compile_str(rust_code)?;
+        let metrics = self.calculate_similarity_metrics(&rust2mojo_output, reference_mojo);
 
-        // Generate code using LLM
-        let llm_output = self.generate_llm_code(rust_code).await?;
-
-        // Perform comparison analysis
-        let metrics = self.calculate_similarity_metrics(&rust2mojo_output, &llm_output);
-        let analysis = self.perform_qualitative_analysis(&rust2mojo_output, &llm_output);
-
-        Ok(ComparisonResult {
+        Ok(ReferenceComparisonResult {
             rust_code: rust_code.to_string(),
             rust2mojo_output,
-            llm_output,
+            reference_output: reference_mojo.to_string(),
             metrics,
-            analysis,
         })
     }
 
-    /// Generate Mojo code using LLM
-    async fn generate_llm_code(&self, rust_code: &str) -> Result<String> {
-        let prompt = self.create_translation_prompt(rust_code);
-
-        // This is a placeholder for actual LLM API integration
-        // In a real implementation, this would make HTTP requests to the LLM API
-        let llm_response = self.call_llm_api(&prompt).await?;
-
-        // Extract Mojo code from LLM response
-        self.extract_mojo_code(&llm_response)
-    }
-
-    /// Create a prompt for LLM to translate Rust to Mojo
-    fn create_translation_prompt(&self, rust_code: &str) -> String {
-        format!(
-            r#"Translate the following Rust code to equivalent Mojo code. 
-Focus on:
-1. Preserving the original functionality and semantics
-2. Using idiomatic Mojo constructs
-3. Maintaining performance characteristics
-4. Ensuring memory safety where possible
-
-Rust code:
-```rust
-{}
-```
-
-Please provide only the Mojo code translation, without explanations:
-```mojo
-"#,
-            rust_code
-        )
-    }
-
-    /// Call the LLM API (placeholder implementation)
-    async fn call_llm_api(&self, _prompt: &str) -> Result<String> {
-        // This is a mock implementation
-        // Real implementation would use HTTP client to call LLM API
-
-        // For now, return a placeholder response
-        Ok(r#"# LLM-generated Mojo code for comparison
-from memory import UnsafePointer
-from collections import List
-
-fn placeholder_function():
-    # This is a placeholder LLM response
-    # Real implementation would call actual LLM API
-    print("LLM generated code")
-"#
-        .to_string())
-    }
-
-    /// Extract Mojo code from LLM response
-    fn extract_mojo_code(&self, llm_response: &str) -> Result<String> {
-        // Look for code blocks in the response
-        if let Some(start) = llm_response.find("```mojo") {
-            if let Some(end) = llm_response[start..].find("```") {
-                let code_start = start + "```mojo".len();
-                let code_end = start + end;
-                return Ok(llm_response[code_start..code_end].trim().to_string());
-            }
-        }
-
-        // If no code blocks found, return the whole response
-        Ok(llm_response.trim().to_string())
-    }
-
     /// Calculate quantitative similarity metrics
     fn calculate_similarity_metrics(
         &self,
@@ -206,103 +197,76 @@ fn placeholder_function():
     }
 
     /// Calculate structural similarity (AST-based comparison)
+    ///
+    /// Both sides are reduced to a histogram of statement kinds and
+    /// parent/child kind pairs, compared with weighted Jaccard similarity.
     fn calculate_structural_similarity(&self, code1: &str, code2: &str) -> f64 {
-        // Simplified structural comparison based on common patterns
-        let patterns1 = self.extract_code_patterns(code1);
-        let patterns2 = self.extract_code_patterns(code2);
-
-        let common_patterns = patterns1.intersection(&patterns2).count();
-        let total_patterns = patterns1.union(&patterns2).count();
-
-        if total_patterns == 0 {
-            1.0
-        } else {
-            common_patterns as f64 / total_patterns as f64
-        }
+        histogram_similarity(
+            &self.extract_code_structure(code1),
+            &self.extract_code_structure(code2),
+        )
     }
 
-    /// Extract code patterns for structural comparison
-    fn extract_code_patterns(&self, code: &str) -> std::collections::HashSet<String> {
-        let mut patterns = std::collections::HashSet::new();
+    /// Build a node-kind histogram of the indentation tree of a Mojo file
+    ///
+    /// Each statement contributes its own kind and a `parent>kind` edge, so
+    /// two files score alike only when the same constructs nest the same way.
+    /// Comments and imports are ignored.
+    fn extract_code_structure(&self, code: &str) -> HashMap<String, usize> {
+        let mut histogram = HashMap::new();
+        let mut parents: Vec<(usize, &str)> = Vec::new();
 
-        // Extract function definitions
         for line in code.lines() {
             let trimmed = line.trim();
-            if trimmed.starts_with("fn ") {
-                patterns.insert("function_definition".to_string());
-            }
-            if trimmed.starts_with("struct ") {
-                patterns.insert("struct_definition".to_string());
-            }
-            if trimmed.starts_with("if ") {
-                patterns.insert("conditional".to_string());
+            let Some(kind) = statement_kind(trimmed) else {
+                continue;
+            };
+            let indent = line.len() - line.trim_start().len();
+            while parents.last().is_some_and(|(depth, _)| *depth >= indent) {
+                parents.pop();
             }
-            if trimmed.starts_with("for ") || trimmed.starts_with("while ") {
-                patterns.insert("loop".to_string());
-            }
-            if trimmed.contains(" = ") {
-                patterns.insert("assignment".to_string());
+
+            let parent = parents.last().map_or("root", |(_, kind)| kind);
+            *histogram.entry(kind.to_string()).or_insert(0) += 1;
+            *histogram.entry(format!("{}>{}", parent, kind)).or_insert(0) += 1;
+
+            if trimmed.ends_with(':') {
+                parents.push((indent, kind));
             }
         }
 
-        patterns
+        histogram
     }
 
     /// Calculate semantic similarity (meaning preservation)
-    fn calculate_semantic_similarity(&self, _code1: &str, _code2: &str) -> f64 {
-        // This would require more sophisticated analysis
-        // For now, return a placeholder based on basic heuristics
-        0.8 // Placeholder value
+    ///
+    /// Averages the overlap of the identifiers used, of the function arities,
+    /// and of the control-flow constructs in each file.
+    fn calculate_semantic_similarity(&self, code1: &str, code2: &str) -> f64 {
+        let identifiers = histogram_similarity(&identifiers(code1), &identifiers(code2));
+        let arities = histogram_similarity(&function_arities(code1), &function_arities(code2));
+        let control_flow = histogram_similarity(
+            &count_line_prefixes(code1, CONTROL_FLOW),
+            &count_line_prefixes(code2, CONTROL_FLOW),
+        );
+
+        (identifiers + arities + control_flow) / 3.0
     }
 
     /// Calculate performance similarity
-    fn calculate_performance_similarity(&self, _code1: &str, _code2: &str) -> f64 {
-        // This would require performance profiling and analysis
-        // For now, return a placeholder
-        0.75 // Placeholder value
+    ///
+    /// Compares counts of loops, heap allocations, copies and raw memory use,
+    /// the constructs that dominate the cost of the generated code.
+    fn calculate_performance_similarity(&self, code1: &str, code2: &str) -> f64 {
+        histogram_similarity(
+            &count_occurrences(code1, PERFORMANCE_CONSTRUCTS),
+            &count_occurrences(code2, PERFORMANCE_CONSTRUCTS),
+        )
     }
 
-    /// Perform qualitative analysis
-    fn perform_qualitative_analysis(
-        &self,
-        rust2mojo_code: &str,
-        llm_code: &str,
-    ) -> QualitativeAnalysis {
-        let mut rust2mojo_advantages = Vec::new();
-        let mut llm_advantages = Vec::new();
-        let mut improvement_suggestions = Vec::new();
-        let correctness_issues = Vec::new();
-
-        // Analyze code structure
-        if rust2mojo_code.contains("# Generated Mojo code") {
-            rust2mojo_advantages.push("Consistent header comments".to_string());
-        }
-
-        if llm_code.len() < rust2mojo_code.len() {
-            llm_advantages.push("More concise code generation".to_string());
-        } else {
-            rust2mojo_advantages.push("More explicit code generation".to_string());
-        }
-
-        // Check for imports
-        if rust2mojo_code.contains("from memory import") {
-            rust2mojo_advantages.push("Includes necessary memory imports".to_string());
-        }
-
-        // Suggest improvements
-        if !rust2mojo_code.contains("fn main():") && llm_code.contains("fn main():") {
-            improvement_suggestions.push("Consider special handling for main function".to_string());
-        }
-
-        improvement_suggestions.push("Compare generated code performance".to_string());
-        improvement_suggestions.push("Validate semantic equivalence".to_string());
-
-        QualitativeAnalysis {
-            rust2mojo_advantages,
-            llm_advantages,
-            improvement_suggestions,
-            correctness_issues,
-        }
+    /// Serialize a comparison result as pretty-printed JSON for tooling
+    pub fn generate_json_report(&self, result: &ComparisonResult) -> Result<String> {
+        Ok(serde_json::to_string_pretty(result)?)
     }
 
     /// Generate a detailed comparison report
@@ -388,6 +352,225 @@ fn placeholder_function():
     }
 }
 
+/// Comparison against an LLM, which needs the `comparison` feature for its HTTP client
+#[cfg(feature = "comparison")]
+impl ComparisonEngine {
+    /// Compare rust2mojo output with LLM-generated code
+    pub async fn compare(&self, rust_code: &str) -> Result<ComparisonResult> {
+        if !self.config.enabled {
+            return Err(crate::Error::InternalError(
+                "LLM comparison is disabled".to_string(),
+            ));
+        }
+
+        // Generate code using rust2mojo
+        let rust2mojo_output = self.compiler.compile_str(rust_code)?;
+
+        // Generate code using LLM
+        let llm_output = self.generate_llm_code(rust_code).await?;
+
+        // Perform comparison analysis
+        let metrics = self.calculate_similarity_metrics(&rust2mojo_output, &llm_output);
+        let analysis = self.perform_qualitative_analysis(&rust2mojo_output, &llm_output);
+
+        Ok(ComparisonResult {
+            rust_code: rust_code.to_string(),
+            rust2mojo_output,
+            llm_output,
+            metrics,
+            analysis,
+        })
+    }
+
+    /// Perform qualitative analysis
+    fn perform_qualitative_analysis(
+        &self,
+        rust2mojo_code: &str,
+        llm_code: &str,
+    ) -> QualitativeAnalysis {
+        let mut rust2mojo_advantages = Vec::new();
+        let mut llm_advantages = Vec::new();
+        let mut improvement_suggestions = Vec::new();
+        let correctness_issues = Vec::new();
+
+        // Analyze code structure
+        if rust2mojo_code.contains("# Generated Mojo code") {
+            rust2mojo_advantages.push("Consistent header comments".to_string());
+        }
+
+        if llm_code.len() < rust2mojo_code.len() {
+            llm_advantages.push("More concise code generation".to_string());
+        } else {
+            rust2mojo_advantages.push("More explicit code generation".to_string());
+        }
+
+        // Check for imports
+        if rust2mojo_code.contains("from memory import") {
+            rust2mojo_advantages.push("Includes necessary memory imports".to_string());
+        }
+
+        // Suggest improvements
+        if !rust2mojo_code.contains("fn main():") && llm_code.contains("fn main():") {
+            improvement_suggestions.push("Consider special handling for main function".to_string());
+        }
+
+        improvement_suggestions.push("Compare generated code performance".to_string());
+        improvement_suggestions.push("Validate semantic equivalence".to_string());
+
+        QualitativeAnalysis {
+            rust2mojo_advantages,
+            llm_advantages,
+            improvement_suggestions,
+            correctness_issues,
+        }
+    }
+
+    /// Generate Mojo code using LLM
+    async fn generate_llm_code(&self, rust_code: &str) -> Result<String> {
+        let cache_path = self.cache_path(rust_code);
+        if let Some(cached) = cache_path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+        {
+            return Ok(cached);
+        }
+
+        let prompt = self.create_translation_prompt(rust_code);
+        let llm_response = self.call_llm_api(&prompt).await?;
+
+        // Extract Mojo code from LLM response
+        let mojo_code = self.extract_mojo_code(&llm_response)?;
+
+        if let Some(path) = cache_path {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(path, &mojo_code)?;
+        }
+        Ok(mojo_code)
+    }
+
+    /// Cache file for a translation, keyed by the input and the sampling settings
+    fn cache_path(&self, rust_code: &str) -> Option<PathBuf> {
+        let dir = self.config.cache_dir.as_ref()?;
+        // FNV-1a, so keys stay stable across Rust releases unlike `DefaultHasher`
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let key = [
+            rust_code.as_bytes(),
+            b"\0",
+            self.config.model.as_bytes(),
+            b"\0",
+            &self.config.temperature.to_bits().to_le_bytes(),
+        ];
+        for byte in key.concat() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        Some(dir.join(format!("{:016x}.mojo", hash)))
+    }
+
+    /// Create a prompt for LLM to translate Rust to Mojo
+    fn create_translation_prompt(&self, rust_code: &str) -> String {
+        format!(
+            r#"Translate the following Rust code to equivalent Mojo code. 
+Focus on:
+1. Preserving the original functionality and semantics
+2. Using idiomatic Mojo constructs
+3. Maintaining performance characteristics
+4. Ensuring memory safety where possible
+
+Rust code:
+```rust
+{}
+```
+
+Please provide only the Mojo code translation, without explanations:
+```mojo
+"#,
+            rust_code
+        )
+    }
+
+    /// Send the prompt to the LLM API and return the text of its reply
+    async fn call_llm_api(&self, prompt: &str) -> Result<String> {
+        let api_key = std::env::var(&self.config.api_key_env).map_err(|_| {
+            Error::InternalError(format!(
+                "LLM API key not found: set the {} environment variable",
+                self.config.api_key_env
+            ))
+        })?;
+
+        let request = MessagesRequest {
+            model: &self.config.model,
+            max_tokens: self.config.max_tokens,
+            temperature: self.config.temperature,
+            messages: [Message {
+                role: "user",
+                content: prompt,
+            }],
+        };
+
+        let response = self
+            .client
+            .post(&self.config.api_endpoint)
+            .header("x-api-key", api_key)
+            .header("anthropic-version", ANTHROPIC_API_VERSION)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| {
+                Error::InternalError(format!(
+                    "LLM API request to {} failed: {}",
+                    self.config.api_endpoint, e
+                ))
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::InternalError(format!(
+                "LLM API returned {}: {}",
+                status, body
+            )));
+        }
+
+        let message: MessagesResponse = response.json().await.map_err(|e| {
+            Error::InternalError(format!("Failed to parse LLM API response: {}", e))
+        })?;
+        let text: String = message
+            .content
+            .into_iter()
+            .filter_map(|block| block.text)
+            .collect();
+        if text.trim().is_empty() {
+            return Err(Error::InternalError(
+                "LLM API response contained no text".to_string(),
+            ));
+        }
+
+        Ok(text)
+    }
+
+    /// Extract Mojo code from LLM response
+    ///
+    /// The prompt ends with an open ```` ```mojo ```` fence, so a reply may
+    /// contain a full fenced block or just the code followed by a closing fence.
+    fn extract_mojo_code(&self, llm_response: &str) -> Result<String> {
+        if let Some(start) = llm_response.find("```mojo") {
+            let code_start = start + "```mojo".len();
+            let code = &llm_response[code_start..];
+            let code_end = code.find("```").unwrap_or(code.len());
+            return Ok(code[..code_end].trim().to_string());
+        }
+
+        if let Some(end) = llm_response.find("```") {
+            return Ok(llm_response[..end].trim().to_string());
+        }
+
+        Ok(llm_response.trim().to_string())
+    }
+}
+
 /// Batch comparison for multiple test cases
 pub struct BatchComparison {
     engine: ComparisonEngine,
@@ -404,6 +587,7 @@ impl BatchComparison {
     }
 
     /// Add a test case to the batch
+    #[cfg(feature = "comparison")]
     pub async fn add_test_case(&mut self, rust_code: &str) -> Result<()> {
         let result = self.engine.compare(rust_code).await?;
         self.results.push(result);
@@ -451,6 +635,15 @@ impl BatchComparison {
         }
     }
 
+    /// Serialize the aggregate statistics and every case as pretty-printed JSON
+    pub fn generate_json_batch_report(&self) -> Result<String> {
+        let report = BatchReport {
+            statistics: self.generate_statistics(),
+            results: self.results.clone(),
+        };
+        Ok(serde_json::to_string_pretty(&report)?)
+    }
+
     /// Generate detailed batch report
     pub fn generate_batch_report(&self) -> String {
         let stats = self.generate_statistics();
@@ -485,8 +678,15 @@ impl BatchComparison {
     }
 }
 
+/// Machine-readable batch report: aggregates plus per-case results
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchReport {
+    pub statistics: BatchStatistics,
+    pub results: Vec<ComparisonResult>,
+}
+
 /// Aggregate statistics for batch comparisons
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct BatchStatistics {
     pub total_test_cases: usize,
     pub average_structural_similarity: f64,
@@ -495,6 +695,187 @@ pub struct BatchStatistics {
     pub average_overall_score: f64,
 }
 
+/// Control-flow constructs counted for semantic similarity, by line prefix
+const CONTROL_FLOW: &[(&str, &[&str])] = &[
+    ("branch", &["if ", "elif ", "else"]),
+    ("loop", &["for ", "while "]),
+    ("return", &["return"]),
+    ("raise", &["raise "]),
+    ("jump", &["break", "continue"]),
+];
+
+/// Cost-relevant constructs counted for performance similarity, by occurrence
+const PERFORMANCE_CONSTRUCTS: &[(&str, &[&str])] = &[
+    ("loop", &["for ", "while "]),
+    ("call", &["("]),
+    (
+        "allocation",
+        &["List(", "List[", "Dict(", "Dict[", "String(", "alloc"],
+    ),
+    ("growth", &[".append(", ".extend(", ".insert("]),
+    ("copy", &[".copy()", ".clone()", ".to_string()"]),
+    ("raw_memory", &["UnsafePointer", "memcpy", ".free("]),
+];
+
+/// Mojo keywords and builtins left out of identifier comparison
+const KEYWORDS: &[&str] = &[
+    "fn", "def", "struct", "trait", "var", "let", "alias", "if", "elif", "else", "for", "while",
+    "in", "return", "raise", "raises", "pass", "break", "continue", "and", "or", "not", "True",
+    "False", "None", "self", "Self", "inout", "owned", "borrowed", "from", "import", "try",
+    "except",
+];
+
+/// Weighted Jaccard similarity of two count histograms; two empty ones match
+fn histogram_similarity<K: std::hash::Hash + Eq>(
+    a: &HashMap<K, usize>,
+    b: &HashMap<K, usize>,
+) -> f64 {
+    let keys: HashSet<&K> = a.keys().chain(b.keys()).collect();
+    let (mut shared, mut total) = (0, 0);
+    for key in keys {
+        let count_a = a.get(key).copied().unwrap_or(0);
+        let count_b = b.get(key).copied().unwrap_or(0);
+        shared += count_a.min(count_b);
+        total += count_a.max(count_b);
+    }
+
+    if total == 0 {
+        1.0
+    } else {
+        shared as f64 / total as f64
+    }
+}
+
+/// Lines of code with full-line comments and imports removed
+fn code_lines(code: &str) -> impl Iterator<Item = &str> {
+    code.lines()
+        .map(str::trim)
+        .filter(|line| statement_kind(line).is_some())
+}
+
+/// Count how often each non-keyword identifier occurs
+fn identifiers(code: &str) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for line in code_lines(code) {
+        for word in line.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+            let starts_identifier = word
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphabetic() || c == '_');
+            if starts_identifier && !KEYWORDS.contains(&word) {
+                *counts.entry(word.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// Count functions by number of parameters, ignoring `self`
+fn function_arities(code: &str) -> HashMap<usize, usize> {
+    let mut counts = HashMap::new();
+    for line in code_lines(code) {
+        if statement_kind(line) != Some("function") {
+            continue;
+        }
+        let Some(params) = line
+            .split_once('(')
+            .and_then(|(_, rest)| rest.split_once(')'))
+            .map(|(params, _)| params)
+        else {
+            continue;
+        };
+        let arity = params
+            .split(',')
+            .map(str::trim)
+            .filter(|param| !param.is_empty() && !param.ends_with("self"))
+            .count();
+        *counts.entry(arity).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Count the lines starting with each construct's prefixes
+fn count_line_prefixes(
+    code: &str,
+    constructs: &[(&'static str, &[&str])],
+) -> HashMap<&'static str, usize> {
+    let mut counts = HashMap::new();
+    for line in code_lines(code) {
+        for (name, prefixes) in constructs {
+            if prefixes.iter().any(|p| line.starts_with(p)) {
+                *counts.entry(*name).or_insert(0) += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// Count every occurrence of each construct's patterns
+fn count_occurrences(
+    code: &str,
+    constructs: &[(&'static str, &[&str])],
+) -> HashMap<&'static str, usize> {
+    let mut counts = HashMap::new();
+    for line in code_lines(code) {
+        for (name, patterns) in constructs {
+            let hits: usize = patterns.iter().map(|p| line.matches(p).count()).sum();
+            if hits > 0 {
+                *counts.entry(*name).or_insert(0) += hits;
+            }
+        }
+    }
+    counts
+}
+
+/// Classify one trimmed line of Mojo; `None` for lines with no structure
+fn statement_kind(line: &str) -> Option<&'static str> {
+    let starts = |prefixes: &[&str]| prefixes.iter().any(|p| line.starts_with(p));
+
+    let kind = if line.is_empty() || starts(&["#", "from ", "import "]) {
+        return None;
+    } else if starts(&["@"]) {
+        "decorator"
+    } else if starts(&["fn ", "def "]) {
+        "function"
+    } else if starts(&["struct "]) {
+        "struct"
+    } else if starts(&["trait "]) {
+        "trait"
+    } else if starts(&["if ", "elif "]) {
+        "conditional"
+    } else if starts(&["else"]) {
+        "else"
+    } else if starts(&["for "]) {
+        "for_loop"
+    } else if starts(&["while "]) {
+        "while_loop"
+    } else if starts(&["return"]) {
+        "return"
+    } else if starts(&["raise "]) {
+        "raise"
+    } else if starts(&["try:", "except"]) {
+        "exception"
+    } else if starts(&["var ", "let "]) {
+        "declaration"
+    } else if starts(&["alias "]) {
+        "alias"
+    } else if starts(&["break", "continue"]) {
+        "jump"
+    } else if line == "pass" {
+        "pass"
+    } else if [" = ", "+= ", "-= ", "*= ", "/= ", "%= "]
+        .iter()
+        .any(|op| line.contains(op))
+    {
+        "assignment"
+    } else if line.contains('(') {
+        "call"
+    } else {
+        "expression"
+    };
+    Some(kind)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -508,20 +889,25 @@ mod tests {
     }
 
     #[test]
-    fn test_extract_code_patterns() {
+    fn test_extract_code_structure() {
         let engine = ComparisonEngine::new(ComparisonConfig::default());
         let code = r#"
-            fn test() {
-                if true {
-                    let x = 42;
-                }
-            }
-        "#;
+# comment
+from collections import List
 
-        let patterns = engine.extract_code_patterns(code);
-        assert!(patterns.contains("function_definition"));
-        assert!(patterns.contains("conditional"));
-        assert!(patterns.contains("assignment"));
+fn test():
+    if True:
+        var x = 42
+    x = 1
+"#;
+
+        let structure = engine.extract_code_structure(code);
+        assert_eq!(structure["function"], 1);
+        assert_eq!(structure["root>function"], 1);
+        assert_eq!(structure["function>conditional"], 1);
+        assert_eq!(structure["conditional>declaration"], 1);
+        assert_eq!(structure["function>assignment"], 1);
+        assert_eq!(structure.values().sum::<usize>(), 8);
     }
 
     #[test]
@@ -534,4 +920,234 @@ mod tests {
         let similarity = engine.calculate_structural_similarity(code1, code2);
         assert!(similarity > 0.5); // Should be similar structure
     }
+
+    #[test]
+    fn test_structural_similarity_tracks_tree_shape() {
+        let engine = ComparisonEngine::new(ComparisonConfig::default());
+
+        let clamp = "fn clamp(value: Int, limit: Int) -> Int:\n    var result = value\n    if result > limit:\n        result = limit\n    return result\n";
+        let cap = "fn cap(n: Int, max: Int) -> Int:\n    var out = n\n    if out > max:\n        out = max\n    return out\n";
+        let point = "@value\nstruct Point:\n    var x: Int\n    var y: Int\n\n    fn norm(self) -> Int:\n        return self.x * self.x + self.y * self.y\n";
+        let sum = "fn sum(items: List[Int]) -> Int:\n    var total = 0\n    for item in items:\n        total += item[]\n    return total\n";
+
+        assert!(engine.calculate_structural_similarity(clamp, cap) > 0.99);
+        assert!(engine.calculate_structural_similarity(clamp, point) < 0.3);
+        assert!(engine.calculate_structural_similarity(clamp, sum) < 0.7);
+        assert!(
+            engine.calculate_structural_similarity(clamp, sum)
+                > engine.calculate_structural_similarity(clamp, point)
+        );
+    }
+
+    #[cfg(feature = "comparison")]
+    #[test]
+    fn test_extract_mojo_code() {
+        let engine = ComparisonEngine::new(ComparisonConfig::default());
+
+        let fenced = "Here you go:\n```mojo\nfn f():\n    pass\n```\nDone.";
+        assert_eq!(
+            engine.extract_mojo_code(fenced).unwrap(),
+            "fn f():\n    pass"
+        );
+
+        let continued = "fn f():\n    pass\n```";
+        assert_eq!(
+            engine.extract_mojo_code(continued).unwrap(),
+            "fn f():\n    pass"
+        );
+    }
+
+    #[cfg(feature = "comparison")]
+    fn mock_config(server: &wiremock::MockServer, api_key_env: &str) -> ComparisonConfig {
+        ComparisonConfig {
+            enabled: true,
+            api_endpoint: format!("{}/v1/messages", server.uri()),
+            api_key_env: api_key_env.to_string(),
+            ..ComparisonConfig::default()
+        }
+    }
+
+    #[cfg(feature = "comparison")]
+    #[tokio::test]
+    async fn test_compare_calls_llm_api() {
+        use wiremock::matchers::{body_string_contains, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("x-api-key", "test-key"))
+            .and(body_string_contains("\"model\":\"claude-3-sonnet\""))
+            .and(body_string_contains("fn answer() -> i32"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{
+                    "type": "text",
+                    "text": "fn answer() -> Int32:\n    return 42\n```"
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        std::env::set_var("RUST2MOJO_TEST_LLM_KEY", "test-key");
+        let engine = ComparisonEngine::new(mock_config(&server, "RUST2MOJO_TEST_LLM_KEY"));
+
+        let result = engine.compare("fn answer() -> i32 { 42 }").await.unwrap();
+        assert_eq!(result.llm_output, "fn answer() -> Int32:\n    return 42");
+    }
+
+    #[cfg(feature = "comparison")]
+    #[tokio::test]
+    async fn test_cached_responses_skip_the_llm_api() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{"type": "text", "text": "fn f():\n    pass\n```"}]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let cache_dir =
+            std::env::temp_dir().join(format!("rust2mojo-llm-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache_dir);
+        std::env::set_var("RUST2MOJO_TEST_CACHE_KEY", "test-key");
+        let config = ComparisonConfig {
+            cache_dir: Some(cache_dir.clone()),
+            ..mock_config(&server, "RUST2MOJO_TEST_CACHE_KEY")
+        };
+        let engine = ComparisonEngine::new(config.clone());
+
+        let first = engine.compare("fn f() {}").await.unwrap();
+        let second = engine.compare("fn f() {}").await.unwrap();
+        assert_eq!(first.llm_output, second.llm_output);
+
+        // Different sampling settings must not reuse the cached answer
+        let warmer = ComparisonConfig {
+            temperature: 0.7,
+            ..config
+        };
+        assert_ne!(
+            engine.cache_path("fn f() {}"),
+            ComparisonEngine::new(warmer).cache_path("fn f() {}")
+        );
+
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[cfg(feature = "comparison")]
+    #[tokio::test]
+    async fn test_llm_api_failures_are_internal_errors() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(401).set_body_string("invalid x-api-key"))
+            .mount(&server)
+            .await;
+
+        let engine = ComparisonEngine::new(mock_config(&server, "RUST2MOJO_TEST_MISSING_KEY"));
+        let err = engine.compare("fn f() {}").await.unwrap_err();
+        assert!(
+            matches!(&err, Error::InternalError(msg) if msg.contains("RUST2MOJO_TEST_MISSING_KEY"))
+        );
+
+        std::env::set_var("RUST2MOJO_TEST_REJECTED_KEY", "bad-key");
+        let engine = ComparisonEngine::new(mock_config(&server, "RUST2MOJO_TEST_REJECTED_KEY"));
+        let err = engine.compare("fn f() {}").await.unwrap_err();
+        assert!(matches!(&err, Error::InternalError(msg) if msg.contains("401")));
+    }
+
+    #[test]
+    fn test_semantic_similarity_varies_with_input() {
+        let engine = ComparisonEngine::new(ComparisonConfig::default());
+
+        let add = "fn add(a: Int, b: Int) -> Int:\n    return a + b\n";
+        let add_checked = "fn add(a: Int, b: Int) -> Int:\n    if a > 100:\n        raise Error(\"overflow\")\n    return a + b\n";
+        let greet = "fn greet(name: String):\n    for _ in range(3):\n        print(name)\n";
+
+        let same = engine.calculate_semantic_similarity(add, add);
+        let close = engine.calculate_semantic_similarity(add, add_checked);
+        let far = engine.calculate_semantic_similarity(add, greet);
+        assert_eq!(same, 1.0);
+        assert!(close < same);
+        assert!(far < close);
+        assert!(far < 0.3);
+    }
+
+    #[test]
+    fn test_performance_similarity_varies_with_input() {
+        let engine = ComparisonEngine::new(ComparisonConfig::default());
+
+        let scalar = "fn total(a: Int, b: Int) -> Int:\n    return a + b\n";
+        let collecting = "fn total(n: Int) -> Int:\n    var items = List[Int]()\n    for i in range(n):\n        items.append(i)\n    return len(items)\n";
+        let copying = "fn total(n: Int) -> Int:\n    var items = List[Int]()\n    for i in range(n):\n        items.append(i)\n        var snapshot = items.copy()\n    return len(items)\n";
+
+        assert_eq!(engine.calculate_performance_similarity(scalar, scalar), 1.0);
+        let close = engine.calculate_performance_similarity(collecting, copying);
+        let far = engine.calculate_performance_similarity(scalar, collecting);
+        assert!(close < 1.0);
+        assert!(far < close);
+    }
+
+    fn sample_result(rust_code: &str, structural_similarity: f64) -> ComparisonResult {
+        ComparisonResult {
+            rust_code: rust_code.to_string(),
+            rust2mojo_output: "fn f():\n    pass\n".to_string(),
+            llm_output: "fn f():\n    pass".to_string(),
+            metrics: SimilarityMetrics {
+                structural_similarity,
+                semantic_similarity: 0.5,
+                performance_similarity: 1.0,
+                overall_score: (structural_similarity + 1.5) / 3.0,
+            },
+            analysis: QualitativeAnalysis {
+                rust2mojo_advantages: vec!["Consistent header comments".to_string()],
+                llm_advantages: Vec::new(),
+                improvement_suggestions: vec!["Validate semantic equivalence".to_string()],
+                correctness_issues: Vec::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_json_report_round_trips() {
+        let engine = ComparisonEngine::new(ComparisonConfig::default());
+        let result = sample_result("fn f() {}", 1.0);
+
+        let json = engine.generate_json_report(&result).unwrap();
+        let parsed: ComparisonResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, result);
+    }
+
+    #[test]
+    fn test_json_batch_report_round_trips() {
+        let mut batch = BatchComparison::new(ComparisonConfig::default());
+        batch.results.push(sample_result("fn f() {}", 1.0));
+        batch.results.push(sample_result("fn g() {}", 0.5));
+
+        let json = batch.generate_json_batch_report().unwrap();
+        let report: BatchReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(report.results, batch.results);
+        assert_eq!(report.statistics, batch.generate_statistics());
+        assert_eq!(report.statistics.total_test_cases, 2);
+        assert_eq!(report.statistics.average_structural_similarity, 0.75);
+    }
+
+    #[test]
+    fn test_compare_against_reference() {
+        let engine = ComparisonEngine::new(ComparisonConfig::default());
+
+        let rust_code = "fn answer() { let x = 42; }";
+        let reference = "fn answer():\n    let x = 42\n";
+
+        let result = engine
+            .compare_against_reference(rust_code, reference)
+            .unwrap();
+        assert_eq!(result.reference_output, reference);
+        assert_eq!(result.metrics.structural_similarity, 1.0);
+        assert!(result.metrics.overall_score > 0.5);
+    }
 }
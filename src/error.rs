@@ -1,5 +1,6 @@
 //! Error handling for the rust2mojo compiler
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Result type alias for the rust2mojo compiler
@@ -10,8 +11,11 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[allow(clippy::enum_variant_names)]
 pub enum Error {
     /// Rust parsing errors
-    #[error("Failed to parse Rust code: {0}")]
-    ParseError(String),
+    #[error("Failed to parse Rust code: {message}")]
+    ParseError {
+        message: String,
+        location: Option<SourceLocation>,
+    },
 
     /// Mojo code generation errors
     #[error("Failed to generate Mojo code: {0}")]
@@ -21,18 +25,107 @@ pub enum Error {
     #[error("I/O error: {0}")]
     IoError(String),
 
+    /// JSON serialization or deserialization errors
+    #[error("Serialization error: {0}")]
+    SerializationError(String),
+
     /// Unsupported Rust language feature
-    #[error("Unsupported Rust feature: {0}")]
-    UnsupportedFeature(String),
+    #[error("Unsupported Rust feature: {feature}")]
+    UnsupportedFeature {
+        feature: String,
+        location: Option<SourceLocation>,
+    },
 
     /// Internal compiler error
     #[error("Internal compiler error: {0}")]
     InternalError(String),
 }
 
+impl Error {
+    /// Where in the Rust source the error occurred, when known
+    pub fn location(&self) -> Option<SourceLocation> {
+        match self {
+            Error::ParseError { location, .. } | Error::UnsupportedFeature { location, .. } => {
+                *location
+            }
+            _ => None,
+        }
+    }
+
+    /// Rebase a location reported for a piece of the source that starts at `start`
+    pub(crate) fn relative_to(mut self, start: SourceLocation) -> Self {
+        if let Error::ParseError { location, .. } | Error::UnsupportedFeature { location, .. } =
+            &mut self
+        {
+            *location = location.map(|location| location.relative_to(start));
+        }
+        self
+    }
+}
+
+/// A line/column position in the Rust source, both 1-based
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourceLocation {
+    /// Rebase a location within a piece of the source that starts at `start`
+    pub(crate) fn relative_to(self, start: SourceLocation) -> Self {
+        if self.line == 1 {
+            Self {
+                line: start.line,
+                column: start.column + self.column - 1,
+            }
+        } else {
+            Self {
+                line: start.line + self.line - 1,
+                column: self.column,
+            }
+        }
+    }
+
+    /// Position where a syn span starts, if the span points into the source
+    pub fn from_span(span: proc_macro2::Span) -> Option<Self> {
+        let start = span.start();
+        // Spans not tied to the parsed source report line 0
+        (start.line > 0).then_some(Self {
+            line: start.line,
+            column: start.column + 1,
+        })
+    }
+}
+
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Something that was translated lossily or skipped, so the output needs review
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub message: String,
+    /// Where in the Rust source the warning applies, when known
+    pub location: Option<SourceLocation>,
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{}: {}", location, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
 impl From<syn::Error> for Error {
     fn from(err: syn::Error) -> Self {
-        Error::ParseError(err.to_string())
+        Error::ParseError {
+            message: err.to_string(),
+            location: SourceLocation::from_span(err.span()),
+        }
     }
 }
 
@@ -41,3 +134,9 @@ impl From<std::io::Error> for Error {
         Error::IoError(err.to_string())
     }
 }
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::SerializationError(err.to_string())
+    }
+}
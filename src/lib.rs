@@ -46,8 +46,163 @@ pub mod codegen;
 pub mod comparison;
 pub mod error;
 pub mod parser;
+mod pretty;
+pub mod transform;
+pub mod watch;
 
-pub use error::{Error, Result};
+pub use error::{Error, Result, SourceLocation, Warning};
+
+/// Configuration options controlling how Rust code is translated
+///
+/// # Examples
+///
+/// ```
+/// use rust2mojo::{Compiler, CompilerConfig};
+///
+/// let config = CompilerConfig {
+///     inline_single_use_aliases: true,
+///     ..CompilerConfig::default()
+/// };
+/// let compiler = Compiler::with_config(config);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompilerConfig {
+    /// Expand type aliases that are used exactly once and drop their definitions
+    pub inline_single_use_aliases: bool,
+    /// Emit a `__moveinit__` for non-`Copy` structs whose fields need moving
+    pub emit_move_init: bool,
+    /// Emit only signatures with `...` bodies, for generating Mojo interface stubs
+    pub signatures_only: bool,
+    /// Drop `#[test]` functions; when disabled they are emitted with a `# test` marker
+    pub skip_tests: bool,
+    /// Annotate functions without a return value with `-> None`
+    pub explicit_none_return: bool,
+    /// How items inside `mod` blocks are named in the flat Mojo output
+    pub module_naming: ModuleNaming,
+}
+
+/// Naming of items declared inside `mod` blocks
+///
+/// Mojo has no inline modules, so a module's items are emitted in place
+/// alongside the rest of the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModuleNaming {
+    /// Prefix items with their module path, so `math::add` becomes `math_add`
+    #[default]
+    Prefixed,
+    /// Keep item names as written; same-named items in different modules collide
+    Unprefixed,
+}
+
+impl Default for CompilerConfig {
+    fn default() -> Self {
+        Self {
+            inline_single_use_aliases: false,
+            emit_move_init: false,
+            signatures_only: false,
+            skip_tests: true,
+            explicit_none_return: false,
+            module_naming: ModuleNaming::default(),
+        }
+    }
+}
+
+/// Options controlling the compiler itself, independent of how constructs are translated
+///
+/// Use [`CompilerOptions::builder`] to override individual options.
+///
+/// # Examples
+///
+/// ```
+/// use rust2mojo::{Compiler, CompilerOptions};
+///
+/// let options = CompilerOptions::builder()
+///     .target_mojo_version("24.4")
+///     .emit_header(false)
+///     .build();
+/// let compiler = Compiler::with_options(options);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompilerOptions {
+    /// Mojo version the generated code targets, recorded in the output header
+    pub target_mojo_version: String,
+    /// Emit the "generated by rust2mojo" comment header
+    pub emit_header: bool,
+    /// Reject unsupported constructs instead of emitting placeholders
+    pub strict: bool,
+}
+
+impl CompilerOptions {
+    /// Start building options from the defaults
+    pub fn builder() -> CompilerOptionsBuilder {
+        CompilerOptionsBuilder::default()
+    }
+}
+
+impl Default for CompilerOptions {
+    fn default() -> Self {
+        Self {
+            target_mojo_version: "24.5".to_string(),
+            emit_header: true,
+            strict: false,
+        }
+    }
+}
+
+/// Builder for [`CompilerOptions`]
+#[derive(Debug, Clone, Default)]
+pub struct CompilerOptionsBuilder {
+    options: CompilerOptions,
+}
+
+impl CompilerOptionsBuilder {
+    /// Set the Mojo version the generated code targets
+    pub fn target_mojo_version(mut self, version: impl Into<String>) -> Self {
+        self.options.target_mojo_version = version.into();
+        self
+    }
+
+    /// Set whether the comment header is emitted
+    pub fn emit_header(mut self, emit_header: bool) -> Self {
+        self.options.emit_header = emit_header;
+        self
+    }
+
+    /// Set whether unsupported constructs are rejected
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.options.strict = strict;
+        self
+    }
+
+    /// Finish building the options
+    pub fn build(self) -> CompilerOptions {
+        self.options
+    }
+}
+
+/// How much of a Rust file was translated faithfully, from [`Compiler::coverage`]
+///
+/// Nodes are the items, statements and expressions code generation visited,
+/// plus the items the parser had to skip.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageReport {
+    /// Nodes considered
+    pub total_nodes: usize,
+    /// Nodes emitted as placeholders or skipped
+    pub untranslated_nodes: usize,
+    /// Kinds of unsupported constructs encountered, each listed once, in the order first seen
+    pub unsupported: Vec<String>,
+}
+
+impl CoverageReport {
+    /// Fraction of nodes that were fully translated, from 0.0 to 1.0
+    pub fn translated_fraction(&self) -> f64 {
+        if self.total_nodes == 0 {
+            return 1.0;
+        }
+        (self.total_nodes - self.untranslated_nodes) as f64 / self.total_nodes as f64
+    }
+}
 
 /// Main compiler interface for converting Rust code to Mojo
 ///
@@ -65,7 +220,8 @@ pub use error::{Error, Result};
 /// assert!(result.is_ok());
 /// ```
 pub struct Compiler {
-    // Configuration options will be added here
+    config: CompilerConfig,
+    options: CompilerOptions,
 }
 
 impl Compiler {
@@ -80,7 +236,69 @@ impl Compiler {
     /// // Compiler is ready to use
     /// ```
     pub fn new() -> Self {
-        Self {}
+        Self::with_config(CompilerConfig::default())
+    }
+
+    /// Create a new compiler instance with the given configuration
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust2mojo::{Compiler, CompilerConfig};
+    ///
+    /// let compiler = Compiler::with_config(CompilerConfig::default());
+    /// assert!(compiler.compile_str("fn f() {}").is_ok());
+    /// ```
+    pub fn with_config(config: CompilerConfig) -> Self {
+        Self::new_with(config, CompilerOptions::default())
+    }
+
+    /// Create a new compiler instance with the given options
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust2mojo::{Compiler, CompilerOptions};
+    ///
+    /// let compiler = Compiler::with_options(CompilerOptions::builder().emit_header(false).build());
+    /// let mojo_code = compiler.compile_str("fn f() {}").unwrap();
+    /// assert!(!mojo_code.contains("# Generated Mojo code"));
+    /// ```
+    pub fn with_options(options: CompilerOptions) -> Self {
+        Self::new_with(CompilerConfig::default(), options)
+    }
+
+    /// Create a new compiler instance with the given configuration and options
+    ///
+    /// [`Compiler::with_config`] and [`Compiler::with_options`] leave the other
+    /// half at its defaults; use this to set both.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust2mojo::{Compiler, CompilerConfig, CompilerOptions};
+    ///
+    /// let config = CompilerConfig {
+    ///     signatures_only: true,
+    ///     ..CompilerConfig::default()
+    /// };
+    /// let options = CompilerOptions::builder().emit_header(false).build();
+    /// let compiler = Compiler::new_with(config, options);
+    /// let mojo_code = compiler.compile_str("fn f() -> i32 { 1 }").unwrap();
+    /// assert_eq!(mojo_code, "fn f() -> Int32:\n    ...\n\n");
+    /// ```
+    pub fn new_with(config: CompilerConfig, options: CompilerOptions) -> Self {
+        Self { config, options }
+    }
+
+    /// Get the configuration this compiler was created with
+    pub fn config(&self) -> &CompilerConfig {
+        &self.config
+    }
+
+    /// Get the options this compiler was created with
+    pub fn options(&self) -> &CompilerOptions {
+        &self.options
     }
 
     /// Compile a Rust source string to Mojo code
@@ -121,9 +339,273 @@ impl Compiler {
     /// - The Rust code contains unsupported language features
     /// - Internal compilation errors occur
     pub fn compile_str(&self, rust_code: &str) -> Result<String> {
-        let ast = parser::parse_rust_code(rust_code)?;
-        let mojo_code = codegen::generate_mojo(&ast)?;
-        Ok(mojo_code)
+        self.compile_str_with_warnings(rust_code)
+            .map(|(mojo_code, _)| mojo_code)
+    }
+
+    /// Compile a Rust source string to Mojo code, also returning translation warnings
+    ///
+    /// Warnings flag output that compiled but may not be faithful: skipped items,
+    /// placeholder expressions, dropped attributes and other lossy conversions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust2mojo::Compiler;
+    ///
+    /// let compiler = Compiler::new();
+    /// let (mojo_code, warnings) = compiler
+    ///     .compile_str_with_warnings("fn f() { let g = |x: i32| x; }")
+    ///     .unwrap();
+    /// assert!(mojo_code.contains("fn f"));
+    /// assert!(!warnings.is_empty());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`Compiler::compile_str`].
+    pub fn compile_str_with_warnings(&self, rust_code: &str) -> Result<(String, Vec<Warning>)> {
+        let (ast, mut warnings) = self.parse_with_warnings(rust_code)?;
+        let (mojo_code, codegen_warnings) =
+            codegen::generate_mojo_with_warnings(&ast, &self.config, &self.options)?;
+        warnings.extend(codegen_warnings);
+        Ok((mojo_code, warnings))
+    }
+
+    /// Compile a Rust source string, collecting every error instead of stopping at the first
+    ///
+    /// A file that fails to parse is split at top-level item boundaries and each
+    /// item is parsed on its own, so independent mistakes are all reported in one
+    /// pass. Items that fail are left out of the output; the Mojo for the rest is
+    /// returned alongside the errors. The output is `None` only when nothing could
+    /// be compiled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust2mojo::Compiler;
+    ///
+    /// let compiler = Compiler::new();
+    /// let rust_code = "fn a() { let = 1; }\nfn b() {}\nfn c() { let = 2; }";
+    /// let (mojo_code, errors) = compiler.compile_str_collect(rust_code);
+    /// assert_eq!(errors.len(), 2);
+    /// assert!(mojo_code.unwrap().contains("fn b():"));
+    /// ```
+    pub fn compile_str_collect(&self, rust_code: &str) -> (Option<String>, Vec<Error>) {
+        let (mut ast, mut errors) = parser::parse_rust_code_collect(rust_code, &self.options);
+        if ast.items.is_empty() && !errors.is_empty() {
+            return (None, errors);
+        }
+
+        self.apply_passes(&mut ast);
+        match self.generate(&ast) {
+            Ok(mojo_code) => (Some(mojo_code), errors),
+            Err(err) => {
+                errors.push(err);
+                (None, errors)
+            }
+        }
+    }
+
+    /// Estimate how much of a Rust source string translates faithfully
+    ///
+    /// Unsupported constructs are counted rather than rejected, even in strict
+    /// mode, so the report covers the whole file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust2mojo::Compiler;
+    ///
+    /// let report = Compiler::new().coverage("fn f() -> i32 { 1 }").unwrap();
+    /// assert_eq!(report.translated_fraction(), 1.0);
+    /// assert!(report.unsupported.is_empty());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails if the source does not parse, or on errors other than unsupported
+    /// constructs, such as an external `mod` declaration.
+    pub fn coverage(&self, rust_code: &str) -> Result<CoverageReport> {
+        let options = CompilerOptions {
+            strict: false,
+            ..self.options.clone()
+        };
+        let (mut ast, skipped) = parser::parse_rust_code_with_skipped(rust_code, &options)?;
+        self.apply_passes(&mut ast);
+        let generated = codegen::generate(&ast, &self.config, &options)?;
+
+        let mut unsupported: Vec<String> = Vec::new();
+        for construct in skipped.iter().chain(&generated.unsupported) {
+            if !unsupported.contains(construct) {
+                unsupported.push(construct.clone());
+            }
+        }
+        Ok(CoverageReport {
+            total_nodes: generated.nodes + skipped.len(),
+            untranslated_nodes: generated.unsupported.len() + skipped.len(),
+            unsupported,
+        })
+    }
+
+    /// Parse a Rust source string into the intermediate AST that code generation consumes
+    ///
+    /// Configured transformation passes have already been applied to the result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust2mojo::{ast::Item, Compiler};
+    ///
+    /// let unit = Compiler::new().parse("fn f() {}").unwrap();
+    /// assert!(matches!(unit.items[0], Item::Function(_)));
+    /// ```
+    pub fn parse(&self, rust_code: &str) -> Result<ast::CompilationUnit> {
+        self.parse_with_warnings(rust_code).map(|(ast, _)| ast)
+    }
+
+    /// Generate Mojo code from an already parsed AST
+    ///
+    /// `compile_str` is equivalent to [`Compiler::parse`] followed by this method,
+    /// so tools can inspect or rewrite the AST in between.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust2mojo::Compiler;
+    ///
+    /// let compiler = Compiler::new();
+    /// let unit = compiler.parse("fn f() {}").unwrap();
+    /// let mojo_code = compiler.generate(&unit).unwrap();
+    /// assert!(mojo_code.contains("fn f():"));
+    /// ```
+    pub fn generate(&self, unit: &ast::CompilationUnit) -> Result<String> {
+        codegen::generate_mojo_with_warnings(unit, &self.config, &self.options)
+            .map(|(mojo_code, _)| mojo_code)
+    }
+
+    /// Compile a Rust source string one top-level item at a time
+    ///
+    /// Yields the Mojo for each item as soon as it is converted, so callers can
+    /// stream the output of large files instead of holding all of it in memory.
+    /// Chunks carry no file header or imports, and whole-unit passes such as
+    /// [`CompilerConfig::inline_single_use_aliases`] and
+    /// [`ModuleNaming::Prefixed`] are not applied.
+    ///
+    /// A file that fails to parse yields a single error. Otherwise an item that
+    /// fails to compile yields an error in its place; in lenient mode the rest of
+    /// the items are still compiled, while in strict mode iteration stops there.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust2mojo::Compiler;
+    ///
+    /// let compiler = Compiler::new();
+    /// let chunks: Vec<String> = compiler
+    ///     .compile_items("fn a() {}\nfn b() {}")
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(chunks.len(), 2);
+    /// assert!(chunks[1].contains("fn b():"));
+    /// ```
+    pub fn compile_items<'a>(
+        &'a self,
+        rust_code: &str,
+    ) -> impl Iterator<Item = Result<String>> + 'a {
+        let (items, parse_error) = match syn::parse_str::<syn::File>(rust_code) {
+            Ok(file) => (file.items, None),
+            Err(err) => (Vec::new(), Some(Err(err.into()))),
+        };
+
+        // Types, traits and impl blocks change how other items are generated, so
+        // they (and the modules that may hold them) are converted up front;
+        // everything else is converted on demand
+        let context: Vec<ast::Item> = items
+            .iter()
+            .filter(|item| {
+                matches!(
+                    item,
+                    syn::Item::Struct(_)
+                        | syn::Item::Enum(_)
+                        | syn::Item::Trait(_)
+                        | syn::Item::Impl(_)
+                        | syn::Item::Mod(_)
+                )
+            })
+            .filter_map(|item| parser::convert_top_level_item(item, &self.options).ok())
+            .flatten()
+            .collect();
+        let mut generator = codegen::ItemGenerator::new(&context, &self.config, &self.options);
+
+        let chunks = items
+            .into_iter()
+            .scan(false, move |stopped, item| {
+                if *stopped {
+                    return None;
+                }
+                let chunk =
+                    parser::convert_top_level_item(&item, &self.options).and_then(|converted| {
+                        converted
+                            .iter()
+                            .map(|item| generator.generate(item))
+                            .collect::<Result<String>>()
+                    });
+                *stopped = self.options.strict && chunk.is_err();
+                Some(chunk)
+            })
+            // Impl blocks emitted with their struct, skipped items and tests yield nothing
+            .filter(|chunk| !matches!(chunk, Ok(code) if code.is_empty()));
+        parse_error.into_iter().chain(chunks)
+    }
+
+    /// Render the intermediate AST for a Rust source string as pretty-printed JSON
+    ///
+    /// Useful for inspecting what the parser produced when debugging a translation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust2mojo::Compiler;
+    ///
+    /// let json = Compiler::new().ast_json("fn f() {}").unwrap();
+    /// assert!(json.contains("\"Function\""));
+    /// ```
+    pub fn ast_json(&self, rust_code: &str) -> Result<String> {
+        let ast = self.parse(rust_code)?;
+        Ok(serde_json::to_string_pretty(&ast)?)
+    }
+
+    /// Generate Mojo code from an AST previously rendered by [`ast_json`](Self::ast_json)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust2mojo::Compiler;
+    ///
+    /// let compiler = Compiler::new();
+    /// let json = compiler.ast_json("fn f() {}").unwrap();
+    /// assert!(compiler.generate_from_ast_json(&json).unwrap().contains("fn f():"));
+    /// ```
+    pub fn generate_from_ast_json(&self, json: &str) -> Result<String> {
+        let unit: ast::CompilationUnit = serde_json::from_str(json)?;
+        self.generate(&unit)
+    }
+
+    fn parse_with_warnings(&self, rust_code: &str) -> Result<(ast::CompilationUnit, Vec<Warning>)> {
+        let (mut ast, warnings) = parser::parse_rust_code_with_warnings(rust_code, &self.options)?;
+        self.apply_passes(&mut ast);
+        Ok((ast, warnings))
+    }
+
+    fn apply_passes(&self, ast: &mut ast::CompilationUnit) {
+        ast.metadata.target_mojo_version = self.options.target_mojo_version.clone();
+        if self.config.module_naming == ModuleNaming::Prefixed {
+            transform::prefix_module_items(ast);
+        }
+        if self.config.inline_single_use_aliases {
+            transform::inline_single_use_aliases(ast);
+        }
     }
 
     /// Compile a Rust source file to Mojo code
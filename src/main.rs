@@ -2,10 +2,15 @@
 //!
 //! Command-line interface for the rust2mojo compiler.
 
-use clap::{Parser, Subcommand};
-use rust2mojo::{Compiler, Result};
-use std::path::PathBuf;
-use tracing::info;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use rust2mojo::watch::{DirWatcher, FileWatcher};
+use rust2mojo::{Compiler, CompilerConfig, Error, Result};
+use std::io::{IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::{info, warn};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -21,9 +26,9 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Compile a Rust file to Mojo
+    /// Compile a Rust file, or every Rust file under a directory, to Mojo
     Compile {
-        /// Input Rust file
+        /// Input Rust file or directory, or `-` to read from stdin
         #[arg(value_name = "FILE")]
         input: PathBuf,
 
@@ -31,22 +36,136 @@ enum Commands {
         #[arg(short, long)]
         output: Option<PathBuf>,
 
+        /// Directory for generated Mojo files, mirroring the input directory's layout
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+
         /// Print output to stdout instead of writing to file
         #[arg(long)]
         stdout: bool,
+
+        /// Emit only signatures with `...` bodies (Mojo interface stubs)
+        #[arg(long)]
+        signatures: bool,
+
+        /// What to emit: generated Mojo, or the intermediate AST as JSON or a tree
+        #[arg(long, value_enum, default_value_t = Emit::Mojo)]
+        emit: Emit,
     },
 
     /// Check if Rust code can be compiled without generating output
     Check {
+        /// Input Rust file, or `-` to read from stdin
+        #[arg(value_name = "FILE")]
+        input: PathBuf,
+    },
+
+    /// Compile a Rust file, then recompile it every time it is saved
+    ///
+    /// Press Ctrl-C to stop watching; from a terminal, Ctrl-D or entering `q`
+    /// also stops it.
+    Watch {
         /// Input Rust file
         #[arg(value_name = "FILE")]
         input: PathBuf,
+
+        /// Output Mojo file (defaults to input.mojo)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Watch a directory tree and recompile changed files into an output tree
+    ///
+    /// Press Ctrl-C to stop watching; from a terminal, Ctrl-D or entering `q`
+    /// also stops it.
+    WatchDir {
+        /// Source directory containing Rust files
+        #[arg(value_name = "SRC")]
+        src: PathBuf,
+
+        /// Output directory for generated Mojo files
+        #[arg(value_name = "OUT")]
+        out: PathBuf,
     },
 
     /// Show version information
     Version,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Emit {
+    /// Generated Mojo code
+    Mojo,
+    /// The parsed intermediate AST, as pretty JSON
+    Ast,
+    /// The parsed intermediate AST, as an indented outline
+    AstTree,
+}
+
+/// Path argument that stands for stdin
+const STDIN_PATH: &str = "-";
+
+/// Read Rust source from `input`, or from `stdin` when `input` is `-`
+fn read_input(input: &Path, mut stdin: impl Read) -> Result<String> {
+    let mut rust_code = String::new();
+    if input == Path::new(STDIN_PATH) {
+        stdin
+            .read_to_string(&mut rust_code)
+            .map_err(|e| Error::IoError(format!("Failed to read stdin: {}", e)))?;
+    } else {
+        rust_code = std::fs::read_to_string(input)
+            .map_err(|e| Error::IoError(format!("Failed to read input file: {}", e)))?;
+    }
+    Ok(rust_code)
+}
+
+/// Flag that is set on SIGINT or SIGTERM, or, when stdin is a terminal, once
+/// it is closed or a line reading `q` is entered
+///
+/// Stdin is ignored otherwise: under `nohup`, systemd or `</dev/null` it is
+/// closed from the start, which must not stop a long-running watch.
+fn shutdown_flag() -> Arc<AtomicBool> {
+    let shutdown = Arc::new(AtomicBool::new(false));
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        let stop = Arc::clone(&shutdown);
+        // SAFETY: the handler only stores to an atomic, which is async-signal-safe
+        let registered = unsafe {
+            signal_hook_registry::register(signal, move || stop.store(true, Ordering::Relaxed))
+        };
+        if let Err(err) = registered {
+            warn!("Failed to handle signal {}: {}", signal, err);
+        }
+    }
+
+    if std::io::stdin().is_terminal() {
+        let stop = Arc::clone(&shutdown);
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lines() {
+                match line {
+                    Ok(line) if line.trim() != "q" => continue,
+                    _ => break,
+                }
+            }
+            stop.store(true, Ordering::Relaxed);
+        });
+    }
+    shutdown
+}
+
+/// Report a compilation error as `file:line:col: message` and exit
+fn fail(input: &Path, err: Error) -> ! {
+    let input = if input == Path::new(STDIN_PATH) {
+        "<stdin>".to_string()
+    } else {
+        input.display().to_string()
+    };
+    match err.location() {
+        Some(location) => eprintln!("{}:{}: {}", input, location, err),
+        None => eprintln!("{}: {}", input, err),
+    }
+    std::process::exit(1);
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -57,29 +176,92 @@ fn main() -> Result<()> {
         "rust2mojo=info"
     };
 
-    tracing_subscriber::fmt().with_env_filter(filter).init();
+    // Logs go to stderr so stdout carries only the generated output
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .init();
 
     match cli.command {
         Commands::Compile {
             input,
             output,
+            out_dir,
             stdout,
+            signatures,
+            emit,
         } => {
-            info!("Compiling Rust file: {:?}", input);
+            let config = CompilerConfig {
+                signatures_only: signatures,
+                ..CompilerConfig::default()
+            };
+            let compiler = Compiler::with_config(config);
 
-            let compiler = Compiler::new();
-            let mojo_code = compiler.compile_file(&input)?;
+            if input.is_dir() {
+                if emit != Emit::Mojo || output.is_some() || stdout {
+                    Cli::command()
+                        .error(
+                            ErrorKind::ArgumentConflict,
+                            "directory input only supports Mojo output via --out-dir",
+                        )
+                        .exit();
+                }
+                info!("Compiling Rust files under {:?}", input);
 
-            if stdout {
-                print!("{}", mojo_code);
-            } else {
-                let output_path = output.unwrap_or_else(|| input.with_extension("mojo"));
+                let out_dir = out_dir.unwrap_or_else(|| input.clone());
+                let summary = DirWatcher::new(&input, out_dir).compile_all(&compiler)?;
+                info!("{} succeeded, {} failed", summary.succeeded, summary.failed);
+                if summary.failed > 0 {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
 
-                std::fs::write(&output_path, mojo_code).map_err(|e| {
-                    rust2mojo::Error::IoError(format!("Failed to write output: {}", e))
-                })?;
+            // The output file is named after the input, which stdin does not have
+            if input == Path::new(STDIN_PATH) && out_dir.is_some() && output.is_none() {
+                Cli::command()
+                    .error(
+                        ErrorKind::ArgumentConflict,
+                        "stdin input needs --output to name the file written under --out-dir",
+                    )
+                    .exit();
+            }
+
+            info!("Compiling Rust file: {:?}", input);
+            let output = output.or_else(|| {
+                let file_name = input.file_name()?;
+                Some(out_dir?.join(file_name).with_extension("mojo"))
+            });
+            let rust_code =
+                read_input(&input, std::io::stdin()).unwrap_or_else(|err| fail(&input, err));
+            let (code, output_path) = match emit {
+                // Without an output path, stdin input is compiled to stdout
+                Emit::Mojo => (
+                    compiler.compile_str(&rust_code),
+                    output.or_else(|| {
+                        (input != Path::new(STDIN_PATH)).then(|| input.with_extension("mojo"))
+                    }),
+                ),
+                // The AST goes to stdout unless an output path is given
+                Emit::Ast => (
+                    compiler.ast_json(&rust_code).map(|json| json + "\n"),
+                    output,
+                ),
+                Emit::AstTree => (
+                    compiler.parse(&rust_code).map(|unit| unit.to_string()),
+                    output,
+                ),
+            };
+            let code = code.unwrap_or_else(|err| fail(&input, err));
 
-                info!("Generated Mojo code: {:?}", output_path);
+            match output_path {
+                Some(output_path) if !stdout => {
+                    std::fs::write(&output_path, code)
+                        .map_err(|e| Error::IoError(format!("Failed to write output: {}", e)))?;
+
+                    info!("Generated output: {:?}", output_path);
+                }
+                _ => print!("{}", code),
             }
         }
 
@@ -87,11 +269,28 @@ fn main() -> Result<()> {
             info!("Checking Rust file: {:?}", input);
 
             let compiler = Compiler::new();
-            let _mojo_code = compiler.compile_file(&input)?;
+            let _mojo_code = read_input(&input, std::io::stdin())
+                .and_then(|rust_code| compiler.compile_str(&rust_code))
+                .unwrap_or_else(|err| fail(&input, err));
 
             info!("✓ Rust code can be successfully compiled to Mojo");
         }
 
+        Commands::Watch { input, output } => {
+            let output = output.unwrap_or_else(|| input.with_extension("mojo"));
+            info!("Watching {:?} -> {:?}", input, output);
+
+            let shutdown = shutdown_flag();
+            FileWatcher::new(input, output).run(&Compiler::new(), &shutdown)?;
+        }
+
+        Commands::WatchDir { src, out } => {
+            info!("Watching {:?} -> {:?}", src, out);
+
+            let shutdown = shutdown_flag();
+            DirWatcher::new(src, out).run(&Compiler::new(), &shutdown)?;
+        }
+
         Commands::Version => {
             println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
             println!("Rust to Mojo compiler");
@@ -100,3 +299,27 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stdin_compiles_like_file() {
+        let rust_code = "fn add(a: i32, b: i32) -> i32 { a + b }\n";
+        let path = std::env::temp_dir().join(format!("rust2mojo-stdin-{}.rs", std::process::id()));
+        std::fs::write(&path, rust_code).unwrap();
+
+        let compiler = Compiler::new();
+        let from_file = compiler.compile_file(&path);
+        let file_input = read_input(&path, std::io::empty());
+        std::fs::remove_file(&path).unwrap();
+
+        let stdin_input = read_input(Path::new(STDIN_PATH), rust_code.as_bytes()).unwrap();
+        assert_eq!(stdin_input, file_input.unwrap());
+        assert_eq!(
+            compiler.compile_str(&stdin_input).unwrap(),
+            from_file.unwrap()
+        );
+    }
+}
@@ -3,82 +3,380 @@
 //! This module handles parsing Rust source code into our intermediate AST representation.
 
 use crate::ast::*;
-use crate::error::Result;
-use syn::{visit::Visit, File, Item as SynItem};
+use crate::error::{Error, Result, SourceLocation, Warning};
+use crate::CompilerOptions;
+use syn::{spanned::Spanned, visit::Visit, File, Item as SynItem};
 
 /// Parse Rust source code into our intermediate representation
 pub fn parse_rust_code(source: &str) -> Result<CompilationUnit> {
+    parse_rust_code_with_options(source, &CompilerOptions::default())
+}
+
+/// Parse Rust source code into our intermediate representation using the given options
+///
+/// In strict mode, items that cannot be converted are an error instead of being skipped.
+pub fn parse_rust_code_with_options(
+    source: &str,
+    options: &CompilerOptions,
+) -> Result<CompilationUnit> {
+    parse_rust_code_with_warnings(source, options).map(|(unit, _)| unit)
+}
+
+/// Parse Rust source code, also returning warnings for items that were skipped
+pub fn parse_rust_code_with_warnings(
+    source: &str,
+    options: &CompilerOptions,
+) -> Result<(CompilationUnit, Vec<Warning>)> {
+    let converter = convert_file(source, options)?;
+    Ok((new_unit(converter.items), converter.warnings))
+}
+
+/// Parse Rust source code, also returning the kind of each item that was skipped
+pub(crate) fn parse_rust_code_with_skipped(
+    source: &str,
+    options: &CompilerOptions,
+) -> Result<(CompilationUnit, Vec<String>)> {
+    let converter = convert_file(source, options)?;
+    Ok((new_unit(converter.items), converter.skipped))
+}
+
+fn convert_file(source: &str, options: &CompilerOptions) -> Result<AstConverter> {
     let syntax_tree: File = syn::parse_str(source)?;
 
-    let mut converter = AstConverter::new();
+    let mut converter = AstConverter::new(options.strict);
     converter.visit_file(&syntax_tree);
+    match converter.error.take() {
+        Some(err) => Err(err),
+        None => Ok(converter),
+    }
+}
+
+/// Parse Rust source code, recovering from errors instead of stopping at the first
+///
+/// When the file does not parse as a whole it is split at top-level item
+/// boundaries and each piece is parsed on its own, so one broken function does
+/// not hide errors in the next. Items that fail to convert are reported and
+/// left out. Error locations always refer to the full source.
+pub fn parse_rust_code_collect(
+    source: &str,
+    options: &CompilerOptions,
+) -> (CompilationUnit, Vec<Error>) {
+    let start = SourceLocation { line: 1, column: 1 };
+    let files: Vec<(SourceLocation, syn::Result<File>)> = match syn::parse_str::<File>(source) {
+        Ok(file) => vec![(start, Ok(file))],
+        Err(_) => split_top_level_items(source)
+            .into_iter()
+            .map(|(start, chunk)| (start, syn::parse_str::<File>(chunk)))
+            .collect(),
+    };
+
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    for (start, file) in files {
+        let file = match file {
+            Ok(file) => file,
+            Err(err) => {
+                errors.push(Error::from(err).relative_to(start));
+                continue;
+            }
+        };
+        for item in &file.items {
+            match convert_top_level_item(item, options) {
+                Ok(converted) => items.extend(converted),
+                Err(err) => errors.push(err.relative_to(start)),
+            }
+        }
+    }
+    (new_unit(items), errors)
+}
 
-    Ok(CompilationUnit {
-        items: converter.items,
+fn new_unit(items: Vec<Item>) -> CompilationUnit {
+    CompilationUnit {
+        items,
         metadata: CompilationMetadata {
             source_file: None,
             rust_edition: "2021".to_string(),
             target_mojo_version: "24.5".to_string(),
         },
-    })
+    }
+}
+
+/// Split source text after each top-level `}` or `;`
+///
+/// Comments and string and char literals are skipped so delimiters inside them
+/// don't count. Each piece is paired with the position it starts at. Unbalanced
+/// delimiters leave the rest of the source in one piece.
+fn split_top_level_items(source: &str) -> Vec<(SourceLocation, &str)> {
+    let mut pieces = Vec::new();
+    let mut depth = 0usize;
+    let mut piece_start = 0;
+    let mut index = 0;
+
+    while let Some(c) = source[index..].chars().next() {
+        let rest = &source[index..];
+        let mut end_piece = false;
+        let len = if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if rest.starts_with("/*") {
+            block_comment_len(rest)
+        } else if c == '"' {
+            string_len(rest)
+        } else if let Some(len) = raw_string_len(rest, &source[..index]) {
+            len
+        } else if let Some(len) = char_literal_len(rest) {
+            len
+        } else {
+            match c {
+                '{' | '(' | '[' => depth += 1,
+                ')' | ']' => depth = depth.saturating_sub(1),
+                '}' => {
+                    depth = depth.saturating_sub(1);
+                    end_piece = depth == 0;
+                }
+                ';' => end_piece = depth == 0,
+                _ => {}
+            }
+            c.len_utf8()
+        };
+        index += len;
+
+        if end_piece {
+            pieces.push((
+                location_of(source, piece_start),
+                &source[piece_start..index],
+            ));
+            piece_start = index;
+        }
+    }
+
+    if !source[piece_start..].trim().is_empty() {
+        pieces.push((location_of(source, piece_start), &source[piece_start..]));
+    }
+    pieces
+}
+
+/// Length of the (possibly nested) block comment at the start of `rest`
+fn block_comment_len(rest: &str) -> usize {
+    let mut nesting = 0;
+    let mut index = 0;
+    while index < rest.len() {
+        if rest[index..].starts_with("/*") {
+            nesting += 1;
+            index += 2;
+        } else if rest[index..].starts_with("*/") {
+            nesting -= 1;
+            index += 2;
+            if nesting == 0 {
+                return index;
+            }
+        } else {
+            index += rest[index..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    rest.len()
+}
+
+/// Length of the string literal at the start of `rest`, quotes included
+fn string_len(rest: &str) -> usize {
+    let mut chars = rest.char_indices().skip(1);
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return index + 1,
+            _ => {}
+        }
+    }
+    rest.len()
+}
+
+/// Length of the raw string literal (`r"…"`, `r#"…"#`) at the start of `rest`
+fn raw_string_len(rest: &str, before: &str) -> Option<usize> {
+    let continues_identifier = before
+        .chars()
+        .next_back()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_');
+    if !rest.starts_with('r') || continues_identifier {
+        return None;
+    }
+    let hashes = rest[1..].chars().take_while(|&c| c == '#').count();
+    let body_start = 1 + hashes;
+    if !rest[body_start..].starts_with('"') {
+        return None;
+    }
+    let closing = format!("\"{}", "#".repeat(hashes));
+    Some(
+        rest[body_start + 1..]
+            .find(&closing)
+            .map_or(rest.len(), |end| body_start + 1 + end + closing.len()),
+    )
+}
+
+/// Length of the char literal at the start of `rest`; `None` for a lifetime
+fn char_literal_len(rest: &str) -> Option<usize> {
+    let body = rest.strip_prefix('\'')?;
+    let mut chars = body.chars();
+    match chars.next()? {
+        '\\' => {
+            // The escaped character may be more than one byte long
+            let start = 1 + chars.next()?.len_utf8();
+            body.get(start..)?.find('\'').map(|end| end + start + 2)
+        }
+        c if chars.next() == Some('\'') => Some(c.len_utf8() + 2),
+        _ => None,
+    }
+}
+
+/// Line and column of a byte offset into the source
+fn location_of(source: &str, offset: usize) -> SourceLocation {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    SourceLocation {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
+
+/// Convert a single top-level syn item, along with any items nested inside it
+///
+/// Lets callers that already hold a parsed [`syn::File`] convert its items one at
+/// a time instead of building the whole [`CompilationUnit`] up front.
+pub fn convert_top_level_item(item: &SynItem, options: &CompilerOptions) -> Result<Vec<Item>> {
+    let mut converter = AstConverter::new(options.strict);
+    converter.visit_item(item);
+    match converter.error {
+        Some(err) => Err(err),
+        None => Ok(converter.items),
+    }
 }
 
 /// Convert syn AST to our intermediate representation
 struct AstConverter {
     items: Vec<Item>,
+    /// First conversion error encountered, reported once the visit completes
+    error: Option<Error>,
+    /// Reject unsupported items instead of skipping them
+    strict: bool,
+    /// Items that were skipped in lenient mode
+    warnings: Vec<Warning>,
+    /// Kinds of the skipped items, e.g. `union item`
+    skipped: Vec<String>,
 }
 
 impl AstConverter {
-    fn new() -> Self {
-        Self { items: Vec::new() }
+    fn new(strict: bool) -> Self {
+        Self {
+            items: Vec::new(),
+            error: None,
+            strict,
+            warnings: Vec::new(),
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Convert an inline module, sending its items through [`Visit::visit_item`]
+    /// so unsupported ones are skipped or rejected like top-level items
+    fn convert_module(&mut self, item_mod: &syn::ItemMod) -> Result<Module> {
+        let Some((_, content)) = &item_mod.content else {
+            return Err(Error::UnsupportedFeature {
+                feature: format!(
+                    "external module `mod {};` (only inline modules are supported)",
+                    item_mod.ident
+                ),
+                location: SourceLocation::from_span(item_mod.span()),
+            });
+        };
+
+        let enclosing_items = std::mem::take(&mut self.items);
+        for item in content {
+            self.visit_item(item);
+        }
+        let items = std::mem::replace(&mut self.items, enclosing_items);
+
+        Ok(Module {
+            name: item_mod.ident.to_string(),
+            visibility: convert_visibility(&item_mod.vis),
+            items,
+        })
     }
 }
 
 impl<'ast> Visit<'ast> for AstConverter {
     fn visit_item(&mut self, item: &'ast SynItem) {
-        match item {
-            SynItem::Fn(item_fn) => {
-                let function = convert_function(item_fn);
-                self.items.push(Item::Function(function));
-            }
-            SynItem::Struct(item_struct) => {
-                let struct_item = convert_struct(item_struct);
-                self.items.push(Item::Struct(struct_item));
-            }
-            SynItem::Enum(item_enum) => {
-                let enum_item = convert_enum(item_enum);
-                self.items.push(Item::Enum(enum_item));
-            }
-            SynItem::Impl(item_impl) => {
-                let impl_item = convert_impl(item_impl);
-                self.items.push(Item::Impl(impl_item));
-            }
-            SynItem::Use(item_use) => {
-                let use_item = convert_use(item_use);
-                self.items.push(Item::Use(use_item));
-            }
-            SynItem::Mod(item_mod) => {
-                let mod_item = convert_module(item_mod);
-                self.items.push(Item::Mod(mod_item));
-            }
-            SynItem::Const(item_const) => {
-                let const_item = convert_const(item_const);
-                self.items.push(Item::Const(const_item));
-            }
-            SynItem::Static(item_static) => {
-                let static_item = convert_static(item_static);
-                self.items.push(Item::Static(static_item));
+        let converted = match item {
+            SynItem::Mod(item_mod) => self.convert_module(item_mod).map(|m| Some(Item::Mod(m))),
+            _ => convert_item(item),
+        };
+        match converted {
+            Ok(Some(converted)) => self.items.push(converted),
+            Ok(None) => {
+                let construct = format!("{} item", item_kind(item));
+                let location = SourceLocation::from_span(item.span());
+                if self.strict {
+                    self.error.get_or_insert(Error::UnsupportedFeature {
+                        feature: construct,
+                        location,
+                    });
+                } else {
+                    self.warnings.push(Warning {
+                        message: format!("skipped unsupported {}", construct),
+                        location,
+                    });
+                    self.skipped.push(construct);
+                }
             }
-            SynItem::Type(item_type) => {
-                let type_item = convert_type_alias(item_type);
-                self.items.push(Item::Type(type_item));
+            Err(err) => {
+                self.error.get_or_insert(err);
             }
-            _ => {
-                // TODO: Handle other item types or emit warnings
+        }
+
+        // Module contents are visited by `convert_module` itself
+        if !matches!(item, SynItem::Mod(_)) {
+            syn::visit::visit_item(self, item);
+        }
+    }
+
+    /// Warn about the lifetime bounds `convert_generics` drops, e.g. `T: 'a`
+    fn visit_type_param(&mut self, param: &'ast syn::TypeParam) {
+        for bound in &param.bounds {
+            if let syn::TypeParamBound::Lifetime(lifetime) = bound {
+                self.warnings.push(Warning {
+                    message: format!("dropped lifetime bound `{}` on `{}`", lifetime, param.ident),
+                    location: SourceLocation::from_span(lifetime.span()),
+                });
             }
         }
+        syn::visit::visit_type_param(self, param);
+    }
+}
+
+/// Convert an item other than a module, which needs the converter to visit its contents
+fn convert_item(item: &SynItem) -> Result<Option<Item>> {
+    let converted = match item {
+        SynItem::Fn(item_fn) => Item::Function(convert_function(item_fn)),
+        SynItem::Struct(item_struct) => Item::Struct(convert_struct(item_struct)),
+        SynItem::Enum(item_enum) => Item::Enum(convert_enum(item_enum)),
+        SynItem::Impl(item_impl) => Item::Impl(convert_impl(item_impl)),
+        SynItem::Use(item_use) => Item::Use(convert_use(item_use)),
+        SynItem::Const(item_const) => Item::Const(convert_const(item_const)),
+        SynItem::Static(item_static) => Item::Static(convert_static(item_static)),
+        SynItem::Type(item_type) => Item::Type(convert_type_alias(item_type)),
+        SynItem::Trait(item_trait) => Item::Trait(convert_trait(item_trait)),
+        _ => return Ok(None),
+    };
+    Ok(Some(converted))
+}
 
-        syn::visit::visit_item(self, item);
+/// Human-readable name of an item kind, for diagnostics
+fn item_kind(item: &SynItem) -> &'static str {
+    match item {
+        SynItem::ExternCrate(_) => "`extern crate`",
+        SynItem::ForeignMod(_) => "`extern` block",
+        SynItem::Macro(_) => "macro",
+        SynItem::TraitAlias(_) => "trait alias",
+        SynItem::Union(_) => "union",
+        _ => "unknown",
     }
 }
 
@@ -87,6 +385,12 @@ fn convert_function(item_fn: &syn::ItemFn) -> Function {
         name: item_fn.sig.ident.to_string(),
         visibility: convert_visibility(&item_fn.vis),
         generics: convert_generics(&item_fn.sig.generics),
+        lifetimes: item_fn
+            .sig
+            .generics
+            .lifetimes()
+            .map(|param| param.lifetime.ident.to_string())
+            .collect(),
         parameters: convert_parameters(&item_fn.sig.inputs),
         return_type: convert_return_type(&item_fn.sig.output),
         body: convert_block(&item_fn.block),
@@ -122,22 +426,94 @@ fn convert_impl(item_impl: &syn::ItemImpl) -> Impl {
             .as_ref()
             .map(|(_, path, _)| convert_path_type(path)),
         generics: convert_generics(&item_impl.generics),
-        items: item_impl.items.iter().map(convert_impl_item).collect(),
+        items: item_impl
+            .items
+            .iter()
+            .filter_map(convert_impl_item)
+            .collect(),
+    }
+}
+
+fn convert_trait(item_trait: &syn::ItemTrait) -> Trait {
+    Trait {
+        name: item_trait.ident.to_string(),
+        visibility: convert_visibility(&item_trait.vis),
+        generics: convert_generics(&item_trait.generics),
+        items: item_trait
+            .items
+            .iter()
+            .filter_map(convert_trait_item)
+            .collect(),
+        attributes: convert_attributes(&item_trait.attrs),
+    }
+}
+
+fn convert_trait_item(item: &syn::TraitItem) -> Option<TraitItem> {
+    match item {
+        syn::TraitItem::Fn(trait_fn) => Some(TraitItem::Function(TraitFunction {
+            name: trait_fn.sig.ident.to_string(),
+            generics: convert_generics(&trait_fn.sig.generics),
+            parameters: convert_parameters(&trait_fn.sig.inputs),
+            return_type: convert_return_type(&trait_fn.sig.output),
+            default_body: trait_fn.default.as_ref().map(convert_block),
+            attributes: convert_attributes(&trait_fn.attrs),
+        })),
+        syn::TraitItem::Type(trait_type) => Some(TraitItem::Type {
+            name: trait_type.ident.to_string(),
+            bounds: trait_type
+                .bounds
+                .iter()
+                .filter_map(|bound| match bound {
+                    syn::TypeParamBound::Trait(trait_bound) => {
+                        Some(Type::Path(path_to_string(&trait_bound.path)))
+                    }
+                    _ => None,
+                })
+                .collect(),
+        }),
+        _ => None, // Associated consts and macros are not supported yet
     }
 }
 
 fn convert_use(item_use: &syn::ItemUse) -> Use {
+    let mut imports = Vec::new();
+    collect_use_imports(&item_use.tree, &mut Vec::new(), &mut imports);
     Use {
-        path: quote::quote!(#item_use).to_string(),
+        imports,
         visibility: convert_visibility(&item_use.vis),
     }
 }
 
-fn convert_module(item_mod: &syn::ItemMod) -> Module {
-    Module {
-        name: item_mod.ident.to_string(),
-        visibility: convert_visibility(&item_mod.vis),
-        items: Vec::new(), // TODO: Convert module items
+/// Flatten a use tree into one import per name, with `prefix` the path so far
+fn collect_use_imports(tree: &syn::UseTree, prefix: &mut Vec<String>, out: &mut Vec<UseImport>) {
+    let mut import = |name: String, alias: Option<String>| {
+        // `a::b::{self}` imports `b` itself
+        let (module, name) = match (name.as_str(), prefix.split_last()) {
+            ("self", Some((last, module))) => (module.to_vec(), last.clone()),
+            _ => (prefix.clone(), name),
+        };
+        out.push(UseImport {
+            module,
+            name,
+            alias,
+        });
+    };
+    match tree {
+        syn::UseTree::Path(path) => {
+            prefix.push(path.ident.to_string());
+            collect_use_imports(&path.tree, prefix, out);
+            prefix.pop();
+        }
+        syn::UseTree::Name(name) => import(name.ident.to_string(), None),
+        syn::UseTree::Rename(rename) => {
+            import(rename.ident.to_string(), Some(rename.rename.to_string()))
+        }
+        syn::UseTree::Glob(_) => import("*".to_string(), None),
+        syn::UseTree::Group(group) => {
+            for tree in &group.items {
+                collect_use_imports(tree, prefix, out);
+            }
+        }
     }
 }
 
@@ -173,13 +549,44 @@ fn convert_type_alias(item_type: &syn::ItemType) -> TypeAlias {
 fn convert_visibility(vis: &syn::Visibility) -> Visibility {
     match vis {
         syn::Visibility::Public(_) => Visibility::Public,
-        syn::Visibility::Restricted(_) => Visibility::Private, // pub(crate), pub(super), etc. treated as private
+        syn::Visibility::Restricted(restricted) => {
+            if restricted.in_token.is_some() {
+                Visibility::InPath(path_to_string(&restricted.path))
+            } else if restricted.path.is_ident("crate") {
+                Visibility::Crate
+            } else if restricted.path.is_ident("super") {
+                Visibility::Super
+            } else {
+                // `pub(self)` is the same as private
+                Visibility::Private
+            }
+        }
         syn::Visibility::Inherited => Visibility::Private,
     }
 }
 
-fn convert_generics(_generics: &syn::Generics) -> Vec<Generic> {
-    Vec::new() // TODO: Implement proper conversion
+fn convert_generics(generics: &syn::Generics) -> Vec<Generic> {
+    // Lifetime and const parameters have no Mojo counterpart yet and are skipped
+    generics
+        .type_params()
+        .map(|param| Generic {
+            name: param.ident.to_string(),
+            bounds: param
+                .bounds
+                .iter()
+                .filter_map(|bound| match bound {
+                    syn::TypeParamBound::Trait(trait_bound) => {
+                        Some(Type::Path(path_to_string(&trait_bound.path)))
+                    }
+                    // Lifetime bounds like `T: 'a` only constrain the borrow
+                    // checker; Mojo has nothing to express them with, so the
+                    // converter warns about them in `visit_type_param`
+                    syn::TypeParamBound::Lifetime(_) => None,
+                    _ => None,
+                })
+                .collect(),
+        })
+        .collect()
 }
 
 fn convert_parameters(
@@ -200,12 +607,38 @@ fn convert_parameters(
                         None // Skip complex patterns for now
                     }
                 }
-                syn::FnArg::Receiver(_) => None, // Skip self parameters for now
+                // `receiver.ty` is already `&Self`, `&mut Self` or `Self`
+                syn::FnArg::Receiver(receiver) => Some(Parameter {
+                    name: "self".to_string(),
+                    type_: convert_type(&receiver.ty),
+                    mutable: receiver.mutability.is_some(),
+                }),
             }
         })
         .collect()
 }
 
+/// Convert a closure parameter; an unannotated parameter gets the inferred type `_`
+fn convert_closure_parameter(pat: &syn::Pat) -> Option<Parameter> {
+    let (pat, type_) = match pat {
+        syn::Pat::Type(pat_type) => (&*pat_type.pat, convert_type(&pat_type.ty)),
+        _ => (pat, Type::Path("_".to_string())),
+    };
+    match pat {
+        syn::Pat::Ident(pat_ident) => Some(Parameter {
+            name: pat_ident.ident.to_string(),
+            type_,
+            mutable: pat_ident.mutability.is_some(),
+        }),
+        syn::Pat::Wild(_) => Some(Parameter {
+            name: "_".to_string(),
+            type_,
+            mutable: false,
+        }),
+        _ => None, // Skip complex patterns for now
+    }
+}
+
 fn convert_return_type(output: &syn::ReturnType) -> Option<Type> {
     match output {
         syn::ReturnType::Default => None,
@@ -217,8 +650,25 @@ fn convert_block(block: &syn::Block) -> Vec<Statement> {
     block.stmts.iter().filter_map(convert_statement).collect()
 }
 
-fn convert_attributes(_attrs: &[syn::Attribute]) -> Vec<Attribute> {
-    Vec::new() // TODO: Implement proper conversion
+fn convert_attributes(attrs: &[syn::Attribute]) -> Vec<Attribute> {
+    attrs
+        .iter()
+        .map(|attr| {
+            let path = attr.path();
+            let tokens = match &attr.meta {
+                syn::Meta::Path(_) => String::new(),
+                syn::Meta::List(meta_list) => meta_list.tokens.to_string(),
+                syn::Meta::NameValue(meta_name_value) => {
+                    let value = &meta_name_value.value;
+                    quote::quote!(#value).to_string()
+                }
+            };
+            Attribute {
+                path: quote::quote!(#path).to_string(),
+                tokens,
+            }
+        })
+        .collect()
 }
 
 fn convert_struct_fields(fields: &syn::Fields) -> Vec<Field> {
@@ -254,56 +704,58 @@ fn convert_struct_fields(fields: &syn::Fields) -> Vec<Field> {
     }
 }
 
-fn convert_variant(_variant: &syn::Variant) -> Variant {
+fn convert_variant(variant: &syn::Variant) -> Variant {
+    let data = match &variant.fields {
+        syn::Fields::Named(_) => VariantData::Struct(convert_struct_fields(&variant.fields)),
+        syn::Fields::Unnamed(fields_unnamed) => VariantData::Tuple(
+            fields_unnamed
+                .unnamed
+                .iter()
+                .map(|field| convert_type(&field.ty))
+                .collect(),
+        ),
+        syn::Fields::Unit => VariantData::Unit,
+    };
     Variant {
-        name: _variant.ident.to_string(),
-        data: VariantData::Unit, // TODO: Implement proper conversion
+        name: variant.ident.to_string(),
+        data,
     }
 }
 
-fn convert_impl_item(_item: &syn::ImplItem) -> ImplItem {
-    match _item {
-        syn::ImplItem::Fn(method) => ImplItem::Function(convert_function(&syn::ItemFn {
+fn convert_impl_item(item: &syn::ImplItem) -> Option<ImplItem> {
+    match item {
+        syn::ImplItem::Fn(method) => Some(ImplItem::Function(convert_function(&syn::ItemFn {
             attrs: method.attrs.clone(),
             vis: method.vis.clone(),
             sig: method.sig.clone(),
             block: Box::new(method.block.clone()),
+        }))),
+        syn::ImplItem::Const(item_const) => Some(ImplItem::Const(Const {
+            name: item_const.ident.to_string(),
+            visibility: convert_visibility(&item_const.vis),
+            type_: convert_type(&item_const.ty),
+            value: convert_expression(&item_const.expr),
         })),
-        _ => {
-            // TODO: Handle other impl item types
-            ImplItem::Function(Function {
-                name: "placeholder".to_string(),
-                visibility: Visibility::Private,
-                generics: Vec::new(),
-                parameters: Vec::new(),
-                return_type: None,
-                body: Vec::new(),
-                attributes: Vec::new(),
-            })
-        }
+        syn::ImplItem::Type(item_type) => Some(ImplItem::Type(TypeAlias {
+            name: item_type.ident.to_string(),
+            visibility: convert_visibility(&item_type.vis),
+            generics: convert_generics(&item_type.generics),
+            type_: convert_type(&item_type.ty),
+        })),
+        // Macro invocations and verbatim tokens have no structure to convert
+        _ => None,
     }
 }
 
 fn convert_type(ty: &syn::Type) -> Type {
     match ty {
-        syn::Type::Path(type_path) => {
-            if let Some(ident) = type_path.path.get_ident() {
-                let type_str = ident.to_string();
-                Type::Path(type_str)
-            } else {
-                // Handle complex paths
-                let path_str = type_path
-                    .path
-                    .segments
-                    .iter()
-                    .map(|s| s.ident.to_string())
-                    .collect::<Vec<_>>()
-                    .join("::");
-                Type::Path(path_str)
-            }
-        }
+        syn::Type::Path(type_path) => convert_path_type(&type_path.path),
         syn::Type::Reference(type_ref) => Type::Reference {
             mutable: type_ref.mutability.is_some(),
+            lifetime: type_ref
+                .lifetime
+                .as_ref()
+                .map(|lifetime| lifetime.ident.to_string()),
             inner: Box::new(convert_type(&type_ref.elem)),
         },
         syn::Type::Ptr(type_ptr) => Type::Pointer {
@@ -324,6 +776,15 @@ fn convert_type(ty: &syn::Type) -> Type {
             Type::Array { inner, size }
         }
         syn::Type::Slice(type_slice) => Type::Slice(Box::new(convert_type(&type_slice.elem))),
+        syn::Type::Never(_) => Type::Never,
+        syn::Type::BareFn(bare_fn) => Type::Function {
+            params: bare_fn
+                .inputs
+                .iter()
+                .map(|arg| convert_type(&arg.ty))
+                .collect(),
+            return_: Box::new(convert_return_type(&bare_fn.output).unwrap_or(Type::Unit)),
+        },
         syn::Type::Tuple(type_tuple) => {
             if type_tuple.elems.is_empty() {
                 Type::Unit
@@ -338,15 +799,31 @@ fn convert_type(ty: &syn::Type) -> Type {
     }
 }
 
-fn convert_path_type(_path: &syn::Path) -> Type {
-    Type::Path(
-        _path
-            .segments
-            .iter()
-            .map(|s| s.ident.to_string())
-            .collect::<Vec<_>>()
-            .join("::"),
-    )
+fn convert_generic_arguments(
+    args: &syn::punctuated::Punctuated<syn::GenericArgument, syn::Token![,]>,
+) -> Vec<Type> {
+    args.iter()
+        .filter_map(|arg| match arg {
+            syn::GenericArgument::Type(ty) => Some(convert_type(ty)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn convert_path_type(path: &syn::Path) -> Type {
+    let name = path_to_string(path);
+    let args = match path.segments.last().map(|segment| &segment.arguments) {
+        Some(syn::PathArguments::AngleBracketed(generic_args)) => {
+            convert_generic_arguments(&generic_args.args)
+        }
+        _ => Vec::new(),
+    };
+
+    if args.is_empty() {
+        Type::Path(name)
+    } else {
+        Type::Generic { name, args }
+    }
 }
 
 fn convert_statement(stmt: &syn::Stmt) -> Option<Statement> {
@@ -361,33 +838,53 @@ fn convert_statement(stmt: &syn::Stmt) -> Option<Statement> {
             match expr {
                 syn::Expr::If(expr_if) => convert_if_statement(expr_if),
                 syn::Expr::While(expr_while) => convert_while_statement(expr_while),
+                syn::Expr::Break(expr_break) => {
+                    Some(Statement::Break(loop_label(expr_break.label.as_ref())))
+                }
+                syn::Expr::Continue(expr_continue) => Some(Statement::Continue(loop_label(
+                    expr_continue.label.as_ref(),
+                ))),
+                syn::Expr::Loop(expr_loop) => Some(Statement::Loop {
+                    body: convert_block(&expr_loop.body),
+                    label: loop_label(expr_loop.label.as_ref().map(|label| &label.name)),
+                }),
                 syn::Expr::ForLoop(expr_for) => convert_for_statement(expr_for),
+                syn::Expr::Match(expr_match) => convert_match_statement(expr_match),
+                syn::Expr::Block(expr_block) if expr_block.label.is_none() => {
+                    Some(Statement::Block(convert_block(&expr_block.block)))
+                }
                 syn::Expr::Return(expr_return) => {
                     let value = expr_return.expr.as_ref().map(|e| convert_expression(e));
                     Some(Statement::Return(value))
                 }
+                syn::Expr::Macro(expr_macro) => Some(convert_macro_statement(&expr_macro.mac)),
                 _ => {
                     // Regular expression statement
                     Some(Statement::Expression(convert_expression(expr)))
                 }
             }
         }
-        syn::Stmt::Macro(_) => {
-            // Macro calls, skip for now
-            None
-        }
+        syn::Stmt::Macro(stmt_macro) => Some(convert_macro_statement(&stmt_macro.mac)),
     }
 }
 
+/// Loop label name without the leading `'`
+fn loop_label(label: Option<&syn::Lifetime>) -> Option<String> {
+    label.map(|label| label.ident.to_string())
+}
+
 fn convert_local_statement(local: &syn::Local) -> Option<Statement> {
+    // `let x: T = ...` wraps the identifier pattern in a `Pat::Type`
+    let (pat, type_) = match &local.pat {
+        syn::Pat::Type(pat_type) => (&*pat_type.pat, Some(convert_type(&pat_type.ty))),
+        pat => (pat, None),
+    };
+
     // Extract variable name from pattern
-    if let syn::Pat::Ident(pat_ident) = &local.pat {
+    if let syn::Pat::Ident(pat_ident) = pat {
         let name = pat_ident.ident.to_string();
         let mutable = pat_ident.mutability.is_some();
 
-        // Extract type annotation if present - syn::Local doesn't have ty field directly
-        let type_ = None; // TODO: Extract from pattern type annotations when present
-
         // Extract initializer if present
         let value = local
             .init
@@ -401,8 +898,13 @@ fn convert_local_statement(local: &syn::Local) -> Option<Statement> {
             value,
         })
     } else {
-        // Complex patterns not supported yet
-        None
+        // Destructuring needs a value to take apart
+        let init = local.init.as_ref()?;
+        Some(Statement::LetPattern {
+            pattern: convert_pattern(pat),
+            type_,
+            value: convert_expression(&init.expr),
+        })
     }
 }
 
@@ -424,17 +926,41 @@ fn convert_expression(expr: &syn::Expr) -> Expression {
                 Expression::Path(path)
             }
         }
-        syn::Expr::Binary(expr_binary) => {
-            let left = Box::new(convert_expression(&expr_binary.left));
-            let right = Box::new(convert_expression(&expr_binary.right));
-            let op = convert_binary_operator(&expr_binary.op);
-            Expression::Binary { left, op, right }
-        }
+        syn::Expr::Binary(expr_binary) => match convert_binary_operator(&expr_binary.op) {
+            Some(op) => Expression::Binary {
+                left: Box::new(convert_expression(&expr_binary.left)),
+                op,
+                right: Box::new(convert_expression(&expr_binary.right)),
+            },
+            None => {
+                let op = &expr_binary.op;
+                unsupported(
+                    format!("binary operator `{}`", quote::quote!(#op)),
+                    op.span(),
+                )
+            }
+        },
         syn::Expr::Call(expr_call) => {
             let function = Box::new(convert_expression(&expr_call.func));
             let args = expr_call.args.iter().map(convert_expression).collect();
             Expression::Call { function, args }
         }
+        syn::Expr::MethodCall(expr_method) => {
+            let receiver = Box::new(convert_expression(&expr_method.receiver));
+            let method = expr_method.method.to_string();
+            let turbofish = expr_method
+                .turbofish
+                .as_ref()
+                .map(|generics| convert_generic_arguments(&generics.args))
+                .unwrap_or_default();
+            let args = expr_method.args.iter().map(convert_expression).collect();
+            Expression::MethodCall {
+                receiver,
+                method,
+                turbofish,
+                args,
+            }
+        }
         syn::Expr::Return(expr_return) => {
             // Return expressions are handled as statements in our AST
             match &expr_return.expr {
@@ -442,13 +968,12 @@ fn convert_expression(expr: &syn::Expr) -> Expression {
                 None => Expression::Literal(Literal::String("()".to_string())), // Unit return
             }
         }
-        syn::Expr::If(_expr_if) => {
-            // If expressions are complex, for now return a placeholder
-            Expression::Literal(Literal::String("if_expr_placeholder".to_string()))
+        syn::Expr::If(expr_if) => {
+            Expression::Block(convert_if_statement(expr_if).into_iter().collect())
         }
-        syn::Expr::Block(_expr_block) => {
-            // Block expressions can contain statements
-            Expression::Literal(Literal::String("block_expr_placeholder".to_string()))
+        // The block's trailing expression is its value
+        syn::Expr::Block(expr_block) if expr_block.label.is_none() => {
+            Expression::Block(convert_block(&expr_block.block))
         }
         syn::Expr::Assign(expr_assign) => {
             let left = Box::new(convert_expression(&expr_assign.left));
@@ -460,47 +985,120 @@ fn convert_expression(expr: &syn::Expr) -> Expression {
             }
         }
         syn::Expr::Field(expr_field) => {
-            // Field access like obj.field
-            let base = convert_expression(&expr_field.base);
-            if let syn::Member::Named(field_name) = &expr_field.member {
-                Expression::Path(format!(
-                    "{}.{}",
-                    match base {
-                        Expression::Identifier(id) => id,
-                        Expression::Path(path) => path,
-                        _ => "unknown".to_string(),
-                    },
-                    field_name
-                ))
-            } else {
-                Expression::Literal(Literal::String("field_access_placeholder".to_string()))
+            // Field access like `obj.field`, or a tuple index like `pair.0`
+            let field = match &expr_field.member {
+                syn::Member::Named(field_name) => field_name.to_string(),
+                syn::Member::Unnamed(index) => index.index.to_string(),
+            };
+            Expression::FieldAccess {
+                object: Box::new(convert_expression(&expr_field.base)),
+                field,
             }
         }
-        syn::Expr::Index(_expr_index) => {
-            // Array/slice indexing
-            Expression::Literal(Literal::String("index_placeholder".to_string()))
+        syn::Expr::Index(expr_index) => Expression::Index {
+            object: Box::new(convert_expression(&expr_index.expr)),
+            index: Box::new(convert_expression(&expr_index.index)),
+        },
+        syn::Expr::Struct(expr_struct) => Expression::Struct {
+            name: path_to_string(&expr_struct.path),
+            // Shorthand `Point { x }` parses with `x` as both member and expression
+            fields: expr_struct
+                .fields
+                .iter()
+                .map(|field_value| {
+                    let name = match &field_value.member {
+                        syn::Member::Named(ident) => ident.to_string(),
+                        syn::Member::Unnamed(index) => index.index.to_string(),
+                    };
+                    (name, convert_expression(&field_value.expr))
+                })
+                .collect(),
+        },
+        syn::Expr::Array(expr_array) => {
+            Expression::Array(expr_array.elems.iter().map(convert_expression).collect())
+        }
+        syn::Expr::Tuple(expr_tuple) => {
+            Expression::Tuple(expr_tuple.elems.iter().map(convert_expression).collect())
         }
-        syn::Expr::Unary(_expr_unary) => {
-            // Unary operations like !x, -x, *x, &x
-            Expression::Literal(Literal::String("unary_placeholder".to_string()))
+        syn::Expr::Repeat(expr_repeat) => Expression::Repeat {
+            value: Box::new(convert_expression(&expr_repeat.expr)),
+            count: Box::new(convert_expression(&expr_repeat.len)),
+        },
+        syn::Expr::Macro(expr_macro) => convert_macro_expression(&expr_macro.mac),
+        syn::Expr::Match(expr_match) => {
+            // A value-producing match is a block whose tail is the match itself
+            Expression::Block(convert_match_statement(expr_match).into_iter().collect())
         }
+        syn::Expr::Range(expr_range) => Expression::Range {
+            start: expr_range
+                .start
+                .as_ref()
+                .map(|start| Box::new(convert_expression(start))),
+            end: expr_range
+                .end
+                .as_ref()
+                .map(|end| Box::new(convert_expression(end))),
+            inclusive: matches!(expr_range.limits, syn::RangeLimits::Closed(_)),
+        },
+        syn::Expr::Reference(expr_ref) => Expression::Reference {
+            mutable: expr_ref.mutability.is_some(),
+            expr: Box::new(convert_expression(&expr_ref.expr)),
+        },
+        syn::Expr::Closure(expr_closure) => Expression::Closure {
+            params: expr_closure
+                .inputs
+                .iter()
+                .filter_map(convert_closure_parameter)
+                .collect(),
+            body: Box::new(convert_expression(&expr_closure.body)),
+        },
+        syn::Expr::Try(expr_try) => Expression::Try(Box::new(convert_expression(&expr_try.expr))),
+        // Grouping is captured by the tree shape; codegen re-adds the parentheses Mojo needs
+        syn::Expr::Paren(expr_paren) => convert_expression(&expr_paren.expr),
+        syn::Expr::Group(expr_group) => convert_expression(&expr_group.expr),
+        syn::Expr::Unary(expr_unary) => match convert_unary_operator(&expr_unary.op) {
+            Some(op) => Expression::Unary {
+                op,
+                operand: Box::new(convert_expression(&expr_unary.expr)),
+            },
+            None => unsupported_expression(expr),
+        },
         _ => {
             // Fallback for unsupported expressions
-            Expression::Literal(Literal::String("unsupported_expr".to_string()))
+            unsupported_expression(expr)
         }
     }
 }
 
+fn unsupported_expression(expr: &syn::Expr) -> Expression {
+    unsupported(
+        format!("expression `{}`", quote::quote!(#expr)),
+        expr.span(),
+    )
+}
+
+/// Placeholder for a construct that could not be converted, located by its span
+fn unsupported(construct: String, span: proc_macro2::Span) -> Expression {
+    Expression::Unsupported {
+        construct,
+        location: SourceLocation::from_span(span),
+    }
+}
+
 fn convert_literal_expression(expr_lit: &syn::ExprLit) -> Expression {
     match &expr_lit.lit {
         syn::Lit::Str(lit_str) => Expression::Literal(Literal::String(lit_str.value())),
-        syn::Lit::Int(lit_int) => {
-            if let Ok(value) = lit_int.base10_parse::<i64>() {
+        syn::Lit::Int(lit_int) => match lit_int.base10_parse::<i64>() {
+            Ok(value) if lit_int.suffix().is_empty() => {
                 Expression::Literal(Literal::Integer(value))
-            } else {
-                Expression::Literal(Literal::Integer(0))
             }
-        }
+            _ => Expression::Literal(Literal::TypedInteger {
+                digits: lit_int.base10_digits().to_string(),
+                suffix: Some(lit_int.suffix())
+                    .filter(|suffix| !suffix.is_empty())
+                    .map(str::to_string),
+            }),
+        },
         syn::Lit::Float(lit_float) => {
             if let Ok(value) = lit_float.base10_parse::<f64>() {
                 Expression::Literal(Literal::Float(value))
@@ -510,12 +1108,216 @@ fn convert_literal_expression(expr_lit: &syn::ExprLit) -> Expression {
         }
         syn::Lit::Bool(lit_bool) => Expression::Literal(Literal::Boolean(lit_bool.value)),
         syn::Lit::Char(lit_char) => Expression::Literal(Literal::Char(lit_char.value())),
-        _ => Expression::Literal(Literal::String("unsupported_literal".to_string())),
+        lit => unsupported(format!("literal `{}`", quote::quote!(#lit)), lit.span()),
+    }
+}
+
+fn convert_macro_statement(mac: &syn::Macro) -> Statement {
+    let name = mac
+        .path
+        .segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+        .unwrap_or_default();
+    match name.as_str() {
+        "panic" | "unreachable" | "todo" | "unimplemented" => {
+            let default_message = match name.as_str() {
+                "panic" => "explicit panic",
+                "unreachable" => "internal error: entered unreachable code",
+                "todo" => "not yet implemented",
+                _ => "not implemented",
+            };
+            let message = convert_format_macro_args(mac).unwrap_or_else(|| {
+                Expression::Literal(Literal::String(default_message.to_string()))
+            });
+            Statement::Abort(message)
+        }
+        // Anything `convert_macro_expression` cannot lower, such as `assert!`,
+        // is kept as a placeholder so strict mode rejects it
+        _ => Statement::Expression(convert_macro_expression(mac)),
+    }
+}
+
+fn convert_macro_expression(mac: &syn::Macro) -> Expression {
+    let name = mac
+        .path
+        .segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+        .unwrap_or_default();
+    match name.as_str() {
+        "format" => convert_format_macro_args(mac)
+            .unwrap_or_else(|| Expression::Literal(Literal::String(String::new()))),
+        // `vec![a, b]` and `vec![x; n]` have the same shape as array expressions
+        "vec" => {
+            let tokens = &mac.tokens;
+            match syn::parse2::<syn::Expr>(quote::quote!([#tokens])) {
+                Ok(array @ (syn::Expr::Array(_) | syn::Expr::Repeat(_))) => {
+                    convert_expression(&array)
+                }
+                _ => unsupported(format!("macro `{}!`", name), mac.span()),
+            }
+        }
+        // Mojo's `__str__` returns the string instead of writing it to a
+        // formatter, so the formatter argument is dropped
+        "write" | "writeln" => {
+            let newline = if name == "writeln" { "\n" } else { "" };
+            macro_args(mac)
+                .and_then(|args| convert_format_args(args.into_iter().skip(1), newline))
+                .unwrap_or_else(|| Expression::Literal(Literal::String(newline.to_string())))
+        }
+        "println" | "print" | "eprintln" | "eprint" => {
+            let mut args: Vec<Expression> = convert_format_macro_args(mac).into_iter().collect();
+            // Keyword arguments are encoded as `name = value` assignments
+            let keyword = |name: &str, value: Expression| Expression::Binary {
+                left: Box::new(Expression::Identifier(name.to_string())),
+                op: BinaryOp::Assign,
+                right: Box::new(value),
+            };
+            if !name.ends_with("ln") {
+                args.push(keyword(
+                    "end",
+                    Expression::Literal(Literal::String(String::new())),
+                ));
+            }
+            if name.starts_with('e') {
+                args.push(keyword("file", Expression::Literal(Literal::Integer(2))));
+            }
+            Expression::Call {
+                function: Box::new(Expression::Identifier("print".to_string())),
+                args,
+            }
+        }
+        _ => unsupported(format!("macro `{}!`", name), mac.span()),
+    }
+}
+
+/// The comma-separated expressions a macro is invoked with, if its body parses as such
+fn macro_args(mac: &syn::Macro) -> Option<syn::punctuated::Punctuated<syn::Expr, syn::Token![,]>> {
+    mac.parse_body_with(syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated)
+        .ok()
+}
+
+/// Lower the arguments of a formatting macro (`format!`, `panic!`, ...) into a
+/// string-building expression. Returns `None` when the macro has no arguments.
+fn convert_format_macro_args(mac: &syn::Macro) -> Option<Expression> {
+    convert_format_args(macro_args(mac)?.into_iter(), "")
+}
+
+/// Lower a format string and its arguments, appending `suffix` to the format string
+fn convert_format_args(
+    mut args: impl Iterator<Item = syn::Expr>,
+    suffix: &str,
+) -> Option<Expression> {
+    let format = args.next()?;
+
+    let format_str = match &format {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit_str),
+            ..
+        }) => lit_str.value() + suffix,
+        // Non-literal message (2015-style `panic!(msg)`), use it verbatim
+        other => return Some(convert_expression(other)),
+    };
+
+    let mut positional = Vec::new();
+    let mut named = Vec::new();
+    for arg in args {
+        match arg {
+            syn::Expr::Assign(assign) => {
+                if let syn::Expr::Path(path) = &*assign.left {
+                    if let Some(ident) = path.path.get_ident() {
+                        named.push((ident.to_string(), convert_expression(&assign.right)));
+                        continue;
+                    }
+                }
+                positional.push(convert_expression(&syn::Expr::Assign(assign)));
+            }
+            other => positional.push(convert_expression(&other)),
+        }
     }
+
+    Some(lower_format_string(&format_str, &positional, &named))
+}
+
+/// Lower a Rust format string into a chain of Mojo string concatenations,
+/// converting each interpolated argument with `String(...)`.
+fn lower_format_string(
+    format_str: &str,
+    positional: &[Expression],
+    named: &[(String, Expression)],
+) -> Expression {
+    let mut pieces: Vec<Expression> = Vec::new();
+    let mut text = String::new();
+    let mut next_positional = 0;
+    let mut chars = format_str.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut spec = String::new();
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    spec.push(c);
+                }
+                // Formatting options after `:` have no direct Mojo equivalent
+                let selector = spec.split(':').next().unwrap_or("").trim();
+
+                let arg = if selector.is_empty() {
+                    let arg = positional.get(next_positional).cloned();
+                    next_positional += 1;
+                    arg
+                } else if let Ok(index) = selector.parse::<usize>() {
+                    positional.get(index).cloned()
+                } else {
+                    named
+                        .iter()
+                        .find(|(name, _)| name == selector)
+                        .map(|(_, expr)| expr.clone())
+                        .or_else(|| Some(Expression::Identifier(selector.to_string())))
+                };
+
+                if !text.is_empty() {
+                    pieces.push(Expression::Literal(Literal::String(std::mem::take(
+                        &mut text,
+                    ))));
+                }
+                if let Some(arg) = arg {
+                    pieces.push(Expression::Call {
+                        function: Box::new(Expression::Identifier("String".to_string())),
+                        args: vec![arg],
+                    });
+                }
+            }
+            _ => text.push(c),
+        }
+    }
+    if !text.is_empty() || pieces.is_empty() {
+        pieces.push(Expression::Literal(Literal::String(text)));
+    }
+
+    let mut pieces = pieces.into_iter();
+    let first = pieces
+        .next()
+        .unwrap_or(Expression::Literal(Literal::String(String::new())));
+    pieces.fold(first, |left, right| Expression::Binary {
+        left: Box::new(left),
+        op: BinaryOp::Add,
+        right: Box::new(right),
+    })
 }
 
 fn convert_if_statement(expr_if: &syn::ExprIf) -> Option<Statement> {
-    let condition = convert_expression(&expr_if.cond);
     let then_branch = convert_block(&expr_if.then_branch);
 
     let else_branch = if let Some((_, else_expr)) = &expr_if.else_branch {
@@ -531,8 +1333,29 @@ fn convert_if_statement(expr_if: &syn::ExprIf) -> Option<Statement> {
         None
     };
 
+    // `if let P = e { .. } else { .. }` is a two-arm match; an `else if let`
+    // chain nests inside the fallback arm so each binding keeps its own scope
+    if let syn::Expr::Let(expr_let) = &*expr_if.cond {
+        let mut arms = vec![MatchArm {
+            pattern: convert_pattern(&expr_let.pat),
+            guard: None,
+            body: then_branch,
+        }];
+        if let Some(else_branch) = else_branch {
+            arms.push(MatchArm {
+                pattern: Pattern::Wildcard,
+                guard: None,
+                body: else_branch,
+            });
+        }
+        return Some(Statement::Match {
+            expr: convert_expression(&expr_let.expr),
+            arms,
+        });
+    }
+
     Some(Statement::If {
-        condition,
+        condition: convert_expression(&expr_if.cond),
         then_branch,
         else_branch,
     })
@@ -541,29 +1364,167 @@ fn convert_if_statement(expr_if: &syn::ExprIf) -> Option<Statement> {
 fn convert_while_statement(expr_while: &syn::ExprWhile) -> Option<Statement> {
     let condition = convert_expression(&expr_while.cond);
     let body = convert_block(&expr_while.body);
+    let label = loop_label(expr_while.label.as_ref().map(|label| &label.name));
 
-    Some(Statement::While { condition, body })
+    Some(Statement::While {
+        condition,
+        body,
+        label,
+    })
 }
 
 fn convert_for_statement(expr_for: &syn::ExprForLoop) -> Option<Statement> {
-    // Extract pattern (usually just an identifier)
-    let pattern = match &*expr_for.pat {
-        syn::Pat::Ident(pat_ident) => pat_ident.ident.to_string(),
-        _ => "item".to_string(), // Fallback for complex patterns
-    };
+    let pattern = convert_pattern(&expr_for.pat);
 
     let iterator = convert_expression(&expr_for.expr);
     let body = convert_block(&expr_for.body);
+    let label = loop_label(expr_for.label.as_ref().map(|label| &label.name));
 
     Some(Statement::For {
         pattern,
         iterator,
         body,
+        label,
     })
 }
 
-fn convert_binary_operator(op: &syn::BinOp) -> BinaryOp {
+fn convert_match_statement(expr_match: &syn::ExprMatch) -> Option<Statement> {
+    let expr = convert_expression(&expr_match.expr);
+    let arms = expr_match.arms.iter().map(convert_match_arm).collect();
+
+    Some(Statement::Match { expr, arms })
+}
+
+fn convert_match_arm(arm: &syn::Arm) -> MatchArm {
+    let body = match &*arm.body {
+        syn::Expr::Block(expr_block) => convert_block(&expr_block.block),
+        // Single-expression arms go through statement conversion so that
+        // control flow like `return` or `panic!` keeps its meaning
+        expr => convert_statement(&syn::Stmt::Expr(expr.clone(), None))
+            .into_iter()
+            .collect(),
+    };
+
+    MatchArm {
+        pattern: convert_pattern(&arm.pat),
+        guard: arm
+            .guard
+            .as_ref()
+            .map(|(_, guard)| convert_expression(guard)),
+        body,
+    }
+}
+
+fn convert_pattern(pat: &syn::Pat) -> Pattern {
+    match pat {
+        syn::Pat::Wild(_) => Pattern::Wildcard,
+        syn::Pat::Ident(pat_ident) => {
+            let name = pat_ident.ident.to_string();
+            // Bare capitalized identifiers like `None` name unit variants, not bindings
+            if pat_ident.subpat.is_none()
+                && pat_ident.mutability.is_none()
+                && name.starts_with(|c: char| c.is_uppercase())
+            {
+                Pattern::Enum {
+                    path: String::new(),
+                    variant: name,
+                    fields: Vec::new(),
+                }
+            } else {
+                Pattern::Identifier(name)
+            }
+        }
+        syn::Pat::Lit(pat_lit) => match convert_literal_expression(pat_lit) {
+            Expression::Literal(literal) => Pattern::Literal(literal),
+            _ => Pattern::Wildcard,
+        },
+        syn::Pat::Tuple(pat_tuple) => {
+            Pattern::Tuple(pat_tuple.elems.iter().map(convert_pattern).collect())
+        }
+        syn::Pat::TupleStruct(pat_tuple_struct) => {
+            let (path, variant) = split_variant_path(&pat_tuple_struct.path);
+            Pattern::Enum {
+                path,
+                variant,
+                fields: pat_tuple_struct.elems.iter().map(convert_pattern).collect(),
+            }
+        }
+        syn::Pat::Path(pat_path) => {
+            let (path, variant) = split_variant_path(&pat_path.path);
+            Pattern::Enum {
+                path,
+                variant,
+                fields: Vec::new(),
+            }
+        }
+        syn::Pat::Struct(pat_struct) => Pattern::Struct {
+            name: path_to_string(&pat_struct.path),
+            fields: pat_struct
+                .fields
+                .iter()
+                .map(|field| {
+                    let name = match &field.member {
+                        syn::Member::Named(ident) => ident.to_string(),
+                        syn::Member::Unnamed(index) => index.index.to_string(),
+                    };
+                    (name, convert_pattern(&field.pat))
+                })
+                .collect(),
+        },
+        syn::Pat::Or(pat_or) => Pattern::Or(pat_or.cases.iter().map(convert_pattern).collect()),
+        syn::Pat::Range(pat_range) => {
+            let bound = |expr: &Option<Box<syn::Expr>>| match expr.as_deref() {
+                Some(syn::Expr::Lit(expr_lit)) => match convert_literal_expression(expr_lit) {
+                    Expression::Literal(literal) => Some(literal),
+                    _ => None,
+                },
+                _ => None,
+            };
+            Pattern::Range {
+                start: bound(&pat_range.start),
+                end: bound(&pat_range.end),
+                inclusive: matches!(pat_range.limits, syn::RangeLimits::Closed(_)),
+            }
+        }
+        syn::Pat::Paren(pat_paren) => convert_pattern(&pat_paren.pat),
+        syn::Pat::Reference(pat_ref) => convert_pattern(&pat_ref.pat),
+        _ => {
+            // Unsupported patterns match anything for now
+            Pattern::Wildcard
+        }
+    }
+}
+
+/// Split `Enum::Variant` into its enum path and variant name
+fn split_variant_path(path: &syn::Path) -> (String, String) {
+    let mut segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    let variant = segments.pop().unwrap_or_default();
+    (segments.join("::"), variant)
+}
+
+fn path_to_string(path: &syn::Path) -> String {
+    path.segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn convert_unary_operator(op: &syn::UnOp) -> Option<UnaryOp> {
     match op {
+        syn::UnOp::Neg(_) => Some(UnaryOp::Neg),
+        syn::UnOp::Not(_) => Some(UnaryOp::Not),
+        syn::UnOp::Deref(_) => Some(UnaryOp::Deref),
+        _ => None,
+    }
+}
+
+/// Map a binary operator, or `None` for one we don't handle yet
+///
+/// `syn::BinOp` is non-exhaustive, so new operators must not fall through to a
+/// default that would silently change the program's meaning.
+fn convert_binary_operator(op: &syn::BinOp) -> Option<BinaryOp> {
+    let op = match op {
         syn::BinOp::Add(_) => BinaryOp::Add,
         syn::BinOp::Sub(_) => BinaryOp::Sub,
         syn::BinOp::Mul(_) => BinaryOp::Mul,
@@ -582,6 +1543,17 @@ fn convert_binary_operator(op: &syn::BinOp) -> BinaryOp {
         syn::BinOp::Ne(_) => BinaryOp::Ne,
         syn::BinOp::Ge(_) => BinaryOp::Ge,
         syn::BinOp::Gt(_) => BinaryOp::Gt,
-        _ => BinaryOp::Add, // Fallback
-    }
+        syn::BinOp::AddAssign(_) => BinaryOp::AddAssign,
+        syn::BinOp::SubAssign(_) => BinaryOp::SubAssign,
+        syn::BinOp::MulAssign(_) => BinaryOp::MulAssign,
+        syn::BinOp::DivAssign(_) => BinaryOp::DivAssign,
+        syn::BinOp::RemAssign(_) => BinaryOp::ModAssign,
+        syn::BinOp::BitAndAssign(_) => BinaryOp::BitAndAssign,
+        syn::BinOp::BitOrAssign(_) => BinaryOp::BitOrAssign,
+        syn::BinOp::BitXorAssign(_) => BinaryOp::BitXorAssign,
+        syn::BinOp::ShlAssign(_) => BinaryOp::ShlAssign,
+        syn::BinOp::ShrAssign(_) => BinaryOp::ShrAssign,
+        _ => return None,
+    };
+    Some(op)
 }
@@ -0,0 +1,626 @@
+//! Human-readable rendering of the intermediate AST
+//!
+//! The JSON from `--emit ast` is exact but verbose. This renders the same tree as
+//! indented, Rust-like lines: one line per item, member or statement, with nested
+//! bodies indented beneath. Attributes are left out. Binary and unary
+//! sub-expressions are always parenthesized so the grouping the parser produced
+//! is visible.
+
+use crate::ast::*;
+use std::fmt;
+
+const INDENT: &str = "  ";
+
+impl fmt::Display for CompilationUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut printer = Printer::default();
+        printer.items(&self.items);
+        f.write_str(&printer.out)
+    }
+}
+
+#[derive(Default)]
+struct Printer {
+    out: String,
+    depth: usize,
+}
+
+impl Printer {
+    fn line(&mut self, text: impl AsRef<str>) {
+        for _ in 0..self.depth {
+            self.out.push_str(INDENT);
+        }
+        self.out.push_str(text.as_ref());
+        self.out.push('\n');
+    }
+
+    /// Print a header line with `body` indented beneath it
+    fn nested(&mut self, header: impl AsRef<str>, body: impl FnOnce(&mut Self)) {
+        self.line(header);
+        self.depth += 1;
+        body(self);
+        self.depth -= 1;
+    }
+
+    fn items(&mut self, items: &[Item]) {
+        for item in items {
+            self.item(item);
+        }
+    }
+
+    fn item(&mut self, item: &Item) {
+        match item {
+            Item::Function(func) => self.function(func),
+            Item::Struct(struct_item) => self.nested(
+                format!(
+                    "{}struct {}{}",
+                    visibility(&struct_item.visibility),
+                    struct_item.name,
+                    generics(&struct_item.generics)
+                ),
+                |p| p.fields(&struct_item.fields),
+            ),
+            Item::Enum(enum_item) => self.nested(
+                format!(
+                    "{}enum {}{}",
+                    visibility(&enum_item.visibility),
+                    enum_item.name,
+                    generics(&enum_item.generics)
+                ),
+                |p| {
+                    for variant in &enum_item.variants {
+                        match &variant.data {
+                            VariantData::Unit => p.line(&variant.name),
+                            VariantData::Tuple(types) => {
+                                p.line(format!("{}({})", variant.name, list(types, type_str)))
+                            }
+                            VariantData::Struct(fields) => {
+                                p.nested(&variant.name, |p| p.fields(fields))
+                            }
+                        }
+                    }
+                },
+            ),
+            Item::Impl(impl_item) => {
+                let header = match &impl_item.trait_ {
+                    Some(trait_) => format!(
+                        "impl{} {} for {}",
+                        generics(&impl_item.generics),
+                        type_str(trait_),
+                        type_str(&impl_item.target_type)
+                    ),
+                    None => format!(
+                        "impl{} {}",
+                        generics(&impl_item.generics),
+                        type_str(&impl_item.target_type)
+                    ),
+                };
+                self.nested(header, |p| {
+                    for item in &impl_item.items {
+                        match item {
+                            ImplItem::Function(func) => p.function(func),
+                            ImplItem::Const(const_item) => p.const_(const_item),
+                            ImplItem::Type(alias) => p.type_alias(alias),
+                        }
+                    }
+                });
+            }
+            Item::Use(use_item) => self.line(format!(
+                "{}use {}",
+                visibility(&use_item.visibility),
+                list(&use_item.imports, use_path)
+            )),
+            Item::Mod(module) => self.nested(
+                format!("{}mod {}", visibility(&module.visibility), module.name),
+                |p| p.items(&module.items),
+            ),
+            Item::Const(const_item) => self.const_(const_item),
+            Item::Static(static_item) => self.line(format!(
+                "{}static {}{}: {} = {}",
+                visibility(&static_item.visibility),
+                if static_item.mutable { "mut " } else { "" },
+                static_item.name,
+                type_str(&static_item.type_),
+                expr_str(&static_item.value)
+            )),
+            Item::Type(alias) => self.type_alias(alias),
+            Item::Trait(trait_item) => self.nested(
+                format!(
+                    "{}trait {}{}",
+                    visibility(&trait_item.visibility),
+                    trait_item.name,
+                    generics(&trait_item.generics)
+                ),
+                |p| {
+                    for item in &trait_item.items {
+                        match item {
+                            TraitItem::Function(func) => {
+                                let header = signature(
+                                    "",
+                                    &func.name,
+                                    &generics(&func.generics),
+                                    &func.parameters,
+                                    &func.return_type,
+                                );
+                                match &func.default_body {
+                                    Some(body) => p.nested(header, |p| p.statements(body)),
+                                    None => p.line(header),
+                                }
+                            }
+                            TraitItem::Type { name, bounds } if bounds.is_empty() => {
+                                p.line(format!("type {}", name))
+                            }
+                            TraitItem::Type { name, bounds } => p.line(format!(
+                                "type {}: {}",
+                                name,
+                                bounds.iter().map(type_str).collect::<Vec<_>>().join(" + ")
+                            )),
+                        }
+                    }
+                },
+            ),
+        }
+    }
+
+    fn function(&mut self, func: &Function) {
+        let lifetimes = func
+            .lifetimes
+            .iter()
+            .map(|lifetime| format!("'{}", lifetime));
+        let type_params = func.generics.iter().map(generic_str);
+        let params: Vec<String> = lifetimes.chain(type_params).collect();
+        let generics = if params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", params.join(", "))
+        };
+        let header = signature(
+            &visibility(&func.visibility),
+            &func.name,
+            &generics,
+            &func.parameters,
+            &func.return_type,
+        );
+        self.nested(header, |p| p.statements(&func.body));
+    }
+
+    fn fields(&mut self, fields: &[Field]) {
+        for field in fields {
+            self.line(format!(
+                "{}{}: {}",
+                visibility(&field.visibility),
+                field.name,
+                type_str(&field.type_)
+            ));
+        }
+    }
+
+    fn const_(&mut self, const_item: &Const) {
+        self.line(format!(
+            "{}const {}: {} = {}",
+            visibility(&const_item.visibility),
+            const_item.name,
+            type_str(&const_item.type_),
+            expr_str(&const_item.value)
+        ));
+    }
+
+    fn type_alias(&mut self, alias: &TypeAlias) {
+        self.line(format!(
+            "{}type {}{} = {}",
+            visibility(&alias.visibility),
+            alias.name,
+            generics(&alias.generics),
+            type_str(&alias.type_)
+        ));
+    }
+
+    fn statements(&mut self, stmts: &[Statement]) {
+        for stmt in stmts {
+            self.statement(stmt);
+        }
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Expression(expr) => self.line(expr_str(expr)),
+            Statement::Let {
+                name,
+                mutable,
+                type_,
+                value,
+            } => {
+                let mut text = format!("let {}{}", if *mutable { "mut " } else { "" }, name);
+                if let Some(ty) = type_ {
+                    text += &format!(": {}", type_str(ty));
+                }
+                if let Some(value) = value {
+                    text += &format!(" = {}", expr_str(value));
+                }
+                self.line(text);
+            }
+            Statement::LetPattern {
+                pattern,
+                type_,
+                value,
+            } => {
+                let mut text = format!("let {}", pattern_str(pattern));
+                if let Some(ty) = type_ {
+                    text += &format!(": {}", type_str(ty));
+                }
+                self.line(format!("{} = {}", text, expr_str(value)));
+            }
+            Statement::Return(None) => self.line("return"),
+            Statement::Return(Some(expr)) => self.line(format!("return {}", expr_str(expr))),
+            Statement::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.nested(format!("if {}", expr_str(condition)), |p| {
+                    p.statements(then_branch)
+                });
+                if let Some(else_branch) = else_branch {
+                    self.nested("else", |p| p.statements(else_branch));
+                }
+            }
+            Statement::While {
+                condition,
+                body,
+                label,
+            } => self.nested(
+                format!("{}while {}", label_str(label), expr_str(condition)),
+                |p| p.statements(body),
+            ),
+            Statement::Loop { body, label } => {
+                self.nested(format!("{}loop", label_str(label)), |p| p.statements(body))
+            }
+            Statement::For {
+                pattern,
+                iterator,
+                body,
+                label,
+            } => self.nested(
+                format!(
+                    "{}for {} in {}",
+                    label_str(label),
+                    pattern_str(pattern),
+                    expr_str(iterator)
+                ),
+                |p| p.statements(body),
+            ),
+            Statement::Match { expr, arms } => {
+                self.nested(format!("match {}", expr_str(expr)), |p| {
+                    for arm in arms {
+                        let guard = arm
+                            .guard
+                            .as_ref()
+                            .map(|guard| format!(" if {}", expr_str(guard)))
+                            .unwrap_or_default();
+                        p.nested(format!("{}{} =>", pattern_str(&arm.pattern), guard), |p| {
+                            p.statements(&arm.body)
+                        });
+                    }
+                })
+            }
+            Statement::Block(stmts) => self.nested("block", |p| p.statements(stmts)),
+            Statement::Raise(expr) => self.line(format!("raise {}", expr_str(expr))),
+            Statement::Abort(expr) => self.line(format!("abort {}", expr_str(expr))),
+            Statement::Break(label) => self.line(loop_exit("break", label)),
+            Statement::Continue(label) => self.line(loop_exit("continue", label)),
+        }
+    }
+}
+
+fn visibility(vis: &Visibility) -> String {
+    match vis {
+        Visibility::Public => "pub ".to_string(),
+        Visibility::Private => String::new(),
+        Visibility::Crate => "pub(crate) ".to_string(),
+        Visibility::Super => "pub(super) ".to_string(),
+        Visibility::InPath(path) => format!("pub(in {}) ", path),
+    }
+}
+
+/// An import as it would be written in Rust, e.g. `std::fmt::Write as _`
+pub(crate) fn use_path(import: &UseImport) -> String {
+    let mut path = import.module.join("::");
+    if !path.is_empty() {
+        path.push_str("::");
+    }
+    path.push_str(&import.name);
+    if let Some(alias) = &import.alias {
+        path.push_str(&format!(" as {}", alias));
+    }
+    path
+}
+
+fn signature(
+    visibility: &str,
+    name: &str,
+    generics: &str,
+    parameters: &[Parameter],
+    return_type: &Option<Type>,
+) -> String {
+    let params = list(parameters, |param| {
+        format!("{}: {}", param.name, type_str(&param.type_))
+    });
+    let return_type = return_type
+        .as_ref()
+        .map(|ty| format!(" -> {}", type_str(ty)))
+        .unwrap_or_default();
+    format!(
+        "{}fn {}{}({}){}",
+        visibility, name, generics, params, return_type
+    )
+}
+
+fn generics(generics: &[Generic]) -> String {
+    if generics.is_empty() {
+        return String::new();
+    }
+    format!("<{}>", list(generics, generic_str))
+}
+
+fn generic_str(generic: &Generic) -> String {
+    if generic.bounds.is_empty() {
+        generic.name.clone()
+    } else {
+        let bounds: Vec<String> = generic.bounds.iter().map(type_str).collect();
+        format!("{}: {}", generic.name, bounds.join(" + "))
+    }
+}
+
+fn label_str(label: &Option<String>) -> String {
+    label
+        .as_ref()
+        .map(|label| format!("'{}: ", label))
+        .unwrap_or_default()
+}
+
+fn loop_exit(keyword: &str, label: &Option<String>) -> String {
+    match label {
+        Some(label) => format!("{} '{}", keyword, label),
+        None => keyword.to_string(),
+    }
+}
+
+fn list<T>(items: &[T], render: impl Fn(&T) -> String) -> String {
+    items.iter().map(render).collect::<Vec<_>>().join(", ")
+}
+
+fn type_str(ty: &Type) -> String {
+    match ty {
+        Type::Path(path) => path.clone(),
+        Type::Reference {
+            mutable,
+            lifetime,
+            inner,
+        } => format!(
+            "&{}{}{}",
+            lifetime
+                .as_ref()
+                .map(|lifetime| format!("'{} ", lifetime))
+                .unwrap_or_default(),
+            if *mutable { "mut " } else { "" },
+            type_str(inner)
+        ),
+        Type::Pointer { mutable, inner } => format!(
+            "*{} {}",
+            if *mutable { "mut" } else { "const" },
+            type_str(inner)
+        ),
+        Type::Array { inner, size } => match size {
+            Some(size) => format!("[{}; {}]", type_str(inner), size),
+            None => format!("[{}; _]", type_str(inner)),
+        },
+        Type::Slice(inner) => format!("[{}]", type_str(inner)),
+        Type::Tuple(types) => format!("({})", list(types, type_str)),
+        Type::Function { params, return_ } => {
+            format!("fn({}) -> {}", list(params, type_str), type_str(return_))
+        }
+        Type::Generic { name, args } => format!("{}<{}>", name, list(args, type_str)),
+        Type::Unit => "()".to_string(),
+        Type::Never => "!".to_string(),
+    }
+}
+
+fn literal_str(literal: &Literal) -> String {
+    match literal {
+        Literal::String(text) => format!("{:?}", text),
+        Literal::Integer(value) => value.to_string(),
+        Literal::TypedInteger { digits, suffix } => {
+            format!("{}{}", digits, suffix.as_deref().unwrap_or_default())
+        }
+        Literal::Float(value) => format!("{:?}", value),
+        Literal::Boolean(value) => value.to_string(),
+        Literal::Char(c) => format!("{:?}", c),
+    }
+}
+
+/// Render an operand, parenthesizing anything built from operators
+fn operand_str(expr: &Expression) -> String {
+    match expr {
+        Expression::Binary { .. }
+        | Expression::Unary { .. }
+        | Expression::Cast { .. }
+        | Expression::Range { .. } => format!("({})", expr_str(expr)),
+        _ => expr_str(expr),
+    }
+}
+
+fn expr_str(expr: &Expression) -> String {
+    match expr {
+        Expression::Literal(literal) => literal_str(literal),
+        Expression::Identifier(name) | Expression::Path(name) => name.clone(),
+        Expression::Call { function, args } => {
+            format!("{}({})", operand_str(function), list(args, expr_str))
+        }
+        Expression::MethodCall {
+            receiver,
+            method,
+            turbofish,
+            args,
+        } => {
+            let turbofish = if turbofish.is_empty() {
+                String::new()
+            } else {
+                format!("::<{}>", list(turbofish, type_str))
+            };
+            format!(
+                "{}.{}{}({})",
+                operand_str(receiver),
+                method,
+                turbofish,
+                list(args, expr_str)
+            )
+        }
+        Expression::FieldAccess { object, field } => format!("{}.{}", operand_str(object), field),
+        Expression::Index { object, index } => {
+            format!("{}[{}]", operand_str(object), expr_str(index))
+        }
+        Expression::Binary { left, op, right } => format!(
+            "{} {} {}",
+            operand_str(left),
+            binary_op_str(op),
+            operand_str(right)
+        ),
+        Expression::Unary { op, operand } => {
+            let op = match op {
+                UnaryOp::Not => "!",
+                UnaryOp::Neg => "-",
+                UnaryOp::Deref => "*",
+            };
+            format!("{}{}", op, operand_str(operand))
+        }
+        Expression::Cast { expr, type_ } => format!("{} as {}", operand_str(expr), type_str(type_)),
+        Expression::Reference { mutable, expr } => format!(
+            "&{}{}",
+            if *mutable { "mut " } else { "" },
+            operand_str(expr)
+        ),
+        Expression::Dereference(expr) => format!("*{}", operand_str(expr)),
+        Expression::Block(stmts) => format!("{{ {} statement(s) }}", stmts.len()),
+        Expression::Array(elements) => format!("[{}]", list(elements, expr_str)),
+        Expression::Repeat { value, count } => {
+            format!("[{}; {}]", expr_str(value), expr_str(count))
+        }
+        Expression::Tuple(elements) if elements.len() == 1 => {
+            format!("({},)", expr_str(&elements[0]))
+        }
+        Expression::Tuple(elements) => format!("({})", list(elements, expr_str)),
+        Expression::Range {
+            start,
+            end,
+            inclusive,
+        } => format!(
+            "{}{}{}",
+            start.as_deref().map(operand_str).unwrap_or_default(),
+            if *inclusive { "..=" } else { ".." },
+            end.as_deref().map(operand_str).unwrap_or_default()
+        ),
+        Expression::Struct { name, fields } => format!(
+            "{} {{ {} }}",
+            name,
+            list(fields, |(field, value)| format!(
+                "{}: {}",
+                field,
+                expr_str(value)
+            ))
+        ),
+        Expression::Try(expr) => format!("{}?", operand_str(expr)),
+        Expression::Closure { params, body } => format!(
+            "|{}| {}",
+            list(params, |param| format!(
+                "{}: {}",
+                param.name,
+                type_str(&param.type_)
+            )),
+            expr_str(body)
+        ),
+        Expression::Unsupported { construct, .. } => format!("<unsupported {}>", construct),
+    }
+}
+
+fn binary_op_str(op: &BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Add => "+",
+        BinaryOp::Sub => "-",
+        BinaryOp::Mul => "*",
+        BinaryOp::Div => "/",
+        BinaryOp::Mod => "%",
+        BinaryOp::Eq => "==",
+        BinaryOp::Ne => "!=",
+        BinaryOp::Lt => "<",
+        BinaryOp::Le => "<=",
+        BinaryOp::Gt => ">",
+        BinaryOp::Ge => ">=",
+        BinaryOp::And => "&&",
+        BinaryOp::Or => "||",
+        BinaryOp::BitAnd => "&",
+        BinaryOp::BitOr => "|",
+        BinaryOp::BitXor => "^",
+        BinaryOp::Shl => "<<",
+        BinaryOp::Shr => ">>",
+        BinaryOp::Assign => "=",
+        BinaryOp::AddAssign => "+=",
+        BinaryOp::SubAssign => "-=",
+        BinaryOp::MulAssign => "*=",
+        BinaryOp::DivAssign => "/=",
+        BinaryOp::ModAssign => "%=",
+        BinaryOp::BitAndAssign => "&=",
+        BinaryOp::BitOrAssign => "|=",
+        BinaryOp::BitXorAssign => "^=",
+        BinaryOp::ShlAssign => "<<=",
+        BinaryOp::ShrAssign => ">>=",
+    }
+}
+
+fn pattern_str(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Wildcard => "_".to_string(),
+        Pattern::Identifier(name) => name.clone(),
+        Pattern::Literal(literal) => literal_str(literal),
+        Pattern::Tuple(patterns) => format!("({})", list(patterns, pattern_str)),
+        Pattern::Struct { name, fields } => format!(
+            "{} {{ {} }}",
+            name,
+            list(fields, |(field, pattern)| format!(
+                "{}: {}",
+                field,
+                pattern_str(pattern)
+            ))
+        ),
+        Pattern::Enum {
+            path,
+            variant,
+            fields,
+        } => {
+            let name = if path.is_empty() {
+                variant.clone()
+            } else {
+                format!("{}::{}", path, variant)
+            };
+            if fields.is_empty() {
+                name
+            } else {
+                format!("{}({})", name, list(fields, pattern_str))
+            }
+        }
+        Pattern::Or(patterns) => patterns
+            .iter()
+            .map(pattern_str)
+            .collect::<Vec<_>>()
+            .join(" | "),
+        Pattern::Range {
+            start,
+            end,
+            inclusive,
+        } => format!(
+            "{}{}{}",
+            start.as_ref().map(literal_str).unwrap_or_default(),
+            if *inclusive { "..=" } else { ".." },
+            end.as_ref().map(literal_str).unwrap_or_default()
+        ),
+    }
+}
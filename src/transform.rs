@@ -0,0 +1,513 @@
+//! AST-to-AST transformation passes
+//!
+//! This module contains optional passes that rewrite the intermediate representation
+//! between parsing and code generation.
+
+use crate::ast::*;
+
+/// Inline type aliases that are referenced exactly once and drop their definitions
+///
+/// Aliases with generic parameters, or that are referenced from expression paths
+/// (e.g. `Alias::new()`), are always kept.
+pub fn inline_single_use_aliases(unit: &mut CompilationUnit) {
+    let candidates: Vec<TypeAlias> = unit
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Type(alias) if alias.generics.is_empty() => Some(alias.clone()),
+            _ => None,
+        })
+        .collect();
+
+    for alias in candidates {
+        let mut type_uses = 0;
+        let mut expr_uses = 0;
+        for item in &mut unit.items {
+            walk_item(
+                item,
+                &mut |ty| {
+                    if matches!(ty, Type::Path(path) if *path == alias.name) {
+                        type_uses += 1;
+                    }
+                },
+                &mut |path| {
+                    if *path == alias.name || path.starts_with(&format!("{}::", alias.name)) {
+                        expr_uses += 1;
+                    }
+                },
+            );
+        }
+
+        if type_uses != 1 || expr_uses != 0 {
+            continue;
+        }
+
+        unit.items
+            .retain(|item| !matches!(item, Item::Type(other) if other.name == alias.name));
+        for item in &mut unit.items {
+            walk_item(
+                item,
+                &mut |ty| {
+                    if matches!(ty, Type::Path(path) if *path == alias.name) {
+                        *ty = alias.type_.clone();
+                    }
+                },
+                &mut |_| {},
+            );
+        }
+    }
+}
+
+/// Give items inside modules names prefixed with their module path, so that
+/// identically named items in different modules don't collide once the
+/// modules are flattened into one Mojo file
+///
+/// `mod math { fn add() }` defines `math_add`. References are rewritten the
+/// way Rust resolves them: relative to the module they appear in, or through
+/// `crate`, `self` and `super`. Local variables that shadow an item of their
+/// module are not told apart from it.
+pub fn prefix_module_items(unit: &mut CompilationUnit) {
+    let mut defined = Vec::new();
+    collect_item_paths(&unit.items, &mut Vec::new(), &mut defined);
+    prefix_items(&mut unit.items, &mut Vec::new(), &defined);
+}
+
+/// Full paths of the items (modules included) defined in `items`
+fn collect_item_paths(items: &[Item], module: &mut Vec<String>, out: &mut Vec<Vec<String>>) {
+    for item in items {
+        let Some(name) = item_name(item) else {
+            continue;
+        };
+        module.push(name.to_string());
+        out.push(module.clone());
+        if let Item::Mod(inner) = item {
+            collect_item_paths(&inner.items, module, out);
+        }
+        module.pop();
+    }
+}
+
+fn item_name(item: &Item) -> Option<&str> {
+    match item {
+        Item::Function(func) => Some(&func.name),
+        Item::Struct(struct_item) => Some(&struct_item.name),
+        Item::Enum(enum_item) => Some(&enum_item.name),
+        Item::Mod(module) => Some(&module.name),
+        Item::Const(const_item) => Some(&const_item.name),
+        Item::Static(static_item) => Some(&static_item.name),
+        Item::Type(alias) => Some(&alias.name),
+        Item::Trait(trait_item) => Some(&trait_item.name),
+        Item::Impl(_) | Item::Use(_) => None,
+    }
+}
+
+fn prefix_items(items: &mut [Item], module: &mut Vec<String>, defined: &[Vec<String>]) {
+    for item in items {
+        if let Item::Mod(inner) = item {
+            module.push(inner.name.clone());
+            prefix_items(&mut inner.items, module, defined);
+            module.pop();
+            continue;
+        }
+
+        let resolve = |path: &mut String| {
+            if let Some(resolved) = resolve_item_path(path, module, defined) {
+                *path = resolved;
+            }
+        };
+        walk_item(
+            item,
+            &mut |ty| match ty {
+                Type::Path(path) | Type::Generic { name: path, .. } => resolve(path),
+                _ => {}
+            },
+            &mut |path| resolve(path),
+        );
+        if module.is_empty() {
+            continue;
+        }
+        let prefix = module.join("_");
+        let name = match item {
+            Item::Function(func) => &mut func.name,
+            Item::Struct(struct_item) => &mut struct_item.name,
+            Item::Enum(enum_item) => &mut enum_item.name,
+            Item::Const(const_item) => &mut const_item.name,
+            Item::Static(static_item) => &mut static_item.name,
+            Item::Type(alias) => &mut alias.name,
+            Item::Trait(trait_item) => &mut trait_item.name,
+            Item::Impl(_) | Item::Use(_) | Item::Mod(_) => continue,
+        };
+        *name = format!("{}_{}", prefix, name);
+    }
+}
+
+/// The prefixed spelling of `path` as written inside `module`, or `None` when
+/// it doesn't name an item defined in the unit
+fn resolve_item_path(path: &str, module: &[String], defined: &[Vec<String>]) -> Option<String> {
+    if path.contains('<') {
+        return None;
+    }
+    let segments: Vec<&str> = path.split("::").collect();
+    let mut absolute: Vec<String> = match segments[0] {
+        "crate" => Vec::new(),
+        "self" | "super" => module.to_vec(),
+        first => {
+            let mut relative = module.to_vec();
+            relative.push(first.to_string());
+            if defined.contains(&relative) {
+                module.to_vec()
+            } else {
+                Vec::new()
+            }
+        }
+    };
+    let mut rest = &segments[..];
+    while let Some((first, tail)) = rest.split_first() {
+        match *first {
+            "crate" | "self" => {}
+            "super" => {
+                absolute.pop()?;
+            }
+            _ => break,
+        }
+        rest = tail;
+    }
+    absolute.extend(rest.iter().map(|segment| segment.to_string()));
+
+    // The longest prefix that names an item; what follows is a variant or associated item
+    let item_len = (1..=absolute.len())
+        .rev()
+        .find(|&len| defined.iter().any(|path| *path == absolute[..len]))?;
+    let mut resolved = vec![absolute[..item_len].join("_")];
+    resolved.extend(absolute[item_len..].iter().cloned());
+    Some(resolved.join("::"))
+}
+
+/// Walk every type and expression path reachable from an item
+fn walk_item(
+    item: &mut Item,
+    on_type: &mut dyn FnMut(&mut Type),
+    on_path: &mut dyn FnMut(&mut String),
+) {
+    match item {
+        Item::Function(func) => walk_function(func, on_type, on_path),
+        Item::Struct(struct_item) => {
+            walk_generics(&mut struct_item.generics, on_type);
+            for field in &mut struct_item.fields {
+                walk_type(&mut field.type_, on_type);
+            }
+        }
+        Item::Enum(enum_item) => {
+            walk_generics(&mut enum_item.generics, on_type);
+            for variant in &mut enum_item.variants {
+                match &mut variant.data {
+                    VariantData::Unit => {}
+                    VariantData::Tuple(types) => {
+                        for ty in types {
+                            walk_type(ty, on_type);
+                        }
+                    }
+                    VariantData::Struct(fields) => {
+                        for field in fields {
+                            walk_type(&mut field.type_, on_type);
+                        }
+                    }
+                }
+            }
+        }
+        Item::Impl(impl_item) => {
+            walk_type(&mut impl_item.target_type, on_type);
+            if let Some(trait_) = &mut impl_item.trait_ {
+                walk_type(trait_, on_type);
+            }
+            walk_generics(&mut impl_item.generics, on_type);
+            for item in &mut impl_item.items {
+                match item {
+                    ImplItem::Function(func) => walk_function(func, on_type, on_path),
+                    ImplItem::Const(const_item) => {
+                        walk_type(&mut const_item.type_, on_type);
+                        walk_expression(&mut const_item.value, on_type, on_path);
+                    }
+                    ImplItem::Type(alias) => {
+                        walk_generics(&mut alias.generics, on_type);
+                        walk_type(&mut alias.type_, on_type);
+                    }
+                }
+            }
+        }
+        Item::Use(_) => {}
+        Item::Mod(module) => {
+            for item in &mut module.items {
+                walk_item(item, on_type, on_path);
+            }
+        }
+        Item::Const(const_item) => {
+            walk_type(&mut const_item.type_, on_type);
+            walk_expression(&mut const_item.value, on_type, on_path);
+        }
+        Item::Static(static_item) => {
+            walk_type(&mut static_item.type_, on_type);
+            walk_expression(&mut static_item.value, on_type, on_path);
+        }
+        Item::Type(alias) => {
+            walk_generics(&mut alias.generics, on_type);
+            walk_type(&mut alias.type_, on_type);
+        }
+        Item::Trait(trait_item) => {
+            walk_generics(&mut trait_item.generics, on_type);
+            for item in &mut trait_item.items {
+                match item {
+                    TraitItem::Function(func) => {
+                        walk_generics(&mut func.generics, on_type);
+                        for param in &mut func.parameters {
+                            walk_type(&mut param.type_, on_type);
+                        }
+                        if let Some(return_type) = &mut func.return_type {
+                            walk_type(return_type, on_type);
+                        }
+                        if let Some(body) = &mut func.default_body {
+                            walk_statements(body, on_type, on_path);
+                        }
+                    }
+                    TraitItem::Type { bounds, .. } => {
+                        for bound in bounds {
+                            walk_type(bound, on_type);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn walk_function(
+    func: &mut Function,
+    on_type: &mut dyn FnMut(&mut Type),
+    on_path: &mut dyn FnMut(&mut String),
+) {
+    walk_generics(&mut func.generics, on_type);
+    for param in &mut func.parameters {
+        walk_type(&mut param.type_, on_type);
+    }
+    if let Some(return_type) = &mut func.return_type {
+        walk_type(return_type, on_type);
+    }
+    walk_statements(&mut func.body, on_type, on_path);
+}
+
+fn walk_generics(generics: &mut [Generic], on_type: &mut dyn FnMut(&mut Type)) {
+    for generic in generics {
+        for bound in &mut generic.bounds {
+            walk_type(bound, on_type);
+        }
+    }
+}
+
+fn walk_type(ty: &mut Type, on_type: &mut dyn FnMut(&mut Type)) {
+    on_type(ty);
+    match ty {
+        Type::Reference { inner, .. }
+        | Type::Pointer { inner, .. }
+        | Type::Array { inner, .. }
+        | Type::Slice(inner) => walk_type(inner, on_type),
+        Type::Tuple(types) => {
+            for ty in types {
+                walk_type(ty, on_type);
+            }
+        }
+        Type::Function { params, return_ } => {
+            for ty in params {
+                walk_type(ty, on_type);
+            }
+            walk_type(return_, on_type);
+        }
+        Type::Generic { args, .. } => {
+            for ty in args {
+                walk_type(ty, on_type);
+            }
+        }
+        Type::Path(_) | Type::Unit | Type::Never => {}
+    }
+}
+
+fn walk_statements(
+    stmts: &mut [Statement],
+    on_type: &mut dyn FnMut(&mut Type),
+    on_path: &mut dyn FnMut(&mut String),
+) {
+    for stmt in stmts {
+        walk_statement(stmt, on_type, on_path);
+    }
+}
+
+fn walk_statement(
+    stmt: &mut Statement,
+    on_type: &mut dyn FnMut(&mut Type),
+    on_path: &mut dyn FnMut(&mut String),
+) {
+    match stmt {
+        Statement::Expression(expr) | Statement::Raise(expr) | Statement::Abort(expr) => {
+            walk_expression(expr, on_type, on_path)
+        }
+        Statement::Break(_) | Statement::Continue(_) => {}
+        Statement::Let { type_, value, .. } => {
+            if let Some(ty) = type_ {
+                walk_type(ty, on_type);
+            }
+            if let Some(value) = value {
+                walk_expression(value, on_type, on_path);
+            }
+        }
+        Statement::LetPattern {
+            pattern,
+            type_,
+            value,
+        } => {
+            walk_pattern(pattern, on_path);
+            if let Some(ty) = type_ {
+                walk_type(ty, on_type);
+            }
+            walk_expression(value, on_type, on_path);
+        }
+        Statement::Return(expr) => {
+            if let Some(expr) = expr {
+                walk_expression(expr, on_type, on_path);
+            }
+        }
+        Statement::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            walk_expression(condition, on_type, on_path);
+            walk_statements(then_branch, on_type, on_path);
+            if let Some(else_branch) = else_branch {
+                walk_statements(else_branch, on_type, on_path);
+            }
+        }
+        Statement::While {
+            condition, body, ..
+        } => {
+            walk_expression(condition, on_type, on_path);
+            walk_statements(body, on_type, on_path);
+        }
+        Statement::Loop { body, .. } => walk_statements(body, on_type, on_path),
+        Statement::For { iterator, body, .. } => {
+            walk_expression(iterator, on_type, on_path);
+            walk_statements(body, on_type, on_path);
+        }
+        Statement::Match { expr, arms } => {
+            walk_expression(expr, on_type, on_path);
+            for arm in arms {
+                walk_pattern(&mut arm.pattern, on_path);
+                if let Some(guard) = &mut arm.guard {
+                    walk_expression(guard, on_type, on_path);
+                }
+                walk_statements(&mut arm.body, on_type, on_path);
+            }
+        }
+        Statement::Block(stmts) => walk_statements(stmts, on_type, on_path),
+    }
+}
+
+fn walk_pattern(pattern: &mut Pattern, on_path: &mut dyn FnMut(&mut String)) {
+    match pattern {
+        Pattern::Wildcard
+        | Pattern::Identifier(_)
+        | Pattern::Literal(_)
+        | Pattern::Range { .. } => {}
+        Pattern::Tuple(patterns) | Pattern::Or(patterns) => {
+            for pattern in patterns {
+                walk_pattern(pattern, on_path);
+            }
+        }
+        Pattern::Struct { name, fields } => {
+            on_path(name);
+            for (_, pattern) in fields {
+                walk_pattern(pattern, on_path);
+            }
+        }
+        Pattern::Enum { path, fields, .. } => {
+            on_path(path);
+            for pattern in fields {
+                walk_pattern(pattern, on_path);
+            }
+        }
+    }
+}
+
+fn walk_expression(
+    expr: &mut Expression,
+    on_type: &mut dyn FnMut(&mut Type),
+    on_path: &mut dyn FnMut(&mut String),
+) {
+    match expr {
+        Expression::Literal(_) | Expression::Unsupported { .. } => {}
+        Expression::Identifier(path) | Expression::Path(path) => on_path(path),
+        Expression::Call { function, args } => {
+            walk_expression(function, on_type, on_path);
+            for arg in args {
+                walk_expression(arg, on_type, on_path);
+            }
+        }
+        Expression::MethodCall {
+            receiver,
+            turbofish,
+            args,
+            ..
+        } => {
+            walk_expression(receiver, on_type, on_path);
+            for ty in turbofish {
+                walk_type(ty, on_type);
+            }
+            for arg in args {
+                walk_expression(arg, on_type, on_path);
+            }
+        }
+        Expression::FieldAccess { object, .. } => walk_expression(object, on_type, on_path),
+        Expression::Index { object, index } => {
+            walk_expression(object, on_type, on_path);
+            walk_expression(index, on_type, on_path);
+        }
+        Expression::Binary { left, right, .. } => {
+            walk_expression(left, on_type, on_path);
+            walk_expression(right, on_type, on_path);
+        }
+        Expression::Unary { operand, .. } => walk_expression(operand, on_type, on_path),
+        Expression::Cast { expr, type_ } => {
+            walk_expression(expr, on_type, on_path);
+            walk_type(type_, on_type);
+        }
+        Expression::Reference { expr, .. } => walk_expression(expr, on_type, on_path),
+        Expression::Dereference(expr) | Expression::Try(expr) => {
+            walk_expression(expr, on_type, on_path)
+        }
+        Expression::Block(stmts) => walk_statements(stmts, on_type, on_path),
+        Expression::Repeat { value, count } => {
+            walk_expression(value, on_type, on_path);
+            walk_expression(count, on_type, on_path);
+        }
+        Expression::Array(exprs) | Expression::Tuple(exprs) => {
+            for expr in exprs {
+                walk_expression(expr, on_type, on_path);
+            }
+        }
+        Expression::Range { start, end, .. } => {
+            for expr in [start, end].into_iter().flatten() {
+                walk_expression(expr, on_type, on_path);
+            }
+        }
+        Expression::Closure { params, body } => {
+            for param in params {
+                walk_type(&mut param.type_, on_type);
+            }
+            walk_expression(body, on_type, on_path);
+        }
+        Expression::Struct { name, fields } => {
+            on_path(name);
+            for (_, value) in fields {
+                walk_expression(value, on_type, on_path);
+            }
+        }
+    }
+}
@@ -0,0 +1,400 @@
+//! File and directory watching, and batch recompilation
+//!
+//! This module walks a source tree for `.rs` files, maps each one onto a mirrored
+//! `.mojo` path in an output tree, and polls for changes so edited files can be
+//! recompiled as they are saved.
+//!
+//! Changes are found by comparing modification times between scans rather than
+//! through OS file events (e.g. the `notify` crate). Polling costs a directory
+//! walk per interval, but behaves the same on every platform and filesystem,
+//! including network mounts and editors that save by renaming, and needs no
+//! extra dependency.
+
+use crate::{Compiler, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+use tracing::{error, info};
+
+/// Modification times of every Rust file under a directory
+pub type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+/// Recursively collect every `.rs` file under `dir`, in sorted order
+///
+/// Symlinked directories are not descended into, so a link cycle cannot
+/// make the walk run forever.
+pub fn find_rust_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Map a Rust file under `src_root` to its `.mojo` counterpart under `out_root`
+///
+/// Files outside `src_root` keep only their file name.
+pub fn output_path(src_root: &Path, out_root: &Path, file: &Path) -> PathBuf {
+    let relative = file
+        .strip_prefix(src_root)
+        .unwrap_or_else(|_| Path::new(file.file_name().unwrap_or_default()));
+    out_root.join(relative).with_extension("mojo")
+}
+
+/// Record the modification time of every Rust file under `dir`
+pub fn snapshot(dir: &Path) -> Result<Snapshot> {
+    find_rust_files(dir)?
+        .into_iter()
+        .map(|path| {
+            let modified = std::fs::metadata(&path)?.modified()?;
+            Ok((path, modified))
+        })
+        .collect()
+}
+
+/// Record the modification time of a single file; a missing file gives an empty snapshot
+///
+/// Editors that save by renaming a temporary file over the original can leave the
+/// path briefly missing, which should read as "not changed yet" rather than an error.
+pub fn file_snapshot(path: &Path) -> Result<Snapshot> {
+    match std::fs::metadata(path) {
+        Ok(metadata) => Ok([(path.to_path_buf(), metadata.modified()?)]
+            .into_iter()
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Snapshot::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Files that were added or modified between two snapshots
+pub fn changed_files(before: &Snapshot, after: &Snapshot) -> Vec<PathBuf> {
+    after
+        .iter()
+        .filter(|(path, modified)| before.get(*path) != Some(*modified))
+        .map(|(path, _)| path.clone())
+        .collect()
+}
+
+/// Compile `input` into `output`, logging success or failure
+///
+/// Returns whether compilation succeeded.
+pub fn compile_and_report(compiler: &Compiler, input: &Path, output: &Path) -> bool {
+    let result = compiler.compile_file(input).and_then(|mojo_code| {
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(output, mojo_code)?;
+        Ok(())
+    });
+    match result {
+        Ok(()) => {
+            info!("Compiled {:?} -> {:?}", input, output);
+            true
+        }
+        Err(e) => {
+            error!("Failed to compile {:?}: {}", input, e);
+            false
+        }
+    }
+}
+
+/// Poll `scan` until it differs from `current` and then holds steady for `debounce`
+///
+/// Returns the settled snapshot, or `None` once `shutdown` is set, even while
+/// files are still changing.
+fn wait_for_change(
+    current: &Snapshot,
+    poll_interval: Duration,
+    debounce: Duration,
+    shutdown: &AtomicBool,
+    scan: impl Fn() -> Result<Snapshot>,
+) -> Result<Option<Snapshot>> {
+    while !shutdown.load(Ordering::Relaxed) {
+        std::thread::sleep(poll_interval);
+        let mut latest = scan()?;
+        if changed_files(current, &latest).is_empty() {
+            continue;
+        }
+
+        // Wait for the tree to settle before rebuilding
+        while !shutdown.load(Ordering::Relaxed) {
+            std::thread::sleep(debounce);
+            let settled = scan()?;
+            if settled == latest {
+                return Ok(Some(latest));
+            }
+            latest = settled;
+        }
+    }
+    Ok(None)
+}
+
+/// Counts of files compiled by a batch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+/// Polls a source tree and recompiles changed files into an output tree
+pub struct DirWatcher {
+    src_root: PathBuf,
+    out_root: PathBuf,
+    /// How often the source tree is scanned
+    pub poll_interval: Duration,
+    /// How long the tree must stay unchanged before recompiling, so that a
+    /// burst of saves triggers a single rebuild
+    pub debounce: Duration,
+}
+
+impl DirWatcher {
+    /// Create a watcher mirroring `src_root` into `out_root`
+    pub fn new(src_root: impl Into<PathBuf>, out_root: impl Into<PathBuf>) -> Self {
+        Self {
+            src_root: src_root.into(),
+            out_root: out_root.into(),
+            poll_interval: Duration::from_millis(250),
+            debounce: Duration::from_millis(100),
+        }
+    }
+
+    /// Compile every Rust file in the source tree into the output tree
+    pub fn compile_all(&self, compiler: &Compiler) -> Result<BatchSummary> {
+        let files = find_rust_files(&self.src_root)?;
+        Ok(self.compile_files(compiler, &files))
+    }
+
+    /// Compile `files`, writing each result into the output tree
+    ///
+    /// Failures are logged and skipped so one broken file doesn't stop the rest.
+    pub fn compile_files(&self, compiler: &Compiler, files: &[PathBuf]) -> BatchSummary {
+        let mut summary = BatchSummary::default();
+        for file in files {
+            let output = output_path(&self.src_root, &self.out_root, file);
+            if compile_and_report(compiler, file, &output) {
+                summary.succeeded += 1;
+            } else {
+                summary.failed += 1;
+            }
+        }
+        summary
+    }
+
+    /// Compile the whole tree once, then recompile changed files until `shutdown` is set
+    pub fn run(&self, compiler: &Compiler, shutdown: &AtomicBool) -> Result<()> {
+        let mut current = snapshot(&self.src_root)?;
+        let files: Vec<PathBuf> = current.keys().cloned().collect();
+        self.compile_files(compiler, &files);
+
+        while let Some(latest) = wait_for_change(
+            &current,
+            self.poll_interval,
+            self.debounce,
+            shutdown,
+            || snapshot(&self.src_root),
+        )? {
+            let changed = changed_files(&current, &latest);
+            self.compile_files(compiler, &changed);
+            current = latest;
+        }
+
+        info!("Stopped watching {:?}", self.src_root);
+        Ok(())
+    }
+}
+
+/// Polls a single Rust file and recompiles it into an output file on every save
+pub struct FileWatcher {
+    input: PathBuf,
+    output: PathBuf,
+    /// How often the input file is checked
+    pub poll_interval: Duration,
+    /// How long the file must stay unchanged before recompiling
+    pub debounce: Duration,
+}
+
+impl FileWatcher {
+    /// Create a watcher compiling `input` into `output`
+    pub fn new(input: impl Into<PathBuf>, output: impl Into<PathBuf>) -> Self {
+        Self {
+            input: input.into(),
+            output: output.into(),
+            poll_interval: Duration::from_millis(250),
+            debounce: Duration::from_millis(100),
+        }
+    }
+
+    /// Compile once, then recompile on every change until `shutdown` is set
+    pub fn run(&self, compiler: &Compiler, shutdown: &AtomicBool) -> Result<()> {
+        let mut current = file_snapshot(&self.input)?;
+        compile_and_report(compiler, &self.input, &self.output);
+
+        while let Some(latest) = wait_for_change(
+            &current,
+            self.poll_interval,
+            self.debounce,
+            shutdown,
+            || file_snapshot(&self.input),
+        )? {
+            compile_and_report(compiler, &self.input, &self.output);
+            current = latest;
+        }
+
+        info!("Stopped watching {:?}", self.input);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_path_mirrors_tree() {
+        let output = output_path(
+            Path::new("src"),
+            Path::new("build"),
+            Path::new("src/net/client.rs"),
+        );
+        assert_eq!(output, PathBuf::from("build/net/client.mojo"));
+    }
+
+    #[test]
+    fn test_changed_files_detects_added_and_modified() {
+        let t0 = SystemTime::UNIX_EPOCH;
+        let t1 = t0 + Duration::from_secs(1);
+
+        let before: Snapshot = [
+            (PathBuf::from("a.rs"), t0),
+            (PathBuf::from("b.rs"), t0),
+            (PathBuf::from("gone.rs"), t0),
+        ]
+        .into_iter()
+        .collect();
+        let after: Snapshot = [
+            (PathBuf::from("a.rs"), t0),
+            (PathBuf::from("b.rs"), t1),
+            (PathBuf::from("c.rs"), t0),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            changed_files(&before, &after),
+            vec![PathBuf::from("b.rs"), PathBuf::from("c.rs")]
+        );
+        assert!(changed_files(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_shutdown_interrupts_debounce() {
+        let shutdown = AtomicBool::new(false);
+        let scans = std::cell::Cell::new(0u64);
+        // A file that never stops changing would otherwise debounce forever
+        let result = wait_for_change(
+            &Snapshot::new(),
+            Duration::ZERO,
+            Duration::ZERO,
+            &shutdown,
+            || {
+                scans.set(scans.get() + 1);
+                if scans.get() == 5 {
+                    shutdown.store(true, Ordering::Relaxed);
+                }
+                let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(scans.get());
+                Ok([(PathBuf::from("busy.rs"), modified)].into_iter().collect())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result, None);
+        assert_eq!(scans.get(), 5);
+    }
+
+    #[test]
+    fn test_find_rust_files_recurses() {
+        let root = std::env::temp_dir().join(format!("rust2mojo-watch-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("nested")).unwrap();
+        std::fs::write(root.join("lib.rs"), "fn a() {}").unwrap();
+        std::fs::write(root.join("nested/mod.rs"), "fn b() {}").unwrap();
+        std::fs::write(root.join("notes.txt"), "").unwrap();
+
+        let files = find_rust_files(&root).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(files, vec![root.join("lib.rs"), root.join("nested/mod.rs")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_rust_files_skips_symlink_cycles() {
+        let root = std::env::temp_dir().join(format!("rust2mojo-cycle-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("nested")).unwrap();
+        std::fs::write(root.join("nested/mod.rs"), "fn b() {}").unwrap();
+        std::os::unix::fs::symlink("..", root.join("nested/loop")).unwrap();
+
+        let files = find_rust_files(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(files.unwrap(), vec![root.join("nested/mod.rs")]);
+    }
+
+    #[test]
+    fn test_compile_and_report() {
+        let root = std::env::temp_dir().join(format!("rust2mojo-report-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("good.rs"), "fn good() {}").unwrap();
+        std::fs::write(root.join("bad.rs"), "fn bad( {").unwrap();
+
+        let compiler = Compiler::new();
+        let good = compile_and_report(&compiler, &root.join("good.rs"), &root.join("good.mojo"));
+        let bad = compile_and_report(&compiler, &root.join("bad.rs"), &root.join("bad.mojo"));
+        let output = std::fs::read_to_string(root.join("good.mojo")).unwrap();
+        let missing = file_snapshot(&root.join("missing.rs")).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert!(good);
+        assert!(!bad);
+        assert!(output.contains("fn good():"));
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn test_compile_all_continues_past_failures() {
+        let root = std::env::temp_dir().join(format!("rust2mojo-batch-{}", std::process::id()));
+        let src = root.join("src");
+        let out = root.join("build");
+        std::fs::create_dir_all(src.join("nested")).unwrap();
+        std::fs::write(src.join("nested/good.rs"), "fn good() {}").unwrap();
+        std::fs::write(src.join("bad.rs"), "fn bad( {").unwrap();
+
+        let summary = DirWatcher::new(&src, &out)
+            .compile_all(&Compiler::new())
+            .unwrap();
+        let good_output = out.join("nested/good.mojo");
+        let good_exists = good_output.exists();
+        let bad_exists = out.join("bad.mojo").exists();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            summary,
+            BatchSummary {
+                succeeded: 1,
+                failed: 1
+            }
+        );
+        assert!(good_exists);
+        assert!(!bad_exists);
+    }
+}
@@ -56,9 +56,9 @@ mod compilation_contracts {
         assert!(result.is_ok());
         let mojo_code = result.unwrap();
 
-        // Should still have proper header and imports
+        // Should still have a proper header, but no unused imports
         assert!(mojo_code.contains("# Generated Mojo code"));
-        assert!(mojo_code.contains("from memory import UnsafePointer"));
+        assert!(!mojo_code.contains("import"));
     }
 
     /// Contract: Invalid Rust syntax must produce clear error messages
@@ -0,0 +1,39 @@
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub enum Shape {
+    Circle { radius: f64 },
+    Rectangle { width: f64, height: f64 },
+    Unit,
+}
+
+pub trait Area {
+    fn area(&self) -> f64;
+}
+
+impl Area for Shape {
+    fn area(&self) -> f64 {
+        match self {
+            Shape::Circle { radius } => 3.14159 * radius * radius,
+            Shape::Rectangle { width, height } => width * height,
+            Shape::Unit => 1.0,
+        }
+    }
+}
+
+pub fn total_area(shapes: &[Shape]) -> f64 {
+    let mut total: f64 = 0.0;
+    for shape in shapes {
+        total += shape.area();
+    }
+    total
+}
+
+pub fn describe(count: usize) -> String {
+    match count {
+        0 => "none".to_string(),
+        1 => "one".to_string(),
+        n if n < 10 => format!("{} shapes", n),
+        _ => "many".to_string(),
+    }
+}
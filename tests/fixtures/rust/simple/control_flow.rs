@@ -0,0 +1,32 @@
+fn classify(n: i32) -> &'static str {
+    if n < 0 {
+        "negative"
+    } else if n == 0 {
+        "zero"
+    } else {
+        "positive"
+    }
+}
+
+fn collatz_steps(start: u64) -> u32 {
+    let mut n = start;
+    let mut steps = 0;
+    while n != 1 {
+        if n % 2 == 0 {
+            n /= 2;
+        } else {
+            n = 3 * n + 1;
+        }
+        steps += 1;
+    }
+    steps
+}
+
+fn first_multiple(values: &[i32], divisor: i32) -> i32 {
+    for &value in values {
+        if value % divisor == 0 {
+            return value;
+        }
+    }
+    -1
+}
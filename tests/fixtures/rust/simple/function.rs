@@ -0,0 +1,17 @@
+/// Add two numbers
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+fn scaled_sum(values: Vec<i64>, factor: i64) -> i64 {
+    let mut total = 0;
+    for value in values {
+        total += value * factor;
+    }
+    total
+}
+
+fn main() {
+    let result = add(2, 3);
+    println!("2 + 3 = {}", result);
+}
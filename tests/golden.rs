@@ -0,0 +1,144 @@
+//! Golden-file tests
+//!
+//! Every Rust file under `tests/fixtures/rust/` is compiled and the output
+//! compared with the checked-in file at the same relative path under
+//! `tests/fixtures/mojo/`, with the extension `.mojo.expected`. Files under
+//! `invalid/` are inputs for error tests and are skipped here.
+//!
+//! After an intended change to the generated code, regenerate the
+//! expectations and review the diff:
+//!
+//! ```bash
+//! UPDATE_GOLDEN=1 cargo test --test golden
+//! ```
+
+use rust2mojo::Compiler;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const UPDATE_ENV: &str = "UPDATE_GOLDEN";
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// Rust fixtures under `dir`, sorted so failures are reported in a stable order
+fn rust_fixtures(dir: &Path) -> Vec<PathBuf> {
+    let mut fixtures = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name != "invalid") {
+                fixtures.extend(rust_fixtures(&path));
+            }
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            fixtures.push(path);
+        }
+    }
+    fixtures.sort();
+    fixtures
+}
+
+/// Where the expected output for a Rust fixture is kept
+fn expected_path(fixture: &Path) -> PathBuf {
+    let relative = fixture
+        .strip_prefix(fixtures_dir().join("rust"))
+        .unwrap()
+        .with_extension("mojo.expected");
+    fixtures_dir().join("mojo").join(relative)
+}
+
+/// Describe the first line where two outputs differ
+fn first_difference(expected: &str, actual: &str) -> String {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    for line in 1.. {
+        match (expected_lines.next(), actual_lines.next()) {
+            (None, None) => break,
+            (e, a) if e == a => continue,
+            (e, a) => {
+                return format!(
+                    "line {}:\n  expected: {}\n  actual:   {}",
+                    line,
+                    e.unwrap_or("<end of file>"),
+                    a.unwrap_or("<end of file>")
+                )
+            }
+        }
+    }
+    "trailing newline differs".to_string()
+}
+
+#[test]
+fn golden_files_match() {
+    let update = std::env::var_os(UPDATE_ENV).is_some();
+    let compiler = Compiler::new();
+    let fixtures = rust_fixtures(&fixtures_dir().join("rust"));
+    assert!(!fixtures.is_empty(), "no fixtures found");
+
+    let mut failures = Vec::new();
+    for fixture in &fixtures {
+        let source = fs::read_to_string(fixture).unwrap();
+        let actual = match compiler.compile_str(&source) {
+            Ok(mojo_code) => mojo_code,
+            Err(err) => {
+                failures.push(format!("{}: failed to compile: {}", fixture.display(), err));
+                continue;
+            }
+        };
+
+        let expected_path = expected_path(fixture);
+        if update {
+            fs::create_dir_all(expected_path.parent().unwrap()).unwrap();
+            fs::write(&expected_path, &actual).unwrap();
+            continue;
+        }
+        match fs::read_to_string(&expected_path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => failures.push(format!(
+                "{}: output differs from {}, {}",
+                fixture.display(),
+                expected_path.display(),
+                first_difference(&expected, &actual)
+            )),
+            Err(_) => failures.push(format!(
+                "{}: missing {}",
+                fixture.display(),
+                expected_path.display()
+            )),
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} golden files failed (rerun with {}=1 to update them):\n\n{}",
+        failures.len(),
+        fixtures.len(),
+        UPDATE_ENV,
+        failures.join("\n\n")
+    );
+}
+
+#[test]
+fn every_expectation_has_a_fixture() {
+    let mojo_dir = fixtures_dir().join("mojo");
+    let mut stack = vec![mojo_dir.clone()];
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            let relative = path.strip_prefix(&mojo_dir).unwrap().to_str().unwrap();
+            let fixture = relative
+                .strip_suffix(".mojo.expected")
+                .map(|stem| fixtures_dir().join("rust").join(format!("{}.rs", stem)));
+            assert!(
+                fixture.as_ref().is_some_and(|fixture| fixture.exists()),
+                "{} has no matching Rust fixture",
+                path.display()
+            );
+        }
+    }
+}
@@ -1,6 +1,11 @@
 //! Integration tests for the rust2mojo compiler
 
-use rust2mojo::Compiler;
+use rust2mojo::ast::{
+    BinaryOp, CompilationUnit, Expression, ImplItem, Item, Literal, Parameter, Pattern, Statement,
+    TraitItem, Type, UnaryOp, VariantData, Visibility,
+};
+use rust2mojo::parser::parse_rust_code;
+use rust2mojo::{Compiler, CompilerConfig, CompilerOptions, Error, ModuleNaming, SourceLocation};
 
 #[test]
 fn test_basic_function_compilation() {
@@ -49,6 +54,28 @@ fn test_struct_compilation() {
     assert!(result.is_ok());
     let mojo_code = result.unwrap();
     assert!(mojo_code.contains("struct Point:"));
+    assert!(mojo_code.contains("    var x: Int32\n"));
+    assert!(mojo_code.contains("    var y: Int32\n"));
+}
+
+#[test]
+fn test_struct_field_declarations() {
+    let rust_code = r#"
+        pub struct Empty {}
+        struct Unit;
+        pub struct Account {
+            pub owner: String,
+            balance: u64,
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("struct Empty:\n    pass\n"));
+    assert!(mojo_code.contains("struct Unit:\n    pass\n"));
+
+    // Mojo has no private fields, so both become plain `var`s
+    assert!(mojo_code.contains("    var owner: String\n    var balance: UInt64\n"));
+    assert!(mojo_code.contains("fn __init__(inout self, owner: String, balance: UInt64):"));
 }
 
 #[test]
@@ -64,3 +91,3254 @@ fn test_invalid_rust_code() {
 
     assert!(result.is_err());
 }
+
+#[test]
+fn test_slice_first_translates_to_optional() {
+    let rust_code = r#"
+        fn head(v: Vec<i32>) {
+            let a = v.first();
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("let a = (Optional(v[0]) if len(v) > 0 else None)"));
+}
+
+#[test]
+fn test_slice_accessors_bind_the_receiver_once() {
+    let rust_code = r#"
+        struct Row;
+
+        fn ends(x: Row) {
+            let a = make().first();
+            let b = make().last();
+            let c = make().get(i());
+            let d = x.first();
+        }
+
+        fn make() -> Vec<i32> {
+            vec![1, 2]
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+
+    assert!(mojo_code
+        .contains("let a = (Optional(__list[0]) if len((__list := make())) > 0 else None)"));
+    assert!(mojo_code.contains(
+        "let b = (Optional(__list[len(__list) - 1]) if len((__list := make())) > 0 else None)"
+    ));
+    assert!(mojo_code.contains(
+        "let c = (Optional(__list[__index]) if (__index := i()) < len((__list := make())) else None)"
+    ));
+    assert!(mojo_code.contains("let d = x.first()"));
+}
+
+#[test]
+fn test_slice_get_translates_to_optional() {
+    let rust_code = r#"
+        fn third(v: Vec<i32>) {
+            let c = v.get(2);
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("let c = (Optional(v[2]) if 2 < len(v) else None)"));
+}
+
+#[test]
+fn test_get_binds_its_index_once_and_leaves_maps_alone() {
+    let rust_code = r#"
+        use std::collections::HashMap;
+
+        fn lookup(v: &[i32], m: HashMap<i32, i32>, i: usize) {
+            let a = v.get(i + 1);
+            let b = m.get(&1);
+            let c = unknown.get(2);
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+
+    assert!(mojo_code
+        .contains("let a = (Optional(v[__index]) if (__index := i + 1) < len(v) else None)"));
+    // A `Dict` has its own `get`, and an untyped receiver could be anything
+    assert!(mojo_code.contains("let b = m.get(1)"));
+    assert!(mojo_code.contains("let c = unknown.get(2)"));
+}
+
+#[test]
+fn test_formatted_panic_aborts() {
+    let rust_code = r#"
+        fn check(x: i32) {
+            panic!("bad value: {}", x);
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("from os import abort\n"));
+    assert!(mojo_code.contains("fn check(x: Int32):"));
+    assert!(mojo_code.contains("abort(\"bad value: \" + String(x))"));
+}
+
+#[test]
+fn test_plain_panic_aborts() {
+    let rust_code = r#"
+        fn fail() {
+            panic!("msg");
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("fn fail():"));
+    assert!(mojo_code.contains("abort(\"msg\")"));
+}
+
+#[test]
+fn test_never_type_and_diverging_macros() {
+    let rust_code = r#"
+        fn f() -> ! { panic!() }
+
+        fn sign(x: i32) -> i32 {
+            if x > 0 {
+                1
+            } else if x < 0 {
+                -1
+            } else {
+                unreachable!()
+            }
+        }
+
+        fn later() { todo!() }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(f) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    assert_eq!(f.return_type, Some(Type::Never));
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("fn f():\n    abort(\"explicit panic\")\n"));
+    assert!(mojo_code.contains(
+        "    else:\n        return abort[Int32](\"internal error: entered unreachable code\")\n"
+    ));
+    assert!(mojo_code.contains("fn later():\n    abort(\"not yet implemented\")\n"));
+    assert!(!mojo_code.contains("raises"));
+}
+
+#[test]
+fn test_inline_single_use_aliases() {
+    let rust_code = r#"
+        type Meters = f64;
+        type Id = i32;
+
+        fn walk(distance: Meters) {}
+        fn swap(a: Id, b: Id) {}
+    "#;
+
+    let config = CompilerConfig {
+        inline_single_use_aliases: true,
+        ..CompilerConfig::default()
+    };
+    let compiler = Compiler::with_config(config);
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    // Used once: inlined and removed
+    assert!(!mojo_code.contains("alias Meters"));
+    assert!(mojo_code.contains("fn walk(distance: Float64):"));
+
+    // Used twice: preserved
+    assert!(mojo_code.contains("alias Id = Int32"));
+    assert!(mojo_code.contains("fn swap(owned a: Id, owned b: Id):"));
+}
+
+#[test]
+fn test_impl_display_generates_str() {
+    let rust_code = r#"
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        impl fmt::Display for Point {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "({}, {})", self.x, self.y)
+            }
+        }
+
+        struct Line {
+            n: i32,
+        }
+
+        impl fmt::Display for Line {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                writeln!(f, "{}", self.n)
+            }
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("struct Point(Stringable):"));
+    assert!(mojo_code.contains("    fn __str__(self) -> String:"));
+    assert!(mojo_code
+        .contains("        return \"(\" + String(self.x) + \", \" + String(self.y) + \")\""));
+    assert!(mojo_code.contains("        return String(self.n) + \"\\n\""));
+    assert!(!mojo_code.contains("fn fmt"));
+    assert!(!mojo_code.contains("unsupported"));
+}
+
+#[test]
+fn test_match_arm_block_yields_value() {
+    let rust_code = r#"
+        fn double(n: i32) {
+            let y = match n {
+                0 => 0,
+                n => {
+                    let t = n * 2;
+                    t
+                }
+            };
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("    var y: Int32\n"));
+    assert!(mojo_code.contains("    if n == 0:\n        y = 0\n"));
+    assert!(mojo_code.contains("    else:\n        let t = n * 2\n        y = t\n"));
+}
+
+#[test]
+fn test_match_scrutinee_is_evaluated_once() {
+    let rust_code = r#"
+        fn pick() {
+            match next_val() {
+                1 => one(),
+                2 => two(),
+                _ => other(),
+            }
+            match n {
+                1 => one(),
+                _ => other(),
+            }
+            match next_val() {
+                1 => one(),
+                _ => other(),
+            }
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains(
+        "    var _match = next_val()\n    if _match == 1:\n        one()\n    elif _match == 2:\n"
+    ));
+    assert!(mojo_code.contains("    if n == 1:\n"));
+    assert!(mojo_code.contains("    var _match_1 = next_val()\n    if _match_1 == 1:\n"));
+    assert_eq!(mojo_code.matches("next_val()").count(), 2);
+}
+
+#[test]
+fn test_move_init_for_non_copy_structs() {
+    let rust_code = r#"
+        struct Buffer {
+            data: Vec<u8>,
+            len: usize,
+        }
+
+        #[derive(Clone, Copy)]
+        struct Handle {
+            id: RawId,
+        }
+    "#;
+
+    let config = CompilerConfig {
+        emit_move_init: true,
+        ..CompilerConfig::default()
+    };
+    let compiler = Compiler::with_config(config);
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    let buffer = &mojo_code[mojo_code.find("struct Buffer").unwrap()..];
+    let buffer = &buffer[..buffer.find("struct Handle").unwrap()];
+    assert!(buffer.contains("fn __moveinit__(inout self, owned existing: Self):"));
+    assert!(buffer.contains("self.data = existing.data^"));
+
+    let handle = &mojo_code[mojo_code.find("struct Handle").unwrap()..];
+    assert!(!handle.contains("__moveinit__"));
+}
+
+#[test]
+fn test_parse_unwrap_chain_lowers_to_atol() {
+    let rust_code = r#"
+        fn answer() {
+            let n = "42".parse::<i32>().unwrap();
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("fn answer() raises:"));
+    assert!(mojo_code.contains("let n = Int32(atol(\"42\"))"));
+    assert!(!mojo_code.contains("unwrap"));
+}
+
+#[test]
+fn test_deprecated_items_keep_note() {
+    let rust_code = r#"
+        #[deprecated(since = "0.2.0", note = "use new_api instead")]
+        fn old_api() {}
+
+        #[deprecated]
+        struct Legacy;
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("# Deprecated: use new_api instead\nfn old_api():"));
+    assert!(mojo_code.contains("# Deprecated\nstruct Legacy:"));
+}
+
+#[test]
+fn test_nested_repeat_array_initialization() {
+    let rust_code = r#"
+        fn make_grid() {
+            let grid = [[0; 3]; 4];
+            let row = [1, 2, 3];
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("let grid = List(length=4, fill=List(length=3, fill=0))"));
+    assert!(mojo_code.contains("let row = List(1, 2, 3)"));
+}
+
+#[test]
+fn test_vec_macro_initialization() {
+    let rust_code = r#"
+        fn make(n: usize) {
+            let v = vec![1, 2];
+            let zeros = vec![0; n];
+            let empty: Vec<i32> = vec![];
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("let v = List(1, 2)"));
+    assert!(mojo_code.contains("let zeros = List(length=n, fill=0)"));
+    assert!(mojo_code.contains("let empty: List[Int32] = List()"));
+    assert!(!mojo_code.contains("unsupported"));
+}
+
+#[test]
+fn test_signatures_only_emits_stub_bodies() {
+    let rust_code = r#"
+        fn checksum(len: u32, seed: u32) -> u32 {
+            let mut total = seed;
+            for i in 0..len {
+                total = total * 31 + i;
+            }
+            total
+        }
+    "#;
+
+    let config = CompilerConfig {
+        signatures_only: true,
+        ..CompilerConfig::default()
+    };
+    let compiler = Compiler::with_config(config);
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("fn checksum(len: UInt32, seed: UInt32) -> UInt32:\n    ...\n"));
+    assert!(!mojo_code.contains("total"));
+}
+
+#[test]
+fn test_generic_parameters_are_converted() {
+    let unit = parse_rust_code("fn pair<'a, T, U, const N: usize>(x: T, y: U) {}").unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+
+    let names: Vec<&str> = func.generics.iter().map(|g| g.name.as_str()).collect();
+    assert_eq!(names, ["T", "U"]);
+    assert!(func.generics.iter().all(|g| g.bounds.is_empty()));
+}
+
+#[test]
+fn test_generic_trait_bounds_are_captured() {
+    let unit = parse_rust_code("fn compare<T: PartialEq>(x: T, y: T) -> bool { x == y }").unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+
+    assert_eq!(func.generics.len(), 1);
+    assert_eq!(func.generics[0].name, "T");
+    assert_eq!(
+        func.generics[0].bounds,
+        vec![Type::Path("PartialEq".to_string())]
+    );
+}
+
+#[test]
+fn test_vec_mutation_methods_map_to_list() {
+    let rust_code = r#"
+        fn shuffle(v: Vec<i32>) {
+            v.push(4);
+            v.insert(0, 7);
+            let last = v.pop();
+            let first = v.remove(0);
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("v.append(4)"));
+    assert!(mojo_code.contains("v.insert(0, 7)"));
+    assert!(mojo_code.contains("let last = (Optional(v.pop()) if len(v) > 0 else None)"));
+    assert!(mojo_code.contains("let first = v.pop(0)"));
+
+    let mojo_code = compiler
+        .compile_str("fn make() -> Vec<i32> { vec![1] }\nfn drop_last() { let x = make().pop(); }")
+        .unwrap();
+    assert!(mojo_code
+        .contains("let x = (Optional(__list.pop()) if len((__list := make())) > 0 else None)"));
+}
+
+#[test]
+fn test_vec_append_empties_the_other_list() {
+    let rust_code = r#"
+        fn merge(v: &mut Vec<i32>, other: &mut Vec<i32>) {
+            v.append(other);
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("    v.extend(other)\n    other.clear()\n"));
+}
+
+#[test]
+fn test_list_mutation_rewrites_skip_other_receivers() {
+    let rust_code = r#"
+        use std::collections::{HashMap, HashSet, VecDeque};
+
+        fn update(m: &mut HashMap<i32, i32>, s: &mut HashSet<i32>, q: &mut VecDeque<i32>, r: &mut VecDeque<i32>) {
+            m.insert(1, 2);
+            let old = m.remove(&1);
+            s.insert(3);
+            s.remove(&3);
+            q.append(r);
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("    m[1] = 2\n"));
+    assert!(mojo_code.contains("let old = (Optional(m.pop(1)) if 1 in m else None)"));
+    assert!(mojo_code.contains("    s.insert(3)\n"));
+    assert!(mojo_code.contains("    s.remove(3)\n"));
+    assert!(mojo_code.contains("    q.append(r)\n"));
+    assert!(!mojo_code.contains("extend"));
+}
+
+#[test]
+fn test_list_mutation_rewrites_skip_user_types() {
+    let rust_code = r#"
+        struct Stack {
+            items: Vec<i32>,
+        }
+
+        fn churn(s: &mut Stack) {
+            s.push(1);
+            let top = s.pop();
+            s.truncate(0);
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("    s.push(1)\n"));
+    assert!(mojo_code.contains("    let top = s.pop()\n"));
+    assert!(mojo_code.contains("    s.truncate(0)\n"));
+    assert!(!mojo_code.contains("Optional"));
+}
+
+#[test]
+fn test_attributes_are_preserved() {
+    let unit = parse_rust_code("#[inline] fn f() {}").unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+
+    assert_eq!(func.attributes.len(), 1);
+    assert_eq!(func.attributes[0].path, "inline");
+    assert!(func.attributes[0].tokens.is_empty());
+
+    let unit = parse_rust_code("/// A point\n#[derive(Debug, Clone)] struct P;").unwrap();
+    let Item::Struct(struct_item) = &unit.items[0] else {
+        panic!("expected a struct");
+    };
+
+    let paths: Vec<&str> = struct_item
+        .attributes
+        .iter()
+        .map(|attr| attr.path.as_str())
+        .collect();
+    assert_eq!(paths, ["doc", "derive"]);
+    assert_eq!(struct_item.attributes[1].tokens, "Debug , Clone");
+}
+
+#[test]
+fn test_module_items_are_converted() {
+    let rust_code = r#"
+        mod geometry {
+            fn area(w: i32, h: i32) -> i32 {
+                w * h
+            }
+
+            mod units {
+                const SCALE: i32 = 10;
+            }
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("# mod geometry\nfn geometry_area(w: Int32, h: Int32) -> Int32:"));
+    assert!(mojo_code.contains("# mod units\nalias geometry_units_SCALE = 10"));
+    assert_eq!(mojo_code.matches("fn geometry_area").count(), 1);
+}
+
+#[test]
+fn test_impl_inside_module_is_attached_to_its_struct() {
+    let rust_code = r#"
+        mod shapes {
+            pub struct Point {
+                x: i32,
+            }
+
+            impl Point {
+                pub fn new(x: i32) -> Point {
+                    Point { x }
+                }
+            }
+        }
+
+        fn main() {
+            let p = shapes::Point::new(1);
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains(
+        "        self.x = x\n\n    @staticmethod\n    fn new(x: Int32) -> shapes_Point:\n        return shapes_Point(x=x)\n"
+    ));
+    assert!(mojo_code.contains("let p = shapes_Point.new(1)"));
+    assert!(!mojo_code.contains("TODO"));
+}
+
+#[test]
+fn test_same_named_items_in_different_modules_do_not_collide() {
+    let rust_code = r#"
+        mod parsing {
+            pub fn helper() -> i32 { 1 }
+            pub fn run() -> i32 { helper() }
+        }
+
+        mod printing {
+            pub fn helper() -> i32 { 2 }
+            pub fn run() -> i32 { self::helper() + super::parsing::helper() }
+        }
+
+        fn main() {
+            let total = parsing::helper() + printing::run();
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("fn parsing_helper() -> Int32:"));
+    assert!(mojo_code.contains("fn printing_helper() -> Int32:"));
+    assert!(mojo_code.contains("return parsing_helper()\n"));
+    assert!(mojo_code.contains("return printing_helper() + parsing_helper()\n"));
+    assert!(mojo_code.contains("let total = parsing_helper() + printing_run()"));
+
+    let config = CompilerConfig {
+        module_naming: ModuleNaming::Unprefixed,
+        ..CompilerConfig::default()
+    };
+    let mojo_code = Compiler::with_config(config)
+        .compile_str(rust_code)
+        .unwrap();
+    assert_eq!(mojo_code.matches("fn helper() -> Int32:").count(), 2);
+}
+
+#[test]
+fn test_external_module_is_unsupported() {
+    let compiler = Compiler::new();
+    let result = compiler.compile_str("mod elsewhere;");
+
+    assert!(matches!(
+        result,
+        Err(rust2mojo::Error::UnsupportedFeature { .. })
+    ));
+}
+
+#[test]
+fn test_unsupported_items_inside_modules_are_reported() {
+    let rust_code = "mod m {\n    union U { a: u32, b: f32 }\n    fn f() {}\n}";
+
+    let (mojo_code, warnings) = Compiler::new()
+        .compile_str_with_warnings(rust_code)
+        .unwrap();
+    assert!(mojo_code.contains("fn m_f():"));
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].message, "skipped unsupported union item");
+    assert_eq!(
+        warnings[0].location,
+        Some(SourceLocation { line: 2, column: 5 })
+    );
+
+    let report = Compiler::new().coverage(rust_code).unwrap();
+    assert_eq!(report.unsupported, ["union item"]);
+
+    let strict = Compiler::with_options(CompilerOptions::builder().strict(true).build());
+    assert!(matches!(
+        strict.compile_str(rust_code),
+        Err(Error::UnsupportedFeature { feature, .. }) if feature == "union item"
+    ));
+}
+
+#[test]
+fn test_nested_generic_types_translate() {
+    let rust_code = r#"
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        fn index(points: HashMap<String, Vec<Point>>) {}
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("fn index(owned points: Dict[String, List[Point]]):"));
+}
+
+#[test]
+fn test_enum_variant_payloads_are_converted() {
+    let rust_code = r#"
+        enum Message {
+            Quit,
+            Move { x: i32, y: i32 },
+            Write(String),
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Enum(enum_item) = &unit.items[0] else {
+        panic!("expected an enum");
+    };
+
+    assert_eq!(enum_item.variants[0].data, VariantData::Unit);
+
+    let VariantData::Struct(fields) = &enum_item.variants[1].data else {
+        panic!("expected a struct variant");
+    };
+    let fields: Vec<(&str, &Type)> = fields
+        .iter()
+        .map(|field| (field.name.as_str(), &field.type_))
+        .collect();
+    let int = Type::Path("i32".to_string());
+    assert_eq!(fields, [("x", &int), ("y", &int)]);
+
+    assert_eq!(
+        enum_item.variants[2].data,
+        VariantData::Tuple(vec![Type::Path("String".to_string())])
+    );
+}
+
+#[test]
+fn test_test_functions_are_skipped_by_default() {
+    let rust_code = r#"
+        fn add(a: i32, b: i32) -> i32 {
+            a + b
+        }
+
+        #[test]
+        fn check_add() {
+            add(1, 2);
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("fn add(a: Int32, b: Int32) -> Int32:"));
+    assert!(!mojo_code.contains("check_add"));
+}
+
+#[test]
+fn test_test_functions_are_marked_when_included() {
+    let rust_code = r#"
+        #[test]
+        fn check_add() {
+            add(1, 2);
+        }
+    "#;
+
+    let config = CompilerConfig {
+        skip_tests: false,
+        ..CompilerConfig::default()
+    };
+    let compiler = Compiler::with_config(config);
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("# test\nfn check_add():"));
+}
+
+#[test]
+fn test_let_type_annotations_are_captured() {
+    let unit = parse_rust_code("fn f() { let mut x: i32 = 42; let y = 1; }").unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+
+    let Statement::Let {
+        name,
+        mutable,
+        type_,
+        ..
+    } = &func.body[0]
+    else {
+        panic!("expected a let binding");
+    };
+    assert_eq!(name, "x");
+    assert!(mutable);
+    assert_eq!(type_, &Some(Type::Path("i32".to_string())));
+
+    let Statement::Let { name, type_, .. } = &func.body[1] else {
+        panic!("expected a let binding");
+    };
+    assert_eq!(name, "y");
+    assert_eq!(type_, &None);
+}
+
+#[test]
+fn test_if_let_else_if_let_chain() {
+    let rust_code = r#"
+        fn pick(a: Option<i32>, b: Option<i32>) {
+            if let Some(x) = a {
+                print(x);
+            } else if let Some(y) = b {
+                print(y);
+            } else {
+                print(0);
+            }
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains(
+        "    if a:\n        var x = a.value()\n        print(x)\n    else:\n        if b:\n            var y = b.value()\n            print(y)\n        else:\n            print(0)\n"
+    ));
+}
+
+#[test]
+fn test_explicit_none_return() {
+    let rust_code = r#"
+        fn log(x: i32) {
+            print(x);
+        }
+
+        fn double(x: i32) -> i32 {
+            x * 2
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("fn log(x: Int32):"));
+
+    let config = CompilerConfig {
+        explicit_none_return: true,
+        ..CompilerConfig::default()
+    };
+    let mojo_code = Compiler::with_config(config)
+        .compile_str(rust_code)
+        .unwrap();
+    assert!(mojo_code.contains("fn log(x: Int32) -> None:"));
+    assert!(mojo_code.contains("fn double(x: Int32) -> Int32:"));
+}
+
+#[test]
+fn test_method_receivers_are_converted() {
+    let rust_code = r#"
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        impl Point {
+            fn norm(&self) -> i32 {
+                self.x
+            }
+
+            fn shift(&mut self, dx: i32) {}
+
+            fn into_x(self) -> i32 {
+                self.x
+            }
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Impl(impl_item) = &unit.items[1] else {
+        panic!("expected an impl");
+    };
+    let receivers: Vec<&Parameter> = impl_item
+        .items
+        .iter()
+        .map(|item| match item {
+            ImplItem::Function(func) => &func.parameters[0],
+            _ => panic!("expected a method"),
+        })
+        .collect();
+
+    let self_type = Type::Path("Self".to_string());
+    for (param, mutable) in receivers.iter().zip([false, true]) {
+        assert_eq!(param.name, "self");
+        assert_eq!(
+            param.type_,
+            Type::Reference {
+                mutable,
+                lifetime: None,
+                inner: Box::new(self_type.clone()),
+            }
+        );
+    }
+    assert_eq!(receivers[2].name, "self");
+    assert_eq!(receivers[2].type_, self_type);
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("fn norm(self) -> Int32:"));
+    assert!(mojo_code.contains("fn shift(inout self, dx: Int32):"));
+    assert!(mojo_code.contains("fn into_x(owned self) -> Int32:"));
+}
+
+#[test]
+fn test_for_loop_tuple_pattern_binds_both_names() {
+    let rust_code = r#"
+        fn dump(map: Vec<(i32, i32)>) {
+            for (k, v) in map {
+                print(k + v);
+            }
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains(
+        "    for item in map:\n        var k = item[0]\n        var v = item[1]\n        print(k + v)\n"
+    ));
+}
+
+#[test]
+fn test_for_loop_over_a_hash_map_walks_its_entries() {
+    let rust_code = r#"
+        use std::collections::HashMap;
+
+        fn dump(map: HashMap<i32, i32>) {
+            for (k, v) in &map {
+                print(k + v);
+            }
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains(
+        "    for item in map.items():\n        var k = item[].key\n        var v = item[].value\n        print(k + v)\n"
+    ));
+}
+
+#[test]
+fn test_associated_functions_are_static_methods() {
+    let rust_code = r#"
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        impl Point {
+            fn new(x: i32, y: i32) -> Self {
+                Point { x, y }
+            }
+
+            fn sum(&self) -> i32 {
+                self.x + self.y
+            }
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("    @staticmethod\n    fn new(x: Int32, y: Int32) -> Self:"));
+    assert!(!mojo_code.contains("@staticmethod\n    fn sum"));
+}
+
+#[test]
+fn test_print_macros_become_print_calls() {
+    let rust_code = r#"
+        fn greet(name: String) {
+            println!("Hello");
+            println!("Hello, {}!", name);
+            print!("no newline");
+            eprintln!("oops");
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("    print(\"Hello\")\n"));
+    assert!(mojo_code.contains("    print(\"Hello, \" + String(name) + \"!\")\n"));
+    assert!(mojo_code.contains("    print(\"no newline\", end=\"\")\n"));
+    assert!(mojo_code.contains("    print(\"oops\", file=2)\n"));
+}
+
+#[test]
+fn test_if_expression_as_value() {
+    let rust_code = r#"
+        fn sign(n: i32) {
+            let s = if n < 0 { 2 } else if n == 0 { 0 } else { 1 };
+            let t = if n > 10 {
+                let d = n - 10;
+                d * 2
+            } else {
+                n
+            };
+            let u = if n > 0 { log(n) };
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("let s = 2 if n < 0 else 0 if n == 0 else 1"));
+    assert!(mojo_code.contains(
+        "    var t: Int32\n    if n > 10:\n        let d = n - 10\n        t = d * 2\n    else:\n        t = n\n"
+    ));
+    assert!(mojo_code.contains("    else:\n        u = None\n"));
+}
+
+#[test]
+fn test_string_concatenation() {
+    let rust_code = r#"
+        fn label(b: String, n: i32) {
+            let s = "a".to_string() + &b + &n.to_string();
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("let s = String(\"a\") + b + String(n)"));
+}
+
+#[test]
+fn test_mut_ref_getter_gets_borrow_review_warning() {
+    let rust_code = r#"
+        struct Counter {
+            count: i32,
+        }
+
+        impl Counter {
+            fn count_mut(&mut self) -> &mut i32 {
+                &mut self.count
+            }
+
+            fn count(&self) -> &i32 {
+                &self.count
+            }
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains(
+        "    # WARNING: returns a mutable reference into self; borrow semantics may not carry over, review manually\n    fn count_mut(inout self) -> Int32:"
+    ));
+    assert_eq!(mojo_code.matches("# WARNING").count(), 1);
+}
+
+#[test]
+fn test_range_expressions() {
+    let rust_code = r#"
+        fn count(n: i32) {
+            for i in 0..10 {
+                print(i);
+            }
+            for j in 0..=10 {
+                print(j);
+            }
+            let r = ..n;
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    let Statement::For { iterator, .. } = &func.body[1] else {
+        panic!("expected a for loop");
+    };
+    assert_eq!(
+        iterator,
+        &Expression::Range {
+            start: Some(Box::new(Expression::Literal(Literal::Integer(0)))),
+            end: Some(Box::new(Expression::Literal(Literal::Integer(10)))),
+            inclusive: true,
+        }
+    );
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("for i in range(0, 10):"));
+    assert!(mojo_code.contains("for j in range(0, 10 + 1):"));
+    assert!(mojo_code.contains("let r = range(n)"));
+}
+
+#[test]
+fn test_unary_expressions() {
+    let rust_code = r#"
+        fn f(x: i32) -> i32 {
+            -x
+        }
+
+        fn g(b: bool) -> bool {
+            !b
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let operators: Vec<&UnaryOp> = unit
+        .items
+        .iter()
+        .map(|item| match item {
+            Item::Function(func) => match &func.body[..] {
+                [Statement::Expression(Expression::Unary { op, operand })] => {
+                    assert!(matches!(**operand, Expression::Identifier(_)));
+                    op
+                }
+                body => panic!("expected a unary expression, got {:?}", body),
+            },
+            _ => panic!("expected a function"),
+        })
+        .collect();
+    assert_eq!(operators, [&UnaryOp::Neg, &UnaryOp::Not]);
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("-x"));
+    assert!(mojo_code.contains("not b"));
+}
+
+#[test]
+fn test_lifetime_generics_are_dropped() {
+    let rust_code = "fn f<'a, T: 'a + Clone>(x: &'a T) {}";
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+
+    assert_eq!(func.generics.len(), 1);
+    assert_eq!(func.generics[0].name, "T");
+    assert_eq!(
+        func.generics[0].bounds,
+        vec![Type::Path("Clone".to_string())]
+    );
+
+    let (mojo_code, warnings) = Compiler::new()
+        .compile_str_with_warnings(rust_code)
+        .unwrap();
+    assert!(!mojo_code.contains("'a"));
+    assert!(warnings
+        .iter()
+        .any(|warning| warning.message == "dropped lifetime bound `'a` on `T`"));
+}
+
+#[test]
+fn test_index_expressions() {
+    let unit = parse_rust_code("fn f(a: &[i32]) -> i32 { a[0] }").unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    assert_eq!(
+        func.body,
+        vec![Statement::Expression(Expression::Index {
+            object: Box::new(Expression::Identifier("a".to_string())),
+            index: Box::new(Expression::Literal(Literal::Integer(0))),
+        })]
+    );
+
+    let rust_code = r#"
+        fn g(grid: Vec<Vec<i32>>, i: usize, j: usize) {
+            let cell = grid[i][j];
+            let row = grid[1..];
+        }
+    "#;
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("let cell = grid[i][j]"));
+    assert!(mojo_code.contains("let row = grid[1:]"));
+}
+
+#[test]
+fn test_enum_state_machine() {
+    let rust_code = r#"
+        enum State {
+            Idle,
+            Running,
+            Done,
+        }
+
+        impl State {
+            fn step(self) -> State {
+                match self {
+                    State::Idle => State::Running,
+                    State::Running => State::Done,
+                    State::Done => State::Done,
+                }
+            }
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("@value\nstruct State(EqualityComparable):\n    var _tag: Int\n"));
+    assert!(mojo_code.contains(
+        "    alias Idle = Self(0)\n    alias Running = Self(1)\n    alias Done = Self(2)\n"
+    ));
+    assert!(mojo_code.contains("    fn __eq__(self, other: Self) -> Bool:"));
+    assert!(mojo_code.contains(
+        "    fn step(owned self) -> State:\n        if self == State.Idle:\n            return State.Running\n        elif self == State.Running:\n            return State.Done\n        else:\n            return State.Done\n"
+    ));
+    assert!(!mojo_code.contains("TODO"));
+}
+
+#[test]
+fn test_tuple_index_access() {
+    let rust_code = "fn f(t: (i32, i32)) -> i32 { t.0 }";
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    assert_eq!(
+        func.body,
+        vec![Statement::Expression(Expression::FieldAccess {
+            object: Box::new(Expression::Identifier("t".to_string())),
+            field: "0".to_string(),
+        })]
+    );
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("fn f(owned t: Tuple[Int32, Int32]) -> Int32:\n    return t[0]\n"));
+}
+
+#[test]
+fn test_field_access_nodes() {
+    let rust_code = r#"
+        struct Point {
+            x: i32,
+        }
+
+        impl Point {
+            fn get(&self) -> i32 {
+                self.x
+            }
+
+            fn nested(&self) -> i32 {
+                self.inner.value
+            }
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Impl(impl_item) = &unit.items[1] else {
+        panic!("expected an impl");
+    };
+    let bodies: Vec<&Vec<Statement>> = impl_item
+        .items
+        .iter()
+        .map(|item| match item {
+            ImplItem::Function(func) => &func.body,
+            _ => panic!("expected a method"),
+        })
+        .collect();
+
+    let self_x = Expression::FieldAccess {
+        object: Box::new(Expression::Identifier("self".to_string())),
+        field: "x".to_string(),
+    };
+    assert_eq!(bodies[0], &vec![Statement::Expression(self_x)]);
+
+    let Statement::Expression(Expression::FieldAccess { object, field }) = &bodies[1][0] else {
+        panic!("expected a field access");
+    };
+    assert_eq!(field, "value");
+    assert!(matches!(&**object, Expression::FieldAccess { field, .. } if field == "inner"));
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("return self.inner.value"));
+}
+
+#[test]
+fn test_struct_literal_expressions() {
+    let rust_code = r#"
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        fn origin(y: i32) -> Point {
+            Point { x: 0, y }
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[1] else {
+        panic!("expected a function");
+    };
+    let Statement::Expression(Expression::Struct { name, fields }) = &func.body[0] else {
+        panic!("expected a struct literal");
+    };
+    assert_eq!(name, "Point");
+    assert_eq!(
+        fields,
+        &vec![
+            ("x".to_string(), Expression::Literal(Literal::Integer(0))),
+            ("y".to_string(), Expression::Identifier("y".to_string())),
+        ]
+    );
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("return Point(x=0, y=y)"));
+}
+
+#[test]
+fn test_array_and_tuple_literals() {
+    let rust_code = r#"
+        fn literals() {
+            let a = [1, 2, 3];
+            let z = [0; 8];
+            let t = (1, 2.5, true);
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    let values: Vec<&Expression> = func
+        .body
+        .iter()
+        .map(|stmt| match stmt {
+            Statement::Let {
+                value: Some(value), ..
+            } => value,
+            _ => panic!("expected an initialized let"),
+        })
+        .collect();
+
+    assert!(matches!(values[0], Expression::Array(elements) if elements.len() == 3));
+    assert_eq!(
+        values[1],
+        &Expression::Repeat {
+            value: Box::new(Expression::Literal(Literal::Integer(0))),
+            count: Box::new(Expression::Literal(Literal::Integer(8))),
+        }
+    );
+    assert!(matches!(values[2], Expression::Tuple(elements) if elements.len() == 3));
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("let t = (1, 2.5, True)"));
+}
+
+#[test]
+fn test_reference_expressions() {
+    let rust_code = r#"
+        fn refs(x: i32, p1: Point, p2: Point) {
+            let r = &x;
+            let m = &mut x;
+            let d = p1.distance(&p2);
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    let values: Vec<&Expression> = func
+        .body
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::Let { value, .. } => value.as_ref(),
+            _ => None,
+        })
+        .collect();
+
+    let x = Box::new(Expression::Identifier("x".to_string()));
+    assert_eq!(
+        values[0],
+        &Expression::Reference {
+            mutable: false,
+            expr: x.clone(),
+        }
+    );
+    assert_eq!(
+        values[1],
+        &Expression::Reference {
+            mutable: true,
+            expr: x,
+        }
+    );
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("let d = p1.distance(p2)"));
+}
+
+#[test]
+fn test_numeric_match_with_wildcard() {
+    let rust_code = r#"
+        fn classify(n: i32) -> i32 {
+            match n {
+                0 => 10,
+                x if x > 100 => 20,
+                _ => 30,
+            }
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    let Statement::Match { expr, arms } = &func.body[0] else {
+        panic!("expected a match");
+    };
+    assert_eq!(expr, &Expression::Identifier("n".to_string()));
+    assert_eq!(arms.len(), 3);
+    assert_eq!(arms[0].pattern, Pattern::Literal(Literal::Integer(0)));
+    assert_eq!(arms[1].pattern, Pattern::Identifier("x".to_string()));
+    assert!(arms[1].guard.is_some());
+    assert_eq!(arms[2].pattern, Pattern::Wildcard);
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains(
+        "    if n == 0:\n        return 10\n    elif n > 100:\n        var x = n\n        return 20\n    else:\n        return 30\n"
+    ));
+}
+
+#[test]
+fn test_option_match_binds_payload() {
+    let rust_code = r#"
+        fn unwrap_or_zero(v: Option<i32>) -> i32 {
+            match v {
+                Option::Some(x) => x,
+                Option::None => 0,
+            }
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    let Statement::Match { arms, .. } = &func.body[0] else {
+        panic!("expected a match");
+    };
+    assert_eq!(
+        arms[0].pattern,
+        Pattern::Enum {
+            path: "Option".to_string(),
+            variant: "Some".to_string(),
+            fields: vec![Pattern::Identifier("x".to_string())],
+        }
+    );
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains(
+        "    if v:\n        var x = v.value()\n        return x\n    else:\n        return 0\n"
+    ));
+}
+
+#[test]
+fn test_infinite_loop() {
+    let rust_code = "fn f() { loop { g(); } }";
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    assert!(matches!(&func.body[..], [Statement::Loop { body, label: None }] if body.len() == 1));
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("    while True:\n        g()\n"));
+}
+
+#[test]
+fn test_break_and_continue() {
+    let rust_code = r#"
+        fn scan() {
+            'outer: loop {
+                loop {
+                    break 'outer;
+                }
+                continue;
+                break;
+            }
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    let [Statement::Loop { body, .. }] = &func.body[..] else {
+        panic!("expected a loop");
+    };
+    assert_eq!(
+        body[0],
+        Statement::Loop {
+            body: vec![Statement::Break(Some("outer".to_string()))],
+            label: None,
+        }
+    );
+    assert_eq!(body[1], Statement::Continue(None));
+    assert_eq!(body[2], Statement::Break(None));
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("        continue\n        break\n"));
+}
+
+#[test]
+fn test_labeled_loops() {
+    let rust_code = r#"
+        fn find(grid: Vec<Vec<i32>>) {
+            'outer: for row in grid {
+                for cell in row {
+                    if cell == 0 {
+                        continue 'outer;
+                    }
+                    if cell < 0 {
+                        break 'outer;
+                    }
+                }
+            }
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    let [Statement::For { label, body, .. }] = &func.body[..] else {
+        panic!("expected a for loop");
+    };
+    assert_eq!(label.as_deref(), Some("outer"));
+    assert!(matches!(&body[..], [Statement::For { label: None, .. }]));
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("var _break_outer = False"));
+    assert!(mojo_code.contains("var _continue_outer = False"));
+    assert!(mojo_code.contains("for row in grid:  # 'outer"));
+    assert!(mojo_code.contains("_continue_outer = True\n"));
+    assert!(mojo_code.contains("_break_outer = True\n"));
+    assert!(mojo_code.contains("if _break_outer:\n            break"));
+    assert!(mojo_code.contains("if _continue_outer:\n            continue"));
+}
+
+#[test]
+fn test_closure_expression() {
+    let rust_code = r#"
+        fn make() {
+            let double = |x: i32| x * 2;
+            let add = |a, b| a + b;
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+
+    let Statement::Let {
+        value: Some(Expression::Closure { params, body }),
+        ..
+    } = &func.body[0]
+    else {
+        panic!("expected a closure");
+    };
+    assert_eq!(
+        params,
+        &vec![Parameter {
+            name: "x".to_string(),
+            type_: Type::Path("i32".to_string()),
+            mutable: false,
+        }]
+    );
+    assert!(matches!(**body, Expression::Binary { .. }));
+
+    let Statement::Let {
+        value: Some(Expression::Closure { params, .. }),
+        ..
+    } = &func.body[1]
+    else {
+        panic!("expected a closure");
+    };
+    assert_eq!(params.len(), 2);
+    assert_eq!(params[0].type_, Type::Path("_".to_string()));
+}
+
+#[test]
+fn test_simple_trait() {
+    let rust_code = r#"
+        trait Shape {
+            fn area(&self) -> f64;
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let [Item::Trait(trait_item)] = &unit.items[..] else {
+        panic!("expected a trait");
+    };
+    assert_eq!(trait_item.name, "Shape");
+    let [TraitItem::Function(func)] = &trait_item.items[..] else {
+        panic!("expected a single method");
+    };
+    assert_eq!(func.name, "area");
+    assert_eq!(func.return_type, Some(Type::Path("f64".to_string())));
+    assert!(func.default_body.is_none());
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("trait Shape:\n    fn area(self) -> Float64:\n        ...\n"));
+}
+
+#[test]
+fn test_trait_with_default_method() {
+    let rust_code = r#"
+        trait Greet {
+            fn name(&self) -> String;
+            fn greet(&self) -> String {
+                "Hello".to_string()
+            }
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let [Item::Trait(trait_item)] = &unit.items[..] else {
+        panic!("expected a trait");
+    };
+    let [TraitItem::Function(name), TraitItem::Function(greet)] = &trait_item.items[..] else {
+        panic!("expected two methods");
+    };
+    assert!(name.default_body.is_none());
+    assert_eq!(greet.default_body.as_ref().map(Vec::len), Some(1));
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("default implementation omitted"));
+    assert!(mojo_code.contains("fn greet(self) -> String:\n        ...\n"));
+}
+
+#[test]
+fn test_trait_with_associated_type() {
+    let rust_code = r#"
+        trait Container {
+            type Item: Clone;
+            fn first(&self) -> Option<Self::Item>;
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let [Item::Trait(trait_item)] = &unit.items[..] else {
+        panic!("expected a trait");
+    };
+    assert_eq!(
+        trait_item.items[0],
+        TraitItem::Type {
+            name: "Item".to_string(),
+            bounds: vec![Type::Path("Clone".to_string())],
+        }
+    );
+    assert!(matches!(&trait_item.items[1], TraitItem::Function(func) if func.name == "first"));
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("associated type `Item`"));
+}
+
+#[test]
+fn test_compiler_options_target_version() {
+    let options = CompilerOptions::builder()
+        .target_mojo_version("24.4")
+        .build();
+    assert!(options.emit_header);
+    assert!(!options.strict);
+
+    let compiler = Compiler::with_options(options);
+    assert_eq!(compiler.options().target_mojo_version, "24.4");
+    let mojo_code = compiler.compile_str("fn f() {}").unwrap();
+    assert!(mojo_code.contains("# Target: Mojo 24.4\n"));
+
+    let mojo_code = Compiler::new().compile_str("fn f() {}").unwrap();
+    assert!(mojo_code.contains("# Target: Mojo 24.5\n"));
+}
+
+#[test]
+fn test_compiler_options_without_header() {
+    let compiler = Compiler::with_options(CompilerOptions::builder().emit_header(false).build());
+    let mojo_code = compiler.compile_str("fn f() {}").unwrap();
+    assert!(!mojo_code.contains("# Generated Mojo code"));
+    assert!(!mojo_code.contains("# Target: Mojo"));
+    assert!(mojo_code.contains("fn f():"));
+}
+
+#[test]
+fn test_compiler_combines_config_and_options() {
+    let config = CompilerConfig {
+        module_naming: ModuleNaming::Unprefixed,
+        ..CompilerConfig::default()
+    };
+    let options = CompilerOptions::builder()
+        .emit_header(false)
+        .strict(true)
+        .build();
+    let compiler = Compiler::new_with(config, options);
+    assert_eq!(compiler.config().module_naming, ModuleNaming::Unprefixed);
+    assert!(compiler.options().strict);
+
+    let mojo_code = compiler.compile_str("mod m { fn f() {} }").unwrap();
+    assert_eq!(mojo_code, "# mod m\nfn f():\n    pass\n\n");
+    assert!(compiler
+        .compile_str("fn g() { let c = |x: i32| x; }")
+        .is_err());
+}
+
+#[test]
+fn test_strict_mode_rejects_closure() {
+    let rust_code = r#"
+        fn make() {
+            let double = |x: i32| x * 2;
+        }
+    "#;
+
+    let lenient = Compiler::new().compile_str(rust_code);
+    assert!(lenient.is_ok());
+
+    let strict = Compiler::with_options(CompilerOptions::builder().strict(true).build());
+    let err = strict.compile_str(rust_code).unwrap_err();
+    assert!(
+        matches!(err, Error::UnsupportedFeature { feature: ref construct, .. } if construct == "closure")
+    );
+}
+
+#[test]
+fn test_strict_mode_rejects_placeholders_and_skipped_items() {
+    let strict = Compiler::with_options(CompilerOptions::builder().strict(true).build());
+
+    let err = strict
+        .compile_str("fn f() -> i32 { let v = async { 1 }; 0 }")
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::UnsupportedFeature { feature: ref construct, .. } if construct.contains("async"))
+    );
+
+    let err = strict
+        .compile_str("union Bits { i: u32, f: f32 }")
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::UnsupportedFeature { feature: ref construct, .. } if construct == "union item")
+    );
+
+    assert!(Compiler::new()
+        .compile_str("union Bits { i: u32, f: f32 }")
+        .is_ok());
+    assert!(strict
+        .compile_str("fn add(a: i32, b: i32) -> i32 { a + b }")
+        .is_ok());
+}
+
+#[test]
+fn test_strict_mode_rejects_statement_macros() {
+    let rust_code = r#"
+        fn check(a: i32, b: i32) {
+            assert_eq!(a, b);
+        }
+    "#;
+
+    assert!(Compiler::new().compile_str(rust_code).is_ok());
+
+    let strict = Compiler::with_options(CompilerOptions::builder().strict(true).build());
+    let err = strict.compile_str(rust_code).unwrap_err();
+    assert!(
+        matches!(err, Error::UnsupportedFeature { feature: ref construct, .. } if construct == "macro `assert_eq!`")
+    );
+    assert!(strict
+        .compile_str("fn f(a: i32) { println!(\"{}\", a); panic!(\"no\"); }")
+        .is_ok());
+}
+
+#[test]
+fn test_closure_produces_warning() {
+    let rust_code = r#"
+        fn make() {
+            let double = |x: i32| x * 2;
+        }
+    "#;
+
+    let (mojo_code, warnings) = Compiler::new()
+        .compile_str_with_warnings(rust_code)
+        .unwrap();
+    assert!(mojo_code.contains("fn make"));
+    assert!(warnings
+        .iter()
+        .any(|warning| warning.message.contains("closure")));
+}
+
+#[test]
+fn test_skipped_items_and_dropped_attributes_produce_warnings() {
+    let rust_code = "fn a() {}\n\nunion Bits { i: u32, f: f32 }\n\n#[inline]\nfn b() {}\n";
+
+    let (_, warnings) = Compiler::new()
+        .compile_str_with_warnings(rust_code)
+        .unwrap();
+
+    let skipped = warnings
+        .iter()
+        .find(|warning| warning.message.contains("union"))
+        .expect("skipped union should warn");
+    assert_eq!(skipped.location.map(|location| location.line), Some(3));
+
+    assert!(warnings
+        .iter()
+        .any(|warning| warning.message.contains("#[inline]")));
+
+    let (_, warnings) = Compiler::new()
+        .compile_str_with_warnings("/// Docs\n#[derive(Clone)]\nstruct P { x: i32 }")
+        .unwrap();
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_dropped_macros_produce_located_warnings() {
+    let rust_code = "fn check(a: i32, b: i32) {\n    assert_eq!(a, b);\n}\n\n#[test]\nfn skipped() {\n    assert!(true);\n}\n";
+
+    let (_, warnings) = Compiler::new()
+        .compile_str_with_warnings(rust_code)
+        .unwrap();
+
+    let dropped: Vec<_> = warnings
+        .iter()
+        .filter(|warning| warning.message.contains("macro"))
+        .collect();
+    // The test function is not emitted, so its `assert!` is not reported
+    assert_eq!(dropped.len(), 1);
+    assert!(dropped[0].message.contains("`assert_eq!`"));
+    assert_eq!(
+        dropped[0].location,
+        Some(SourceLocation { line: 2, column: 5 })
+    );
+
+    let strict = Compiler::with_options(CompilerOptions::builder().strict(true).build());
+    let err = strict.compile_str(rust_code).unwrap_err();
+    assert_eq!(err.location().map(|location| location.line), Some(2));
+}
+
+#[test]
+fn test_parse_error_reports_location() {
+    let rust_code = "fn ok() {}\n\nfn broken( {\n    1 +\n}\n";
+
+    let err = Compiler::new().compile_str(rust_code).unwrap_err();
+    assert!(matches!(err, Error::ParseError { .. }));
+    let location = err
+        .location()
+        .expect("parse errors should carry a location");
+    assert!(location.line > 0);
+    assert_eq!(location.line, 3);
+}
+
+#[test]
+fn test_unsupported_feature_reports_location() {
+    let err = Compiler::new()
+        .compile_str("fn a() {}\nmod elsewhere;\n")
+        .unwrap_err();
+    assert_eq!(err.location().map(|location| location.line), Some(2));
+}
+
+#[test]
+fn test_ast_json_round_trips() {
+    let rust_code = r#"
+        struct Point { x: i32, y: i32 }
+
+        fn origin() -> Point {
+            Point { x: 0, y: 0 }
+        }
+    "#;
+
+    let compiler = Compiler::new();
+    let json = compiler.ast_json(rust_code).unwrap();
+    let unit: CompilationUnit = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(unit, compiler.parse(rust_code).unwrap());
+    assert!(matches!(
+        &unit.items[..],
+        [Item::Struct(_), Item::Function(_)]
+    ));
+}
+
+#[test]
+fn test_compile_str_collect_reports_every_broken_item() {
+    let rust_code = r#"fn first() {
+    let x = ;
+}
+
+fn ok() -> &'static str { "}" }
+
+fn second() {
+    let = 5;
+}
+"#;
+
+    let compiler = Compiler::new();
+    assert!(compiler.compile_str(rust_code).is_err());
+
+    let (mojo_code, errors) = compiler.compile_str_collect(rust_code);
+    assert_eq!(errors.len(), 2);
+    assert!(errors
+        .iter()
+        .all(|err| matches!(err, Error::ParseError { .. })));
+    let lines: Vec<_> = errors
+        .iter()
+        .map(|err| err.location().map(|location| location.line))
+        .collect();
+    assert_eq!(lines, vec![Some(2), Some(8)]);
+
+    let mojo_code = mojo_code.unwrap();
+    assert!(mojo_code.contains("fn ok()"));
+    assert!(!mojo_code.contains("fn first"));
+}
+
+#[test]
+fn test_compile_str_collect_survives_multibyte_escapes() {
+    let rust_code = "fn first() {\n    let c = '\\\u{e9}';\n}\n\nfn ok() -> char { '\\n' }\n";
+
+    let (mojo_code, errors) = Compiler::new().compile_str_collect(rust_code);
+    assert_eq!(errors.len(), 1);
+    assert!(mojo_code.unwrap().contains("fn ok()"));
+}
+
+#[test]
+fn test_compile_str_collect_matches_compile_str_on_valid_input() {
+    let rust_code = "struct P { x: i32 }\nfn f(p: P) -> i32 { p.x }";
+    let compiler = Compiler::new();
+
+    let (mojo_code, errors) = compiler.compile_str_collect(rust_code);
+    assert!(errors.is_empty());
+    assert_eq!(mojo_code.unwrap(), compiler.compile_str(rust_code).unwrap());
+}
+
+#[test]
+fn test_compile_str_collect_gathers_strict_conversion_errors() {
+    let compiler = Compiler::with_options(CompilerOptions::builder().strict(true).build());
+    let (mojo_code, errors) =
+        compiler.compile_str_collect("union A { x: u32 }\nfn f() {}\nunion B { y: u32 }");
+
+    assert_eq!(errors.len(), 2);
+    assert!(mojo_code.unwrap().contains("fn f():"));
+}
+
+#[test]
+fn test_lifetimes_are_kept_out_of_type_generics() {
+    let rust_code = r#"
+        fn pick<'a>(x: &'a str) -> &'a str { x }
+
+        fn first<'a>(items: &'a Vec<i32>) -> &'a i32 { &items[0] }
+
+        fn longest<'a>(x: &'a Vec<i32>, y: &'a Vec<i32>) -> &'a Vec<i32> { x }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(pick) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    assert!(pick.generics.is_empty());
+    assert_eq!(pick.lifetimes, vec!["a".to_string()]);
+    assert!(matches!(
+        &pick.parameters[0].type_,
+        Type::Reference { lifetime: Some(lifetime), .. } if lifetime == "a"
+    ));
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("fn pick(x: StringRef) -> StringRef:\n"));
+    assert!(mojo_code
+        .contains("fn first(borrowed items: List[Int32]) -> ref [__lifetime_of(items)] Int32:\n"));
+    // Two possible origins can't be named in the signature, so the value is returned
+    assert!(mojo_code.contains(
+        "fn longest(borrowed x: List[Int32], borrowed y: List[Int32]) -> List[Int32]:\n"
+    ));
+    assert!(!mojo_code.contains('\''));
+}
+
+#[test]
+fn test_pointer_and_function_pointer_types() {
+    let rust_code = r#"
+        fn read(p: *const i32, out: *mut u8) {}
+
+        fn apply(f: fn(i32) -> i32, x: i32) -> i32 { f(x) }
+
+        fn each(callback: fn(&str)) {}
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(apply) = &unit.items[1] else {
+        panic!("expected a function");
+    };
+    assert_eq!(
+        apply.parameters[0].type_,
+        Type::Function {
+            params: vec![Type::Path("i32".to_string())],
+            return_: Box::new(Type::Path("i32".to_string())),
+        }
+    );
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("from memory import UnsafePointer\n"));
+    assert!(mojo_code.contains("fn read(p: UnsafePointer[Int32], out: UnsafePointer[UInt8]):"));
+    assert!(mojo_code.contains("fn apply(f: fn(Int32) -> Int32, x: Int32) -> Int32:"));
+    assert!(mojo_code.contains("fn each(callback: fn(StringRef) -> None):"));
+    assert!(!mojo_code.contains("TODO"));
+}
+
+#[test]
+fn test_array_and_slice_types() {
+    let rust_code = r#"
+        const N: usize = 5;
+
+        fn sum(values: [i32; 5]) -> i32 { 0 }
+
+        fn total(values: &[i32], scratch: &mut [u8]) -> i32 { values[0] }
+
+        fn padded(values: [i32; N]) {}
+    "#;
+
+    let (mojo_code, warnings) = Compiler::new()
+        .compile_str_with_warnings(rust_code)
+        .unwrap();
+    assert!(mojo_code.contains("from collections import InlineArray, List\n"));
+    assert!(mojo_code.contains("from utils import Span\n"));
+    assert!(mojo_code.contains("fn sum(owned values: InlineArray[Int32, 5]) -> Int32:"));
+    assert!(mojo_code.contains("fn total(values: Span[Int32], scratch: Span[UInt8]) -> Int32:"));
+    assert!(mojo_code.contains("fn padded(owned values: List[Int32]):"));
+    assert!(warnings.iter().any(|warning| warning
+        .message
+        .contains("array length is not an integer literal")));
+}
+
+#[test]
+fn test_compilation_unit_pretty_prints_as_a_tree() {
+    let rust_code = r#"
+        fn add(a: i32, b: i32) -> i32 {
+            let sum = a + b * 2;
+            sum
+        }
+
+        pub fn classify(n: i32) -> &'static str {
+            match n {
+                0 => "zero",
+                x if x < 0 => "negative",
+                _ => {
+                    for i in 0..n {
+                        println!("{}", i);
+                    }
+                    "positive"
+                }
+            }
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    assert_eq!(
+        unit.to_string(),
+        r#"fn add(a: i32, b: i32) -> i32
+  let sum = a + (b * 2)
+  sum
+pub fn classify(n: i32) -> &'static str
+  match n
+    0 =>
+      "zero"
+    x if x < 0 =>
+      "negative"
+    _ =>
+      for i in 0..n
+        print(String(i))
+      "positive"
+"#
+    );
+}
+
+#[test]
+fn test_operator_precedence_is_preserved() {
+    let compiler = Compiler::new();
+
+    let mojo_code = compiler
+        .compile_str("fn f() -> i32 { (1 + 2) * 3 }")
+        .unwrap();
+    assert!(mojo_code.contains("return (1 + 2) * 3"));
+
+    let rust_code = r#"
+fn g(a: i32, b: i32, c: i32, x: bool, y: bool) -> bool {
+    let p = 1 + 2 * 3;
+    let q = a - (b - c);
+    let r = (a - b) - c;
+    let s = (a < b) == x;
+    let t = !x == y;
+    let u = -(a + b);
+    x && (y || s)
+}
+"#;
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("let p = 1 + 2 * 3"));
+    assert!(mojo_code.contains("let q = a - (b - c)"));
+    assert!(mojo_code.contains("let r = a - b - c"));
+    assert!(mojo_code.contains("let s = (a < b) == x"));
+    assert!(mojo_code.contains("let t = (not x) == y"));
+    assert!(mojo_code.contains("let u = -(a + b)"));
+    assert!(mojo_code.contains("return x and (y or s)"));
+}
+
+#[test]
+fn test_binary_operators_are_never_replaced_by_addition() {
+    let compiler = Compiler::new();
+    let operators = [
+        ("+", "+"),
+        ("-", "-"),
+        ("*", "*"),
+        ("/", "/"),
+        ("%", "%"),
+        ("==", "=="),
+        ("!=", "!="),
+        ("<", "<"),
+        ("<=", "<="),
+        (">", ">"),
+        (">=", ">="),
+        ("&&", "and"),
+        ("||", "or"),
+        ("&", "&"),
+        ("|", "|"),
+        ("^", "^"),
+        ("<<", "<<"),
+        (">>", ">>"),
+    ];
+    for (rust_op, mojo_op) in operators {
+        let rust_code = format!("fn f(a: i32, b: i32) -> i32 {{ a {} b }}", rust_op);
+        let mojo_code = compiler.compile_str(&rust_code).unwrap();
+        assert!(
+            mojo_code.contains(&format!("return a {} b", mojo_op)),
+            "`{}` was not emitted as `{}`:\n{}",
+            rust_op,
+            mojo_op,
+            mojo_code
+        );
+
+        let compound = format!("{}=", rust_op);
+        if ["+", "-", "*", "/", "%", "&", "|", "^", "<<", ">>"].contains(&rust_op) {
+            let rust_code = format!("fn g(mut a: i32, b: i32) {{ a {} b; }}", compound);
+            let mojo_code = compiler.compile_str(&rust_code).unwrap();
+            assert!(mojo_code.contains(&format!("a {} b", compound)));
+        }
+    }
+}
+
+#[test]
+fn test_use_paths_become_mojo_imports() {
+    let rust_code = r#"
+use geometry::shapes::{Circle, Square, self};
+use crate::util::helpers::clamp as limit;
+use super::config::*;
+use std::collections::HashMap;
+
+fn f() {}
+"#;
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("from geometry.shapes import Circle, Square\n"));
+    assert!(mojo_code.contains("from geometry import shapes\n"));
+    assert!(mojo_code.contains("from util.helpers import clamp as limit\n"));
+    assert!(mojo_code.contains("from ..config import *\n"));
+    // std types are mapped to Mojo builtins where they are used instead
+    assert!(!mojo_code.contains("from std"));
+    assert!(!mojo_code.contains(" :: "));
+}
+
+#[test]
+fn test_std_conversion_methods_are_translated() {
+    let rust_code = r#"
+fn convert(x: i32, v: Vec<i32>, a: i32, b: i32) -> i32 {
+    let s = x.to_string();
+    let w = v.clone();
+    let t: String = "text".into();
+    let n = 2 * (a + b).clone();
+    n
+}
+"#;
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("let s = String(x)"));
+    assert!(mojo_code.contains("let w = v\n"));
+    assert!(mojo_code.contains("let t: String = \"text\""));
+    assert!(mojo_code.contains("let n = 2 * (a + b)"));
+    assert!(!mojo_code.contains(".clone()"));
+    assert!(!mojo_code.contains(".into()"));
+}
+
+#[test]
+fn test_result_returning_main_becomes_raising_main() {
+    let rust_code = r#"
+fn parse(text: &str) -> Result<i32, String> {
+    Ok(1)
+}
+
+fn main() -> Result<(), String> {
+    let value = parse("1")?;
+    if value < 0 {
+        return Err("negative".to_string());
+    }
+    println!("{}", value);
+    Ok(())
+}
+"#;
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("fn main() raises:\n"));
+    assert!(mojo_code.contains("        raise Error(String(\"negative\"))\n"));
+    assert!(mojo_code.contains(
+        "    var _try = parse(\"1\")\n    if _try.is_err():\n        raise Error(_try.err())\n    let value = _try.unwrap()\n"
+    ));
+    assert!(mojo_code.ends_with("    print(String(value))\n\n"));
+    assert!(!mojo_code.contains("Ok(None)"));
+    assert!(!mojo_code.contains("fn main() ->"));
+
+    // Only the free function is the entry point
+    let mojo_code = Compiler::new()
+        .compile_str("struct App;\nimpl App {\n    fn main(&self) -> i32 { 0 }\n}\nfn main() {}")
+        .unwrap();
+    assert!(mojo_code.contains("fn main(self) -> Int32:"));
+    assert!(mojo_code.contains("\nfn main():\n    pass"));
+}
+
+#[test]
+fn test_let_destructuring_binds_every_name() {
+    let rust_code = r#"
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn sum(p: Point) -> i32 {
+    let (a, b) = (1, 2);
+    let Point { x, y: height } = p;
+    let ((c, _), Point { x: d, .. }) = ((3, 4), Point { x: 5, y: 6 });
+    a + b + x + height + c + d
+}
+"#;
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    // The tuple is evaluated once, then taken apart
+    assert!(
+        mojo_code.contains("    var __a_b = (1, 2)\n    var a = __a_b[0]\n    var b = __a_b[1]\n")
+    );
+    assert!(mojo_code.contains("    var x = p.x\n    var height = p.y\n"));
+    assert!(mojo_code.contains("    var c = __c_d[0][0]\n    var d = __c_d[1].x\n"));
+}
+
+#[test]
+fn test_let_destructuring_does_not_redeclare_names() {
+    let rust_code = r#"
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn pick(p: Point, q: Point) -> i32 {
+    let y = 1;
+    let Point { x: px, y } = p;
+    let (a, b) = (px, y);
+    let (a, b) = (b, a);
+    a + b + y
+}
+"#;
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    // The shadowing `y` is renamed, and later uses follow it
+    assert!(mojo_code.contains("    let y = 1\n    var px = p.x\n    var y_1 = p.y\n"));
+    assert!(mojo_code
+        .contains("    var __a_b = (px, y_1)\n    var a = __a_b[0]\n    var b = __a_b[1]\n"));
+    // Destructuring the same names again needs a temporary of its own
+    assert!(mojo_code.contains(
+        "    var __a_b_1 = (b, a)\n    var a_1 = __a_b_1[0]\n    var b_1 = __a_b_1[1]\n"
+    ));
+    assert!(mojo_code.contains("    return a_1 + b_1 + y_1\n"));
+}
+
+#[test]
+fn test_match_guards_become_arm_conditions() {
+    let rust_code = r#"
+fn sign(n: i32) -> i32 {
+    match n {
+        x if x > 0 => 1,
+        _ => -1,
+    }
+}
+
+fn bucket(value: Option<i32>) -> i32 {
+    match value {
+        Some(v) if v < 0 || v > 100 => 0,
+        Some(v) => v,
+        None => -1,
+    }
+}
+"#;
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    // The guard is checked by the arm's `if`, and a failed guard falls through to `else`
+    assert!(mojo_code.contains(
+        "    if n > 0:\n        var x = n\n        return 1\n    else:\n        return -1\n"
+    ));
+    // A guard binding looser than `and` keeps its grouping
+    assert!(mojo_code.contains("if value and (value.value() < 0 or value.value() > 100):"));
+}
+
+#[test]
+fn test_coverage_reports_placeholders_and_skipped_items() {
+    let compiler = Compiler::new();
+
+    let report = compiler
+        .coverage("fn add(a: i32, b: i32) -> i32 { a + b }")
+        .unwrap();
+    assert_eq!(report.untranslated_nodes, 0);
+    assert_eq!(report.translated_fraction(), 1.0);
+
+    let rust_code = r#"
+        fn add(a: i32, b: i32) -> i32 {
+            a + b
+        }
+
+        fn make() {
+            let double = |x: i32| x * 2;
+            let triple = |x: i32| x * 3;
+        }
+
+        union Bits {
+            i: u32,
+            f: f32,
+        }
+    "#;
+    let report = compiler.coverage(rust_code).unwrap();
+    assert!(report.translated_fraction() < 1.0);
+    assert!(report.translated_fraction() > 0.0);
+    assert_eq!(report.untranslated_nodes, 3);
+    assert_eq!(report.unsupported, ["union item", "closure"]);
+
+    // Strict mode would reject the closure, but coverage still measures the file
+    let strict = Compiler::with_options(CompilerOptions::builder().strict(true).build());
+    assert_eq!(strict.coverage(rust_code).unwrap(), report);
+}
+
+#[test]
+fn test_coverage_counts_statement_macros() {
+    let rust_code = r#"
+        fn check(a: i32, b: i32) -> i32 {
+            assert_eq!(a, b);
+            debug_assert!(a > 0);
+            a + b
+        }
+    "#;
+
+    let report = Compiler::new().coverage(rust_code).unwrap();
+    assert_eq!(report.untranslated_nodes, 2);
+    assert_eq!(
+        report.unsupported,
+        ["macro `assert_eq!`", "macro `debug_assert!`"]
+    );
+    assert!(report.translated_fraction() < 1.0);
+}
+
+#[test]
+fn test_block_expressions_yield_their_value() {
+    let rust_code = r#"
+fn step(flag: bool, n: i32) -> i32 {
+    let x = {
+        let t = 1;
+        t + 1
+    };
+    let y = { 5 };
+    let z = 2 * if flag { 1 } else { 2 };
+    x + y + z + n
+}
+
+fn halve(n: i32) -> i32 {
+    {
+        let half = n / 2;
+        half * 2
+    }
+}
+"#;
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    // The block's statements run first and its final expression becomes the value
+    assert!(mojo_code.contains("    let t = 1\n    let x = t + 1\n"));
+    assert!(mojo_code.contains("    let y = 5\n"));
+    assert!(mojo_code.contains("    let z = 2 * (1 if flag else 2)\n"));
+    // A block in tail position returns its own final expression
+    assert!(mojo_code.contains("    let half = n / 2\n    return half * 2\n"));
+    assert!(!mojo_code.contains("unsupported_block"));
+}
+
+#[test]
+fn test_block_bindings_do_not_clobber_outer_variables() {
+    let rust_code = r#"
+fn outer() -> i32 {
+    let t = 5;
+    let x = {
+        let t = 1;
+        t + 1
+    };
+    let y = {
+        let x = t * 2;
+        if x > 4 { x } else { 0 }
+    };
+    t + x + y
+}
+"#;
+    let (mojo_code, warnings) = Compiler::new()
+        .compile_str_with_warnings(rust_code)
+        .unwrap();
+    assert!(mojo_code.contains("    let t = 5\n    let t_1 = 1\n    let x = t_1 + 1\n"));
+    // A late-initialized var is declared with the type its branches yield
+    assert!(mojo_code.contains(
+        "    var y: Int32\n    let x_1 = t * 2\n    if x_1 > 4:\n        y = x_1\n    else:\n        y = 0\n"
+    ));
+    assert!(mojo_code.contains("    return t + x + y\n"));
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_trailing_expression_is_an_implicit_return() {
+    let rust_code = r#"
+fn add(a: i32, b: i32) -> i32 { a + b }
+fn note(a: i32) {}
+fn log(a: i32) { note(a) }
+"#;
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("fn add(a: Int32, b: Int32) -> Int32:\n    return a + b\n"));
+    // A unit function's trailing expression is only evaluated
+    assert!(mojo_code.contains("fn log(a: Int32):\n    note(a)\n"));
+    assert!(!mojo_code.contains("return note(a)"));
+}
+
+#[test]
+fn test_generate_from_ast_json() {
+    let compiler = Compiler::new();
+    let json = compiler.ast_json("fn answer() -> i32 { 42 }").unwrap();
+    assert_eq!(
+        compiler.generate_from_ast_json(&json).unwrap(),
+        compiler.compile_str("fn answer() -> i32 { 42 }").unwrap()
+    );
+
+    let truncated = &json[..json.len() / 2];
+    assert!(matches!(
+        compiler.generate_from_ast_json(truncated),
+        Err(Error::SerializationError(_))
+    ));
+    assert!(matches!(
+        compiler.generate_from_ast_json(r#"{"items": "not a list"}"#),
+        Err(Error::SerializationError(_))
+    ));
+}
+
+#[test]
+fn test_primitive_type_mapping() {
+    let table = [
+        ("i8", "Int8"),
+        ("i16", "Int16"),
+        ("i32", "Int32"),
+        ("i64", "Int64"),
+        ("i128", "Int128"),
+        ("isize", "Int"),
+        ("u8", "UInt8"),
+        ("u16", "UInt16"),
+        ("u32", "UInt32"),
+        ("u64", "UInt64"),
+        ("u128", "UInt128"),
+        ("usize", "UInt"),
+        ("f32", "Float32"),
+        ("f64", "Float64"),
+        ("bool", "Bool"),
+        ("char", "String"),
+        ("String", "String"),
+        ("Point", "Point"),
+    ];
+
+    let compiler = Compiler::new();
+    for (rust_type, mojo_type) in table {
+        let mojo_code = compiler
+            .compile_str(&format!("fn f(x: {}) {{}}", rust_type))
+            .unwrap();
+        assert!(
+            mojo_code.contains(&format!("x: {}):", mojo_type)),
+            "{} should map to {}",
+            rust_type,
+            mojo_type
+        );
+    }
+}
+
+#[test]
+fn test_vec_maps_to_list() {
+    let mojo_code = Compiler::new()
+        .compile_str("fn total(values: Vec<i64>) {}")
+        .unwrap();
+    assert!(mojo_code.contains("fn total(owned values: List[Int64]):"));
+}
+
+#[test]
+fn test_option_maps_to_optional() {
+    let mojo_code = Compiler::new()
+        .compile_str("fn flag(value: Option<bool>) {}")
+        .unwrap();
+    assert!(mojo_code.contains("fn flag(owned value: Optional[Bool]):"));
+}
+
+#[test]
+fn test_hashmap_maps_to_dict() {
+    let mojo_code = Compiler::new()
+        .compile_str("fn counts(map: std::collections::HashMap<u32, f64>) {}")
+        .unwrap();
+    assert!(mojo_code.contains("fn counts(owned map: Dict[UInt32, Float64]):"));
+    assert!(mojo_code.contains("from collections import Dict\n"));
+}
+
+#[test]
+fn test_hashmap_iteration_goes_through_dict_items() {
+    let rust_code = r#"
+        use std::collections::HashMap;
+
+        fn total(map: &HashMap<String, i32>) -> i32 {
+            let mut sum = 0;
+            for (_, v) in map.iter() {
+                sum += v;
+            }
+            let entries = map.iter();
+            sum
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains(
+        "    for item in map.items():\n        var v = item[].value\n        sum += v\n"
+    ));
+    assert!(mojo_code.contains("    let entries = map.items()\n"));
+    assert!(!mojo_code.contains(".items().items()"));
+}
+
+#[test]
+fn test_hashmap_methods_map_to_dict() {
+    let rust_code = r#"
+        use std::collections::HashMap;
+
+        fn tally(counts: &mut HashMap<String, i32>, key: String) -> bool {
+            counts.insert(key.clone(), 1);
+            let current = counts.get(&key);
+            let removed = counts.remove(&key);
+            !counts.contains_key(&key)
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+
+    assert!(mojo_code.contains("    counts[key] = 1\n"));
+    // `Dict.get` already returns an `Optional`
+    assert!(mojo_code.contains("let current = counts.get(key)\n"));
+    assert!(
+        mojo_code.contains("let removed = (Optional(counts.pop(key)) if key in counts else None)")
+    );
+    assert!(mojo_code.contains("return not key in counts\n"));
+}
+
+#[test]
+fn test_generic_arguments_are_preserved() {
+    let unit = parse_rust_code(
+        "fn f(a: Vec<i32>, b: Vec<Vec<i32>>, c: Result<String, std::io::Error>) {}",
+    )
+    .unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+
+    let vec_i32 = Type::Generic {
+        name: "Vec".to_string(),
+        args: vec![Type::Path("i32".to_string())],
+    };
+    assert_eq!(func.parameters[0].type_, vec_i32);
+    assert_eq!(
+        func.parameters[1].type_,
+        Type::Generic {
+            name: "Vec".to_string(),
+            args: vec![vec_i32],
+        }
+    );
+    assert_eq!(
+        func.parameters[2].type_,
+        Type::Generic {
+            name: "Result".to_string(),
+            args: vec![
+                Type::Path("String".to_string()),
+                Type::Path("std::io::Error".to_string()),
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_doc_comments_become_docstrings() {
+    let rust_code = r#"
+        /// Add two numbers.
+        ///
+        /// Overflow wraps in release builds.
+        fn add(a: i32, b: i32) -> i32 {
+            a + b
+        }
+
+        /// A point in the plane.
+        struct Point {
+            x: i32,
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains(
+        "fn add(a: Int32, b: Int32) -> Int32:\n    \"\"\"\n    Add two numbers.\n\n    Overflow wraps in release builds.\n    \"\"\"\n    return a + b\n"
+    ));
+    assert!(mojo_code
+        .contains("struct Point:\n    \"\"\"A point in the plane.\"\"\"\n    var x: Int32\n"));
+}
+
+#[test]
+fn test_parameter_ownership_conventions() {
+    let compiler = Compiler::new();
+    let cases = [
+        ("fn f(x: &i32) {}", "fn f(borrowed x: Int32):"),
+        ("fn f(x: &mut i32) {}", "fn f(inout x: Int32):"),
+        ("fn f(x: String) {}", "fn f(owned x: String):"),
+        ("fn f(x: i32) {}", "fn f(x: Int32):"),
+    ];
+    for (rust_code, signature) in cases {
+        let mojo_code = compiler.compile_str(rust_code).unwrap();
+        assert!(
+            mojo_code.contains(signature),
+            "expected `{}` for `{}`",
+            signature,
+            rust_code
+        );
+    }
+}
+
+#[test]
+fn test_derives_become_trait_conformances() {
+    let rust_code = r#"
+        #[derive(Copy, Clone)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        struct Label {
+            text: String,
+        }
+    "#;
+
+    let (mojo_code, warnings) = Compiler::new()
+        .compile_str_with_warnings(rust_code)
+        .unwrap();
+    // The methods the conformances require are generated along with them
+    assert!(mojo_code.contains("@value\nstruct Point(Copyable):\n"));
+    assert!(mojo_code.contains("@value\nstruct Label(Stringable, Representable, Copyable):\n"));
+    assert!(mojo_code.contains(
+        "    fn __repr__(self) -> String:\n        return \"Label { text: \" + repr(self.text) + \" }\"\n"
+    ));
+    assert!(mojo_code.contains("    fn __str__(self) -> String:\n        return self.__repr__()\n"));
+    assert_eq!(mojo_code.matches("fn __repr__").count(), 1);
+
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].message.contains("`PartialEq`"));
+
+    // A `Display` impl keeps its own `__str__`
+    let mojo_code = Compiler::new()
+        .compile_str(
+            r#"
+            #[derive(Debug)]
+            struct Name { text: String }
+
+            impl std::fmt::Display for Name {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    write!(f, "{}", self.text)
+                }
+            }
+            "#,
+        )
+        .unwrap();
+    assert!(mojo_code.contains("struct Name(Stringable, Representable):"));
+    assert!(mojo_code.contains("fn __repr__(self) -> String:"));
+    assert_eq!(mojo_code.matches("fn __str__").count(), 1);
+    assert!(!mojo_code.contains("return self.__repr__()"));
+}
+
+#[test]
+fn test_c_like_enum_codegen() {
+    let mojo_code = Compiler::new()
+        .compile_str("enum Color { Red, Green, Blue }")
+        .unwrap();
+
+    assert!(mojo_code.contains("@value\nstruct Color(EqualityComparable):\n    var _tag: Int\n"));
+    assert!(mojo_code.contains(
+        "    alias Red = Self(0)\n    alias Green = Self(1)\n    alias Blue = Self(2)\n"
+    ));
+    assert!(!mojo_code.contains("Variant"));
+}
+
+#[test]
+fn test_data_enum_codegen() {
+    let rust_code = r#"
+        enum Shape {
+            Circle(f64),
+            Rect { w: f64, h: f64 },
+            Empty,
+        }
+
+        impl Shape {
+            fn area(&self) -> f64 {
+                match self {
+                    Shape::Circle(r) => r * r,
+                    Shape::Rect { w, h } => w * h,
+                    Shape::Empty => 0.0,
+                }
+            }
+        }
+
+        fn unit() -> Shape {
+            Shape::Rect { w: 1.0, h: 1.0 }
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("from utils import Variant\n"));
+
+    // Each variant's payload gets its own struct
+    assert!(mojo_code.contains("@value\nstruct Shape_Circle:\n    var field_0: Float64\n"));
+    assert!(
+        mojo_code.contains("@value\nstruct Shape_Rect:\n    var w: Float64\n    var h: Float64\n")
+    );
+    assert!(mojo_code.contains("@value\nstruct Shape_Empty:\n    pass\n"));
+
+    assert!(mojo_code.contains(
+        "@value\nstruct Shape:\n    var _value: Variant[Shape_Circle, Shape_Rect, Shape_Empty]\n"
+    ));
+    assert!(mojo_code.contains(
+        "    @staticmethod\n    fn Circle(field_0: Float64) -> Self:\n        return Self(Shape_Circle(field_0))\n"
+    ));
+    assert!(mojo_code.contains("    fn Rect(w: Float64, h: Float64) -> Self:\n"));
+    assert!(mojo_code.contains("    alias Empty = Self(Shape_Empty())\n"));
+
+    // Matching checks the active payload and binds from it
+    assert!(mojo_code.contains(
+        "        if self.isa[Shape_Circle]():\n            var r = self[Shape_Circle].field_0\n"
+    ));
+    assert!(mojo_code.contains(
+        "        elif self.isa[Shape_Rect]():\n            var w = self[Shape_Rect].w\n            var h = self[Shape_Rect].h\n"
+    ));
+    assert!(mojo_code.contains("    return Shape.Rect(w=1, h=1)\n"));
+    assert!(!mojo_code.contains("TODO"));
+}
+
+#[test]
+fn test_nested_control_flow_bodies() {
+    let rust_code = r#"
+        fn classify(values: Vec<i32>) {
+            for x in values {
+                if x > 0 {
+                    print(x);
+                } else if x < 0 {
+                    while x < 0 {}
+                } else {
+                }
+            }
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains(concat!(
+        "    for x in values:\n",
+        "        if x > 0:\n",
+        "            print(x)\n",
+        "        elif x < 0:\n",
+        "            while x < 0:\n",
+        "                pass\n",
+        "        else:\n",
+        "            pass\n",
+    )));
+}
+
+#[test]
+fn test_nested_blocks_are_indented() {
+    let rust_code = r#"
+        fn count(values: Vec<i32>) -> i32 {
+            let mut n = 0;
+            for x in values {
+                if x > 0 {
+                    n = n + 1;
+                }
+            }
+            n
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    let body: Vec<&str> = mojo_code
+        .lines()
+        .skip_while(|line| !line.starts_with("fn count"))
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .collect();
+    assert_eq!(
+        body,
+        [
+            "    var n = 0",
+            "    for x in values:",
+            "        if x > 0:",
+            "            n = n + 1",
+            "    return n",
+        ]
+    );
+}
+
+#[test]
+fn test_try_operator() {
+    let rust_code = r#"
+        fn f() -> Result<i32, E> {
+            let x = g()?;
+            h()?;
+            Ok(x + 1)
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    let Statement::Let {
+        value: Some(Expression::Try(inner)),
+        ..
+    } = &func.body[0]
+    else {
+        panic!("expected a try expression, got {:?}", func.body);
+    };
+    assert!(matches!(**inner, Expression::Call { ref args, .. } if args.is_empty()));
+
+    // An `Err` is returned early instead of being unwrapped
+    let (mojo_code, warnings) = Compiler::new()
+        .compile_str_with_warnings(rust_code)
+        .unwrap();
+    assert!(mojo_code.contains(
+        "fn f() -> Result[Int32, E]:\n    var _try = g()\n    if _try.is_err():\n        return _try\n    let x = _try.unwrap()\n"
+    ));
+    assert!(mojo_code.contains(
+        "    var _try_1 = h()\n    if _try_1.is_err():\n        return _try_1\n    return Ok(x + 1)\n"
+    ));
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_each_try_operator_gets_its_own_temporary() {
+    let rust_code = r#"
+        fn f() -> Result<(), E> {
+            g()?;
+            h()?;
+            Ok(())
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains(
+        "    var _try = g()\n    if _try.is_err():\n        return _try\n    var _try_1 = h()\n    if _try_1.is_err():\n        return _try_1\n"
+    ));
+}
+
+#[test]
+fn test_nested_try_operators_return_early() {
+    let rust_code = r#"
+        fn tail() -> Result<i32, E> {
+            g()?
+        }
+
+        fn nested(a: i32) -> Result<i32, E> {
+            let x = g()? + 1;
+            foo(h()?);
+            Ok(a + k(x)?)
+        }
+    "#;
+
+    let (mojo_code, warnings) = Compiler::new()
+        .compile_str_with_warnings(rust_code)
+        .unwrap();
+    assert!(mojo_code.contains(
+        "fn tail() -> Result[Int32, E]:\n    var _try = g()\n    if _try.is_err():\n        return _try\n    return _try.unwrap()\n"
+    ));
+    assert!(mojo_code.contains("    let x = _try.unwrap() + 1\n"));
+    assert!(mojo_code.contains(
+        "    var _try_1 = h()\n    if _try_1.is_err():\n        return _try_1\n    foo(_try_1.unwrap())\n"
+    ));
+    assert!(mojo_code.contains(
+        "    var _try_2 = k(x)\n    if _try_2.is_err():\n        return _try_2\n    return Ok(a + _try_2.unwrap())\n"
+    ));
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_try_operator_on_option_returns_early() {
+    let rust_code = r#"
+        fn first(v: Option<i32>) -> Option<i32> {
+            let x = v?;
+            Some(x)
+        }
+
+        fn total() -> i32 {
+            g()?
+        }
+
+        fn log() {
+            let a = g()?;
+        }
+    "#;
+
+    let (mojo_code, warnings) = Compiler::new()
+        .compile_str_with_warnings(rust_code)
+        .unwrap();
+    assert!(mojo_code.contains(
+        "    var _try = v\n    if not _try:\n        return None\n    let x = _try.value()\n"
+    ));
+    // Without an early exit the payload is still unwrapped, with a warning
+    assert!(mojo_code.contains("    return g().unwrap()\n"));
+    assert!(mojo_code.contains("    let a = g().unwrap()\n"));
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings
+        .iter()
+        .all(|warning| warning.message.contains("neither Result nor Option")));
+}
+
+#[test]
+fn test_compound_assignment_operators() {
+    let rust_code = r#"
+        fn update(mut x: i32) {
+            x += 1;
+            x <<= 2;
+            x -= 3;
+            x %= 4;
+            x ^= 5;
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    let ops: Vec<&BinaryOp> = func
+        .body
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::Expression(Expression::Binary { op, .. }) => Some(op),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        ops,
+        [
+            &BinaryOp::AddAssign,
+            &BinaryOp::ShlAssign,
+            &BinaryOp::SubAssign,
+            &BinaryOp::ModAssign,
+            &BinaryOp::BitXorAssign,
+        ]
+    );
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("    x += 1\n    x <<= 2\n    x -= 3\n    x %= 4\n    x ^= 5\n"));
+}
+
+#[test]
+fn test_string_types() {
+    let rust_code = r#"
+        fn greet(name: &str, text: String, shared: &String) -> String {
+            text
+        }
+
+        fn label() -> &'static str {
+            "point"
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains(
+        "fn greet(name: StringRef, owned text: String, borrowed shared: String) -> String:"
+    ));
+    assert!(mojo_code.contains("fn label() -> StringRef:\n    return \"point\"\n"));
+}
+
+#[test]
+fn test_string_literals_are_escaped() {
+    let mojo_code = Compiler::new()
+        .compile_str(r#"fn f() { let s = "a\"b\nc"; }"#)
+        .unwrap();
+    assert!(mojo_code.contains(r#"    let s = "a\"b\nc""#));
+
+    let text = "quote \" backslash \\ tab \t bell \u{7} caf\u{e9}";
+    let rust_code = format!("fn f() {{ let s = {:?}; }}", text);
+    let mojo_code = Compiler::new().compile_str(&rust_code).unwrap();
+    let line = mojo_code
+        .lines()
+        .find(|line| line.contains("let s = "))
+        .unwrap();
+    assert!(line.contains(r#"tab \t bell \x07 café""#));
+
+    // The emitted literal decodes back to the original text
+    let literal = line.trim().trim_start_matches("let s = ");
+    let decoded: syn::LitStr = syn::parse_str(literal).unwrap();
+    assert_eq!(decoded.value(), text);
+}
+
+#[test]
+fn test_char_literals() {
+    let mojo_code = Compiler::new()
+        .compile_str("fn f() -> char { 'x' }")
+        .unwrap();
+    assert!(mojo_code.contains("fn f() -> String:\n    return \"x\"\n"));
+
+    let rust_code = r#"
+        fn g() {
+            let newline = '\n';
+            let quote = '\'';
+            let double = '"';
+            let slash = '\\';
+        }
+    "#;
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains(r#"let newline = "\n""#));
+    assert!(mojo_code.contains(r#"let quote = "'""#));
+    assert!(mojo_code.contains(r#"let double = "\"""#));
+    assert!(mojo_code.contains(r#"let slash = "\\""#));
+}
+
+#[test]
+fn test_integer_literal_suffixes_and_wide_values() {
+    let rust_code = r#"
+        fn f() {
+            let small = 255u8;
+            let max = 0xFFFF_FFFF_FFFF_FFFFu64;
+            let big = 170141183460469231731687303715884105727;
+            let plain = 42;
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Function(func) = &unit.items[0] else {
+        panic!("expected a function");
+    };
+    let Statement::Let {
+        value: Some(Expression::Literal(max)),
+        ..
+    } = &func.body[1]
+    else {
+        panic!("expected a literal binding");
+    };
+    assert_eq!(
+        max,
+        &Literal::TypedInteger {
+            digits: u64::MAX.to_string(),
+            suffix: Some("u64".to_string()),
+        }
+    );
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("let small = UInt8(255)\n"));
+    assert!(mojo_code.contains("let max = UInt64(18446744073709551615)\n"));
+    assert!(mojo_code.contains("let big = 170141183460469231731687303715884105727\n"));
+    assert!(mojo_code.contains("let plain = 42\n"));
+}
+
+#[test]
+fn test_inherent_and_trait_impl_methods() {
+    let rust_code = r#"
+        use std::fmt;
+
+        trait Area {
+            fn area(&self) -> f64;
+        }
+
+        struct Point {
+            x: f64,
+            y: f64,
+        }
+
+        impl Point {
+            fn new(x: f64, y: f64) -> Self {
+                Point { x, y }
+            }
+        }
+
+        impl Area for Point {
+            fn area(&self) -> f64 {
+                0.0
+            }
+        }
+
+        impl fmt::Display for Point {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "({}, {})", self.x, self.y)
+            }
+        }
+
+        impl Clone for Point {
+            const ORIGIN: i32 = 0;
+
+            fn clone(&self) -> Self {
+                Point { x: self.x, y: self.y }
+            }
+        }
+    "#;
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    // Only traits with a Mojo counterpart, or defined in this unit, are conformed to
+    assert!(mojo_code.contains("struct Point(Area, Stringable):"));
+    assert!(mojo_code.contains("    @staticmethod\n    fn new(x: Float64, y: Float64) -> Self:"));
+    assert!(mojo_code.contains("    fn area(self) -> Float64:\n        return 0"));
+    assert!(mojo_code.contains("    fn __str__(self) -> String:"));
+    assert!(mojo_code.contains("    fn clone(self) -> Self:"));
+    assert!(!mojo_code.contains("placeholder"));
+}
+
+#[test]
+fn test_associated_consts_and_types_in_impls() {
+    let rust_code = r#"
+        struct Circle {
+            r: f64,
+        }
+
+        impl Circle {
+            const PI: f64 = 3.5;
+            type Output = i32;
+
+            fn area(&self) -> f64 {
+                Self::PI * self.r * self.r
+            }
+        }
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let Item::Impl(impl_item) = &unit.items[1] else {
+        panic!("expected an impl");
+    };
+    assert!(matches!(&impl_item.items[0], ImplItem::Const(c) if c.name == "PI"));
+    assert!(matches!(&impl_item.items[1], ImplItem::Type(t) if t.name == "Output"));
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("    alias PI = 3.5\n    alias Output = Int32\n"));
+    assert!(mojo_code.contains("        return Self.PI * self.r * self.r\n"));
+    assert!(!mojo_code.contains("placeholder"));
+}
+
+#[test]
+fn test_restricted_visibility() {
+    let rust_code = r#"
+        pub fn public() {}
+        fn private() {}
+        pub(crate) fn krate() {}
+        pub(super) fn parent() {}
+        pub(in crate::shapes) fn scoped() {}
+        pub(self) fn own() {}
+    "#;
+
+    let unit = parse_rust_code(rust_code).unwrap();
+    let visibilities: Vec<&Visibility> = unit
+        .items
+        .iter()
+        .map(|item| match item {
+            Item::Function(func) => &func.visibility,
+            _ => panic!("expected a function"),
+        })
+        .collect();
+    assert_eq!(
+        visibilities,
+        [
+            &Visibility::Public,
+            &Visibility::Private,
+            &Visibility::Crate,
+            &Visibility::Super,
+            &Visibility::InPath("crate::shapes".to_string()),
+            &Visibility::Private,
+        ]
+    );
+
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    assert!(mojo_code.contains("\nfn public():"));
+    assert!(mojo_code.contains("\nfn private():"));
+    assert!(mojo_code.contains("# pub(crate)\nfn krate():"));
+    assert!(mojo_code.contains("# pub(super)\nfn parent():"));
+    assert!(mojo_code.contains("# pub(in crate::shapes)\nfn scoped():"));
+}
+
+#[test]
+fn test_parse_then_generate_matches_compile_str() {
+    let rust_code = r#"
+        type Id = u32;
+
+        struct Point { x: i32, y: i32 }
+
+        fn swap(a: Id, b: Id) -> (Id, Id) {
+            (b, a)
+        }
+    "#;
+
+    for config in [
+        CompilerConfig::default(),
+        CompilerConfig {
+            inline_single_use_aliases: true,
+            signatures_only: true,
+            ..CompilerConfig::default()
+        },
+    ] {
+        let compiler = Compiler::with_config(config);
+        let unit = compiler.parse(rust_code).unwrap();
+        assert_eq!(
+            compiler.generate(&unit).unwrap(),
+            compiler.compile_str(rust_code).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_compile_items_streams_each_item() {
+    let rust_code = r#"
+        fn one() -> i32 { 1 }
+        fn two() -> i32 { 2 }
+        fn three() -> i32 { 3 }
+    "#;
+
+    let compiler = Compiler::new();
+    let chunks: Vec<String> = compiler
+        .compile_items(rust_code)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(chunks.len(), 3);
+    assert!(chunks[0].starts_with("fn one() -> Int32:"));
+    assert!(chunks[1].starts_with("fn two() -> Int32:"));
+    assert!(chunks[2].starts_with("fn three() -> Int32:"));
+
+    // Together the chunks are the body of the whole-file output
+    let mojo_code = compiler.compile_str(rust_code).unwrap();
+    assert!(mojo_code.ends_with(&chunks.concat()));
+}
+
+#[test]
+fn test_compile_items_attaches_impls_and_continues_past_errors() {
+    let rust_code = r#"
+        fn before() {}
+        impl Point {
+            fn norm(&self) -> i32 { 0 }
+        }
+        mod external;
+        struct Point { x: i32 }
+    "#;
+
+    let chunks: Vec<_> = Compiler::new().compile_items(rust_code).collect();
+    assert_eq!(chunks.len(), 3);
+    assert!(chunks[0].as_ref().unwrap().contains("fn before():"));
+    assert!(matches!(chunks[1], Err(Error::UnsupportedFeature { .. })));
+    let point = chunks[2].as_ref().unwrap();
+    assert!(point.contains("struct Point:"));
+    assert!(point.contains("    fn norm(self) -> Int32:"));
+
+    // Strict mode stops at the first error
+    let strict = Compiler::with_options(CompilerOptions::builder().strict(true).build());
+    let chunks: Vec<_> = strict.compile_items(rust_code).collect();
+    assert_eq!(chunks.len(), 2);
+    assert!(chunks[1].is_err());
+
+    let chunks: Vec<_> = Compiler::new().compile_items("fn broken(").collect();
+    assert!(matches!(chunks[..], [Err(Error::ParseError { .. })]));
+}
+
+#[test]
+fn test_imports_follow_usage() {
+    let mojo_code = Compiler::new()
+        .compile_str("fn total(values: Vec<i64>) -> i64 { 0 }")
+        .unwrap();
+    assert!(mojo_code.contains("from collections import List\n"));
+    assert!(!mojo_code.contains("UnsafePointer"));
+    assert!(!mojo_code.contains("Optional"));
+
+    let rust_code = r#"
+        use std::collections::HashMap;
+
+        fn index(names: Vec<String>, ids: HashMap<String, u32>) -> Option<u32> {
+            let first: Option<u32> = None;
+            first
+        }
+
+        fn grid() -> [i32; 4] {
+            [0; 4]
+        }
+
+        fn total(values: &[i32]) -> i32 {
+            0
+        }
+    "#;
+    let mojo_code = Compiler::new().compile_str(rust_code).unwrap();
+    let imports: Vec<&str> = mojo_code
+        .lines()
+        .filter(|line| line.starts_with("from "))
+        .collect();
+    // One line per module, each symbol once, all before the first item
+    assert_eq!(
+        imports,
+        [
+            "from collections import Dict, InlineArray, List, Optional",
+            "from utils import Span",
+        ]
+    );
+    assert!(mojo_code.find("from utils").unwrap() < mojo_code.find("fn index").unwrap());
+}